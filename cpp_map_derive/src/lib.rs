@@ -0,0 +1,103 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Derive macros for the cpp_map crate. Use them through the `derive` feature of cpp_map.
+//!
+//! `#[derive(SortByKey)]` implements `Ord`, `PartialOrd`, `Eq` and `PartialEq` using only the
+//! fields marked with `#[sort_key]`. The rest of the struct is payload that is ignored by the
+//! comparison, i.e. two items with the same sort key are considered duplicates by the map.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Index};
+
+/// Implements `Ord`, `PartialOrd`, `Eq` and `PartialEq` based on the field(s) marked
+/// with `#[sort_key]`. If several fields are marked they are compared in declaration order.
+///
+/// ```ignore
+/// #[derive(Debug, SortByKey)]
+/// struct Event {
+///     #[sort_key]
+///     key: i32,
+///     payload: String,
+/// }
+/// ```
+#[proc_macro_derive(SortByKey, attributes(sort_key))]
+pub fn derive_sort_by_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match sort_by_key(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn sort_by_key(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "SortByKey can only be derived for structs",
+            ))
+        }
+    };
+
+    let keys: Vec<TokenStream2> = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .filter(|f| f.attrs.iter().any(|a| a.path().is_ident("sort_key")))
+            .map(|f| {
+                let ident = f.ident.as_ref().unwrap();
+                quote!(#ident)
+            })
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.attrs.iter().any(|a| a.path().is_ident("sort_key")))
+            .map(|(i, _)| {
+                let index = Index::from(i);
+                quote!(#index)
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+    if keys.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "SortByKey requires at least one field marked with #[sort_key]",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::cmp::Ord for #name #ty_generics #where_clause {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                ::core::cmp::Ordering::Equal
+                    #(.then_with(|| ::core::cmp::Ord::cmp(&self.#keys, &other.#keys)))*
+            }
+        }
+
+        impl #impl_generics ::core::cmp::PartialOrd for #name #ty_generics #where_clause {
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl #impl_generics ::core::cmp::PartialEq for #name #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                ::core::cmp::Ord::cmp(self, other) == ::core::cmp::Ordering::Equal
+            }
+        }
+
+        impl #impl_generics ::core::cmp::Eq for #name #ty_generics #where_clause {}
+    })
+}
@@ -0,0 +1,412 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Range aggregation over a [`LinkedList`], using per block aggregates of a user supplied
+//! [`Monoid`].
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
+
+/// An associative operation with an identity element, e.g. sum, max or count.
+/// `combine()` must be associative, but it does not have to be commutative: the items are
+/// always combined in sorted order.
+pub trait Monoid<K, V> {
+    /// The aggregated value
+    type Output: Clone + Debug;
+    /// The identity element, the aggregate of an empty range
+    fn identity(&self) -> Self::Output;
+    /// The aggregate of a single item
+    fn lift(&self, key: &K, value: &V) -> Self::Output;
+    /// Combines two aggregates, `a` goes before `b`
+    fn combine(&self, a: &Self::Output, b: &Self::Output) -> Self::Output;
+}
+
+/// A [`Monoid`] counting the items
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Count;
+
+impl<K, V> Monoid<K, V> for Count {
+    type Output = usize;
+
+    fn identity(&self) -> usize {
+        0
+    }
+
+    fn lift(&self, _key: &K, _value: &V) -> usize {
+        1
+    }
+
+    fn combine(&self, a: &usize, b: &usize) -> usize {
+        a + b
+    }
+}
+
+/// A run of consecutive nodes and their aggregate
+#[derive(Clone, Debug)]
+struct Block<T> {
+    first_: usize,
+    last_: usize,
+    len_: usize,
+    agg_: T,
+}
+
+/// A [`LinkedList`] that keeps the list divided into blocks of about sqrt(n) consecutive
+/// nodes, each with the aggregate of its items. [`Aggregated::aggregate_range()`] combines the
+/// block aggregates of the blocks inside the range and only visits the items of the (at most
+/// two) partially covered blocks, so a range query visits about O(sqrt(n)) items.
+///
+/// Every mutation recomputes the aggregate of the affected block, so the list has to be
+/// modified through the wrapper. The keys must be in a strict order, the blocks are binary
+/// searched.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{Aggregated, LinkedList, Monoid};
+/// struct Sum;
+/// impl Monoid<i32, i64> for Sum {
+///     type Output = i64;
+///     fn identity(&self) -> i64 { 0 }
+///     fn lift(&self, _: &i32, value: &i64) -> i64 { *value }
+///     fn combine(&self, a: &i64, b: &i64) -> i64 { a + b }
+/// }
+/// let mut a = Aggregated::new(LinkedList::<i32, i64>::default(), Sum);
+/// for i in 0..100 {
+///     let _ = a.ordered_insert_pos(i, i as i64, a.list().tail());
+/// }
+/// assert_eq!(a.aggregate_range(10..20), (10..20).sum());
+/// let _ = a.pop_back();
+/// assert_eq!(a.aggregate_range(90..), (90..99).sum());
+/// assert_eq!(a.aggregate_all(), (0..99).sum());
+/// ```
+#[derive(Debug)]
+pub struct Aggregated<K, V, A>
+where
+    K: Debug,
+    V: Debug,
+    A: Monoid<K, V>,
+{
+    list_: LinkedList<K, V>,
+    monoid_: A,
+    blocks_: Vec<Block<A::Output>>,
+}
+
+impl<K, V, A> Aggregated<K, V, A>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    A: Monoid<K, V>,
+{
+    /// Wraps a list, the blocks are built from the current content of the list.
+    pub fn new(list: LinkedList<K, V>, monoid: A) -> Self {
+        let mut rv = Self {
+            list_: list,
+            monoid_: monoid,
+            blocks_: Vec::new(),
+        };
+        let target = rv.target_len_();
+        let (mut first, mut len) = (rv.list_.head_, 0);
+        let mut curr_index = rv.list_.head_;
+        while curr_index != OUT_OF_BOUNDS {
+            let next = rv.next_of_(curr_index);
+            len += 1;
+            if len == target || next == OUT_OF_BOUNDS {
+                let block = rv.new_block_(first, curr_index, len);
+                rv.blocks_.push(block);
+                first = next;
+                len = 0;
+            }
+            curr_index = next;
+        }
+        rv
+    }
+
+    #[inline(always)]
+    /// Read only access to the list
+    pub fn list(&self) -> &LinkedList<K, V> {
+        &self.list_
+    }
+
+    #[inline(always)]
+    /// Read only access to the monoid
+    pub fn monoid(&self) -> &A {
+        &self.monoid_
+    }
+
+    #[inline(always)]
+    /// Drops the aggregates and returns the list
+    pub fn into_inner(self) -> LinkedList<K, V> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`]
+    pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        self.insert_(key, value, None)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], the aggregate of the block receiving the
+    /// new item is updated.
+    pub fn ordered_insert_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        self.insert_(key, value, Some(position))
+    }
+
+    /// Inserts with an optional position hint, the aggregate of the block receiving the new item is updated
+    fn insert_(&mut self, key: K, value: V, position: Option<usize>) -> Result<usize, MapError> {
+        let len = self.list_.len();
+        let index = match position {
+            Some(position) => self.list_.ordered_insert_pos(key, value, position)?,
+            None => self.list_.ordered_insert(key, value)?,
+        };
+        if self.list_.len() == len {
+            // the key already existed, NOP
+            return Ok(index);
+        }
+        let block = match self.block_of_(index) {
+            Some(block) => block,
+            None => {
+                let block = self.new_block_(index, index, 1);
+                self.blocks_.push(block);
+                return Ok(index);
+            }
+        };
+        let prev = self.prev_of_(index);
+        if prev == OUT_OF_BOUNDS {
+            self.blocks_[block].first_ = index;
+        }
+        if prev == self.blocks_[block].last_ {
+            self.blocks_[block].last_ = index;
+        }
+        self.update_block_(block, 1);
+        Ok(index)
+    }
+
+    /// Remove the item at index and return it, the aggregate of its block is updated.
+    pub fn remove(&mut self, index: usize) -> Result<(K, V), MapError> {
+        let block = self.block_of_(index);
+        let (prev, item, next) = self.list_.remove__(index)?;
+        let block = block.ok_or_else(|| map_error!(InternalError, "error, block not found"))?;
+        let b = &mut self.blocks_[block];
+        if b.len_ == 1 {
+            let _ = self.blocks_.remove(block);
+            return Ok(item);
+        }
+        if b.first_ == index {
+            b.first_ = next;
+        }
+        if b.last_ == index {
+            b.last_ = prev;
+        }
+        self.update_block_(block, -1);
+        Ok(item)
+    }
+
+    /// Same as [`LinkedList::pop_front()`]
+    pub fn pop_front(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let head = self.list_.head_;
+        Ok(Some(self.remove(head)?))
+    }
+
+    /// Same as [`LinkedList::pop_back()`]
+    pub fn pop_back(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let tail = self.list_.tail_;
+        Ok(Some(self.remove(tail)?))
+    }
+
+    /// Same as [`LinkedList::apply()`], the aggregate of the block of the item is updated.
+    pub fn apply<R, F>(&mut self, index: usize, f: F) -> Result<R, MapError>
+    where
+        F: FnOnce(&K, &mut V) -> R,
+    {
+        let rv = self.list_.apply(index, f)?;
+        if let Some(block) = self.block_of_(index) {
+            self.update_block_(block, 0);
+        }
+        Ok(rv)
+    }
+
+    /// Returns the aggregate of every item in the list
+    pub fn aggregate_all(&self) -> A::Output {
+        self.blocks_.iter().fold(self.monoid_.identity(), |acc, b| {
+            self.monoid_.combine(&acc, &b.agg_)
+        })
+    }
+
+    /// Returns the aggregate of the items with keys inside the range.
+    ///
+    /// Blocks that are entirely inside the range contribute their stored aggregate, only the
+    /// items of the partially covered blocks are visited.
+    pub fn aggregate_range<R>(&self, range: R) -> A::Output
+    where
+        R: RangeBounds<K>,
+    {
+        let (start, end) = (range.start_bound(), range.end_bound());
+        let first_block = match start {
+            Bound::Included(key) | Bound::Excluded(key) => self
+                .blocks_
+                .partition_point(|b| self.key_(b.first_) <= key)
+                .saturating_sub(1),
+            Bound::Unbounded => 0,
+        };
+        let mut acc = self.monoid_.identity();
+        for block in self.blocks_.iter().skip(first_block) {
+            if !before_end_(end, self.key_(block.first_)) {
+                break;
+            }
+            if after_start_(start, self.key_(block.first_))
+                && before_end_(end, self.key_(block.last_))
+            {
+                acc = self.monoid_.combine(&acc, &block.agg_);
+                continue;
+            }
+            let mut curr_index = block.first_;
+            for _ in 0..block.len_ {
+                if let Some(Some(node)) = self.list_.nodes_.get(curr_index) {
+                    if !before_end_(end, &node.key_) {
+                        return acc;
+                    }
+                    if after_start_(start, &node.key_) {
+                        let item = self.monoid_.lift(&node.key_, &node.value_);
+                        acc = self.monoid_.combine(&acc, &item);
+                    }
+                    curr_index = node.next_;
+                }
+            }
+        }
+        acc
+    }
+
+    /// The preferred number of nodes in a block
+    fn target_len_(&self) -> usize {
+        ((self.list_.len() as f64).sqrt() as usize).max(8)
+    }
+
+    /// Returns the position of the block holding the item at index
+    fn block_of_(&self, index: usize) -> Option<usize> {
+        let key = self.list_.get_k_opt(index)?;
+        if self.blocks_.is_empty() {
+            return None;
+        }
+        Some(
+            self.blocks_
+                .partition_point(|b| self.key_(b.first_) <= key)
+                .saturating_sub(1),
+        )
+    }
+
+    /// Adjust the length of the block by `delta`, recompute its aggregate and split or merge
+    /// it if its length has drifted too far from the target length.
+    fn update_block_(&mut self, block: usize, delta: isize) {
+        let target = self.target_len_();
+        let b = &mut self.blocks_[block];
+        b.len_ = b.len_.saturating_add_signed(delta);
+        if b.len_ > target * 2 {
+            // split the block in two halves
+            let (first, last, len) = (b.first_, b.last_, b.len_);
+            let mut mid = first;
+            for _ in 0..len / 2 {
+                mid = self.next_of_(mid);
+            }
+            let first_half_last = self.prev_of_(mid);
+            self.blocks_[block] = self.new_block_(first, first_half_last, len / 2);
+            let second = self.new_block_(mid, last, len - len / 2);
+            self.blocks_.insert(block + 1, second);
+            return;
+        }
+        // the last block merges with its predecessor, the others with their successor
+        let pair = if block + 1 < self.blocks_.len() {
+            block
+        } else {
+            block.saturating_sub(1)
+        };
+        if pair + 1 < self.blocks_.len()
+            && self.blocks_[pair].len_ + self.blocks_[pair + 1].len_ <= target / 2
+        {
+            let next = self.blocks_.remove(pair + 1);
+            let b = &self.blocks_[pair];
+            let merged = self.new_block_(b.first_, next.last_, b.len_ + next.len_);
+            self.blocks_[pair] = merged;
+        } else {
+            let b = &self.blocks_[block];
+            let agg = self.fold_(b.first_, b.len_);
+            self.blocks_[block].agg_ = agg;
+        }
+    }
+
+    /// Builds a block and its aggregate
+    fn new_block_(&self, first: usize, last: usize, len: usize) -> Block<A::Output> {
+        Block {
+            first_: first,
+            last_: last,
+            len_: len,
+            agg_: self.fold_(first, len),
+        }
+    }
+
+    /// Aggregates `len` items starting at index `first`
+    fn fold_(&self, first: usize, len: usize) -> A::Output {
+        let mut acc = self.monoid_.identity();
+        let mut curr_index = first;
+        for _ in 0..len {
+            if let Some(Some(node)) = self.list_.nodes_.get(curr_index) {
+                let item = self.monoid_.lift(&node.key_, &node.value_);
+                acc = self.monoid_.combine(&acc, &item);
+                curr_index = node.next_;
+            }
+        }
+        acc
+    }
+
+    #[inline(always)]
+    fn key_(&self, index: usize) -> &K {
+        &self.list_.nodes_[index].as_ref().unwrap().key_
+    }
+
+    #[inline(always)]
+    fn next_of_(&self, index: usize) -> usize {
+        self.list_.nodes_[index]
+            .as_ref()
+            .map_or(OUT_OF_BOUNDS, |n| n.next_)
+    }
+
+    #[inline(always)]
+    fn prev_of_(&self, index: usize) -> usize {
+        self.list_.nodes_[index]
+            .as_ref()
+            .map_or(OUT_OF_BOUNDS, |n| n.prev_)
+    }
+}
+
+/// Returns true if the key is not before the start bound
+fn after_start_<K: Ord>(start: Bound<&K>, key: &K) -> bool {
+    match start {
+        Bound::Included(s) => key >= s,
+        Bound::Excluded(s) => key > s,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Returns true if the key is not after the end bound
+fn before_end_<K: Ord>(end: Bound<&K>, key: &K) -> bool {
+    match end {
+        Bound::Included(e) => key <= e,
+        Bound::Excluded(e) => key < e,
+        Bound::Unbounded => true,
+    }
+}
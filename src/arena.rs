@@ -0,0 +1,396 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Several ordered lists sharing one slot arena.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+/// A handle to one of the lists of a [`ListArena`], created by [`ListArena::add_list()`].
+/// A handle is only valid for the arena that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ArenaList(usize);
+
+#[derive(Clone, Copy, Debug)]
+struct ListEnds {
+    head_: usize,
+    tail_: usize,
+    len_: usize,
+}
+
+/// Several ordered lists whose items live in one shared slot arena.
+///
+/// Each list works like a [`crate::LinkedList`] with its own order and its own unique keys.
+/// An item keeps its index for as long as it lives, also when it is moved to another list with
+/// [`ListArena::move_to()`]. Moving only relinks the item, its slot is never handed to another
+/// item.
+///
+/// The arena is one [`crate::LinkedList`] ordered by list and then by key, every list is a run
+/// of that list, so the slots and the links are managed by the LinkedList code.
+/// The searches are sequential, from the head of the list or from a position hint in the list.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::ListArena;
+/// let mut arena = ListArena::<i8, &str>::default();
+/// let todo = arena.add_list();
+/// let done = arena.add_list();
+/// let a = arena.ordered_insert(todo, 1, "a").unwrap();
+/// let _ = arena.ordered_insert(todo, 2, "b").unwrap();
+/// assert!(arena.move_to(a, done).unwrap());
+/// assert_eq!(arena.get(a).unwrap(), (&1, &"a"));
+/// assert_eq!(arena.list_of(a), Some(done));
+/// assert_eq!(arena.iter(todo).map(|(k, _)| *k).collect::<Vec<_>>(), vec![2]);
+/// assert_eq!(arena.len(done), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ListArena<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    // the keys are (list, key) pairs
+    list_: LinkedList<(usize, K), V>,
+    lists_: Vec<ListEnds>,
+}
+
+impl<K, V> Default for ListArena<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ListArena<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// Creates an arena without any lists
+    pub const fn new() -> Self {
+        Self {
+            list_: LinkedList::new(),
+            lists_: Vec::new(),
+        }
+    }
+}
+
+impl<K, V> ListArena<K, V>
+where
+    K: Debug + Ord,
+    V: Debug,
+{
+    /// Creates an arena without any lists, with room for `capacity` items
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            list_: LinkedList::with_capacity(capacity),
+            lists_: Vec::new(),
+        }
+    }
+
+    /// Adds an empty list to the arena
+    pub fn add_list(&mut self) -> ArenaList {
+        self.lists_.push(ListEnds {
+            head_: OUT_OF_BOUNDS,
+            tail_: OUT_OF_BOUNDS,
+            len_: 0,
+        });
+        ArenaList(self.lists_.len() - 1)
+    }
+
+    #[inline(always)]
+    /// Returns the number of lists
+    pub fn list_count(&self) -> usize {
+        self.lists_.len()
+    }
+
+    #[inline(always)]
+    /// Returns the number of items in a list, 0 for an unknown list
+    pub fn len(&self, list: ArenaList) -> usize {
+        self.lists_.get(list.0).map_or(0, |l| l.len_)
+    }
+
+    #[inline(always)]
+    /// Returns true if the list is empty or unknown
+    pub fn is_empty(&self, list: ArenaList) -> bool {
+        self.len(list) == 0
+    }
+
+    #[inline(always)]
+    /// Returns the number of items in all the lists
+    pub fn total_len(&self) -> usize {
+        self.list_.len()
+    }
+
+    #[inline(always)]
+    /// Returns the head index of a list, OUT_OF_BOUNDS if the list is empty or unknown
+    pub fn head(&self, list: ArenaList) -> usize {
+        self.lists_.get(list.0).map_or(OUT_OF_BOUNDS, |l| l.head_)
+    }
+
+    #[inline(always)]
+    /// Returns the tail index of a list, OUT_OF_BOUNDS if the list is empty or unknown
+    pub fn tail(&self, list: ArenaList) -> usize {
+        self.lists_.get(list.0).map_or(OUT_OF_BOUNDS, |l| l.tail_)
+    }
+
+    #[inline(always)]
+    /// Returns the list of the item at index, or None if the index is not an active item
+    pub fn list_of(&self, index: usize) -> Option<ArenaList> {
+        self.list_.get_k_opt(index).map(|k| ArenaList(k.0))
+    }
+
+    /// Returns the item key and value at index
+    pub fn get(&self, index: usize) -> Result<(&K, &V), MapError> {
+        let (key, value) = self.list_.get(index)?;
+        Ok((&key.1, value))
+    }
+
+    /// Returns the item key and a mutable reference to the item value at index
+    pub fn get_pair_mut(&mut self, index: usize) -> Result<(&K, &mut V), MapError> {
+        let (key, value) = self.list_.get_pair_mut(index)?;
+        Ok((&key.1, value))
+    }
+
+    /// Returns the index of the item after the item at index in its list
+    pub fn next(&self, index: usize) -> Option<usize> {
+        let list = self.list_of(index)?.0;
+        let next = self.list_.nodes_[index].as_ref()?.next_;
+        Some(next).filter(|i| self.in_list_(*i, list))
+    }
+
+    /// Returns the index of the item before the item at index in its list
+    pub fn prev(&self, index: usize) -> Option<usize> {
+        let list = self.list_of(index)?.0;
+        let prev = self.list_.nodes_[index].as_ref()?.prev_;
+        Some(prev).filter(|i| self.in_list_(*i, list))
+    }
+
+    /// Iterates over the items of a list in sorted order
+    pub fn iter(&self, list: ArenaList) -> impl Iterator<Item = (&K, &V)> + '_ {
+        let mut curr_index = self.head(list);
+        std::iter::from_fn(move || {
+            let (key, value) = self.list_.get(curr_index).ok()?;
+            curr_index = self.next(curr_index).unwrap_or(OUT_OF_BOUNDS);
+            Some((&key.1, value))
+        })
+    }
+
+    #[inline(always)]
+    /// Returns the index of the first item of the list that does not go before `key`, or None
+    /// if every key of the list goes before `key`
+    pub fn lower_bound(&self, list: ArenaList, key: &K) -> Option<usize> {
+        self.lower_bound_pos(list, key, self.head(list))
+    }
+
+    /// Same as [`ListArena::lower_bound()`], but the search starts at `position`, in both
+    /// directions. A position that is not an item of the list searches from the head.
+    pub fn lower_bound_pos(&self, list: ArenaList, key: &K, position: usize) -> Option<usize> {
+        let position = if self.in_list_(position, list.0) {
+            position
+        } else {
+            self.head(list)
+        };
+        let start = self.list_.get_k_opt(position)?;
+        if key <= &start.1 {
+            // search up the list, stop at the first key that goes before `key`
+            let mut last_match = position;
+            while let Some(prev) = self.prev(last_match) {
+                if key > &self.list_.get_k_opt(prev)?.1 {
+                    break;
+                }
+                last_match = prev;
+            }
+            Some(last_match)
+        } else {
+            // search down the list, stop at the first key that does not go before `key`
+            let mut curr_index = position;
+            while let Some(next) = self.next(curr_index) {
+                if key <= &self.list_.get_k_opt(next)?.1 {
+                    return Some(next);
+                }
+                curr_index = next;
+            }
+            None
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the index of the item of the list with `key`
+    pub fn find(&self, list: ArenaList, key: &K) -> Option<usize> {
+        self.find_pos(list, key, self.head(list))
+    }
+
+    /// Same as [`ListArena::find()`], but the search starts at `position`
+    pub fn find_pos(&self, list: ArenaList, key: &K, position: usize) -> Option<usize> {
+        self.lower_bound_pos(list, key, position)
+            .filter(|i| self.list_.get_k_opt(*i).map(|k| &k.1) == Some(key))
+    }
+
+    #[inline(always)]
+    /// Inserts an item into a list by Order (lesser first) and returns its index.
+    /// Inserting a key that already exists in the list is a NOP, the index of the existing item
+    /// is returned.
+    pub fn ordered_insert(&mut self, list: ArenaList, key: K, value: V) -> Result<usize, MapError> {
+        self.ordered_insert_pos(list, key, value, self.head(list))
+    }
+
+    /// Same as [`ListArena::ordered_insert()`], but the search for the insert position starts
+    /// at `position`. A position that is not an item of the list searches from the head.
+    pub fn ordered_insert_pos(
+        &mut self,
+        list: ArenaList,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        self.check_list_(list)?;
+        let before = self.lower_bound_pos(list, &key, position);
+        if let Some(before) = before {
+            if self.list_.get_k_opt(before).map(|k| &k.1) == Some(&key) {
+                return Ok(before);
+            }
+        }
+        self.link_(list.0, key, value, before)
+    }
+
+    /// Removes the item at index from its list and from the arena, and returns it
+    pub fn remove(&mut self, index: usize) -> Result<(K, V), MapError> {
+        let ((_, key), value) = self.unlink_(index)?;
+        Ok((key, value))
+    }
+
+    #[inline(always)]
+    /// Moves the item at index to the list `to`, in O(n) of the target list. The item keeps
+    /// its index, only the links are changed.
+    /// Returns false, and leaves the item where it was, if `to` already has an item with the
+    /// same key.
+    pub fn move_to(&mut self, index: usize, to: ArenaList) -> Result<bool, MapError> {
+        self.move_to_pos(index, to, self.head(to))
+    }
+
+    /// Same as [`ListArena::move_to()`], but the search for the new position starts at
+    /// `position` of the list `to`, so a good hint makes the move O(1).
+    pub fn move_to_pos(
+        &mut self,
+        index: usize,
+        to: ArenaList,
+        position: usize,
+    ) -> Result<bool, MapError> {
+        self.check_list_(to)?;
+        let key = match self.list_.get_k_opt(index) {
+            Some(key) => key,
+            None => {
+                return Err(map_error!(
+                    InternalError,
+                    "error, item {} was not active",
+                    index
+                ))
+            }
+        };
+        if key.0 == to.0 {
+            return Ok(true);
+        }
+        let before = self.lower_bound_pos(to, &key.1, position);
+        if let Some(before) = before {
+            if self.list_.get_k_opt(before).map(|k| &k.1) == Some(&key.1) {
+                return Ok(false);
+            }
+        }
+        // the slot is freed and taken again at once, so the item keeps its index
+        let ((_, key), value) = self.unlink_(index)?;
+        let new_index = self.link_(to.0, key, value, before)?;
+        debug_assert_eq!(new_index, index);
+        Ok(true)
+    }
+
+    #[inline(always)]
+    /// Returns true if index is an item of `list`
+    fn in_list_(&self, index: usize, list: usize) -> bool {
+        self.list_.get_k_opt(index).map(|k| k.0) == Some(list)
+    }
+
+    fn check_list_(&self, list: ArenaList) -> Result<(), MapError> {
+        if list.0 >= self.lists_.len() {
+            return Err(map_error!(InternalError, "unknown list {:?}", list));
+        }
+        Ok(())
+    }
+
+    /// Inserts a new item into `list`, before the item `before` or, if None, after the tail of
+    /// the list. The items of the lower lists go first, so the tail of the closest lower list
+    /// that is not empty is the place of the first item of a list.
+    fn link_(
+        &mut self,
+        list: usize,
+        key: K,
+        value: V,
+        before: Option<usize>,
+    ) -> Result<usize, MapError> {
+        let after = match before {
+            Some(before) => self.list_.nodes_[before].as_ref().map(|n| n.prev_),
+            None => self.lists_[..=list]
+                .iter()
+                .rev()
+                .map(|l| l.tail_)
+                .find(|i| *i != OUT_OF_BOUNDS),
+        }
+        .unwrap_or(OUT_OF_BOUNDS);
+        let index = match self.list_.nodes_.get(after) {
+            Some(Some(node)) if node.next_ == OUT_OF_BOUNDS => {
+                self.list_.push_back_((list, key), value)?
+            }
+            Some(Some(node)) => {
+                let next = node.next_;
+                self.list_.insert_before_(next, (list, key), value)?
+            }
+            _ => {
+                let head = self.list_.head_;
+                self.list_.insert_before_(head, (list, key), value)?
+            }
+        };
+        let ends = &mut self.lists_[list];
+        if ends.len_ == 0 || before == Some(ends.head_) {
+            ends.head_ = index;
+        }
+        if before.is_none() {
+            ends.tail_ = index;
+        }
+        ends.len_ += 1;
+        Ok(index)
+    }
+
+    /// Removes the item at index from its list and from the arena
+    fn unlink_(&mut self, index: usize) -> Result<((usize, K), V), MapError> {
+        let list = match self.list_of(index) {
+            Some(list) => list.0,
+            None => {
+                return Err(map_error!(
+                    InternalError,
+                    "error, item {} was not active",
+                    index
+                ))
+            }
+        };
+        let (prev, item, next) = self.list_.remove__(index)?;
+        let prev = Some(prev).filter(|i| self.in_list_(*i, list));
+        let next = Some(next).filter(|i| self.in_list_(*i, list));
+        let ends = &mut self.lists_[list];
+        if ends.head_ == index {
+            ends.head_ = next.unwrap_or(OUT_OF_BOUNDS);
+        }
+        if ends.tail_ == index {
+            ends.tail_ = prev.unwrap_or(OUT_OF_BOUNDS);
+        }
+        ends.len_ -= 1;
+        Ok(item)
+    }
+}
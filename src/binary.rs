@@ -0,0 +1,373 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A compact binary format that preserves the exact slot layout of a list.
+//!
+//! The slot indices, the free index pool, the tombstones and head/tail are all stored, so a
+//! decoded list returns the same indices (and will reuse free slots in the same order) as the
+//! original list. The metadata of the items is stored too. All integers are stored as little
+//! endian, indices as u64.
+
+use crate::{LinkedList, MapError, Node, TotalF32, TotalF64, OUT_OF_BOUNDS};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::fmt::Debug;
+
+const MAGIC: &[u8; 4] = b"CPPM";
+const VERSION: u8 = 1;
+
+/// Encoding and decoding of keys and values for [`LinkedList::to_bytes()`] and
+/// [`LinkedList::from_bytes()`].
+pub trait BinaryCodec: Sized {
+    /// Append the encoded value to `buf`
+    fn encode(&self, buf: &mut Vec<u8>);
+    /// Decode a value from the front of `buf` and advance `buf` past it
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError>;
+}
+
+#[inline(always)]
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8], MapError> {
+    if buf.len() < len {
+        return Err(map_error!(
+            DecodeError,
+            "unexpected end of data, needed {} more bytes",
+            len - buf.len()
+        ));
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+macro_rules! primitive_codec {
+    ($($t:ty),*) => {$(
+        impl BinaryCodec for $t {
+            #[inline(always)]
+            fn encode(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.to_le_bytes());
+            }
+
+            #[inline(always)]
+            fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+                let bytes = take(buf, std::mem::size_of::<$t>())?;
+                // take() returned exactly size_of bytes
+                Ok(<$t>::from_le_bytes(bytes.try_into().unwrap()))
+            }
+        }
+    )*};
+}
+
+primitive_codec!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+impl BinaryCodec for usize {
+    #[inline(always)]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if *self == OUT_OF_BOUNDS {
+            u64::MAX.encode(buf)
+        } else {
+            (*self as u64).encode(buf)
+        }
+    }
+
+    #[inline(always)]
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        let v = u64::decode(buf)?;
+        if v == u64::MAX {
+            Ok(OUT_OF_BOUNDS)
+        } else {
+            v.try_into()
+                .map_err(|_| map_error!(DecodeError, "index {} is too large", v))
+        }
+    }
+}
+
+impl BinaryCodec for isize {
+    #[inline(always)]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as i64).encode(buf)
+    }
+
+    #[inline(always)]
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        let v = i64::decode(buf)?;
+        v.try_into()
+            .map_err(|_| map_error!(DecodeError, "isize {} is out of range", v))
+    }
+}
+
+impl BinaryCodec for bool {
+    #[inline(always)]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(*self as u8)
+    }
+
+    #[inline(always)]
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        match u8::decode(buf)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(map_error!(DecodeError, "invalid bool {}", b)),
+        }
+    }
+}
+
+impl BinaryCodec for () {
+    #[inline(always)]
+    fn encode(&self, _buf: &mut Vec<u8>) {}
+
+    #[inline(always)]
+    fn decode(_buf: &mut &[u8]) -> Result<Self, MapError> {
+        Ok(())
+    }
+}
+
+impl BinaryCodec for String {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+        buf.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        let len = usize::decode(buf)?;
+        let bytes = take(buf, len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| map_error!(DecodeError, "{}", e))
+    }
+}
+
+impl BinaryCodec for TotalF64 {
+    #[inline(always)]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.0.encode(buf)
+    }
+
+    #[inline(always)]
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        Ok(Self(f64::decode(buf)?))
+    }
+}
+
+impl BinaryCodec for TotalF32 {
+    #[inline(always)]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.0.encode(buf)
+    }
+
+    #[inline(always)]
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        Ok(Self(f32::decode(buf)?))
+    }
+}
+
+impl<T: BinaryCodec> BinaryCodec for Option<T> {
+    #[inline(always)]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.is_some().encode(buf);
+        if let Some(v) = self {
+            v.encode(buf);
+        }
+    }
+
+    #[inline(always)]
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        Ok(if bool::decode(buf)? {
+            Some(T::decode(buf)?)
+        } else {
+            None
+        })
+    }
+}
+
+impl<A: BinaryCodec, B: BinaryCodec> BinaryCodec for (A, B) {
+    #[inline(always)]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.0.encode(buf);
+        self.1.encode(buf);
+    }
+
+    #[inline(always)]
+    fn decode(buf: &mut &[u8]) -> Result<Self, MapError> {
+        Ok((A::decode(buf)?, B::decode(buf)?))
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Encode the list, including the exact slot layout, the tombstones and the metadata, as
+    /// bytes. The settings of the list, other than deferred slot reuse, are not stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, String>::default();
+    /// let _ = ll.ordered_insert(1, "one".to_string());
+    /// let two = ll.ordered_insert(2, "two".to_string()).unwrap();
+    /// let _ = ll.pop_front();
+    /// let ll2 = LinkedList::<i8, String>::from_bytes(&ll.to_bytes()).unwrap();
+    /// assert_eq!(ll2.head(), two);
+    /// assert_eq!(ll2.get(two).unwrap(), (&2, &"two".to_string()));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        K: BinaryCodec,
+        V: BinaryCodec,
+        M: BinaryCodec,
+    {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(MAGIC);
+        buf.push(VERSION);
+        self.head_.encode(&mut buf);
+        self.tail_.encode(&mut buf);
+        self.nodes_.len().encode(&mut buf);
+        for node in self.nodes_.iter() {
+            if let Some(node) = node {
+                buf.push(1);
+                node.prev_.encode(&mut buf);
+                node.next_.encode(&mut buf);
+                node.key_.encode(&mut buf);
+                node.value_.encode(&mut buf);
+                node.meta_.encode(&mut buf);
+            } else {
+                buf.push(0);
+            }
+        }
+        self.id_pool_.len().encode(&mut buf);
+        for i in self.id_pool_.iter() {
+            i.encode(&mut buf);
+        }
+        self.defer_slot_reuse_.encode(&mut buf);
+        self.tombstones_.len().encode(&mut buf);
+        for (i, (prev, next)) in self.tombstones_.iter() {
+            i.encode(&mut buf);
+            prev.encode(&mut buf);
+            next.encode(&mut buf);
+        }
+        buf
+    }
+
+    /// Decode a list encoded by [`LinkedList::to_bytes()`].
+    /// The decoded list has the same slot layout, tombstones and metadata as the encoded list.
+    /// The structure of the list is checked with [`LinkedList::check_consistency()`], a
+    /// damaged or hostile buffer returns a DecodeError.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MapError>
+    where
+        K: BinaryCodec,
+        V: BinaryCodec,
+        M: BinaryCodec,
+    {
+        let mut buf = bytes;
+        if take(&mut buf, MAGIC.len())? != MAGIC {
+            return Err(map_error!(DecodeError, "bad magic number"));
+        }
+        let version = u8::decode(&mut buf)?;
+        if version != VERSION {
+            return Err(map_error!(DecodeError, "unsupported version {}", version));
+        }
+        let head = usize::decode(&mut buf)?;
+        let tail = usize::decode(&mut buf)?;
+        let nodes_len = usize::decode(&mut buf)?;
+        // don't trust nodes_len for the allocation, a slot is at least one byte
+        let mut nodes = Vec::with_capacity(nodes_len.min(buf.len()));
+        let mut free_slots = 0_usize;
+        for _ in 0..nodes_len {
+            match u8::decode(&mut buf)? {
+                0 => {
+                    free_slots += 1;
+                    nodes.push(None);
+                }
+                1 => nodes.push(Some(Node {
+                    prev_: usize::decode(&mut buf)?,
+                    next_: usize::decode(&mut buf)?,
+                    key_: K::decode(&mut buf)?,
+                    value_: V::decode(&mut buf)?,
+                    meta_: M::decode(&mut buf)?,
+                })),
+                tag => return Err(map_error!(DecodeError, "invalid slot tag {}", tag)),
+            }
+        }
+        let id_pool_len = usize::decode(&mut buf)?;
+        if id_pool_len > free_slots {
+            return Err(map_error!(
+                DecodeError,
+                "free index pool has {} entries, expected at most {}",
+                id_pool_len,
+                free_slots
+            ));
+        }
+        let mut id_pool = Vec::with_capacity(id_pool_len);
+        for _ in 0..id_pool_len {
+            id_pool.push(usize::decode(&mut buf)?);
+        }
+        let defer_slot_reuse = bool::decode(&mut buf)?;
+        let tombstones_len = usize::decode(&mut buf)?;
+        if id_pool_len + tombstones_len != free_slots {
+            return Err(map_error!(
+                DecodeError,
+                "{} free indices and {} tombstones, expected {} free slots",
+                id_pool_len,
+                tombstones_len,
+                free_slots
+            ));
+        }
+        let mut tombstones = BTreeMap::new();
+        for _ in 0..tombstones_len {
+            let i = usize::decode(&mut buf)?;
+            let links = (usize::decode(&mut buf)?, usize::decode(&mut buf)?);
+            let _ = tombstones.insert(i, links);
+        }
+        if !buf.is_empty() {
+            return Err(map_error!(
+                DecodeError,
+                "{} bytes of trailing data",
+                buf.len()
+            ));
+        }
+        let is_empty = free_slots == nodes.len();
+        for end in [head, tail].iter() {
+            if is_empty != (*end == OUT_OF_BOUNDS)
+                || (*end != OUT_OF_BOUNDS && !matches!(nodes.get(*end), Some(Some(_))))
+            {
+                return Err(map_error!(DecodeError, "invalid head/tail index {}", end));
+            }
+        }
+        let list = Self {
+            head_: head,
+            tail_: tail,
+            nodes_: nodes,
+            id_pool_: id_pool,
+            defer_slot_reuse_: defer_slot_reuse,
+            tombstones_: tombstones,
+            ..Self::new()
+        };
+        // the links, the key order and the free slots
+        list.check_consistency()
+            .map_err(|e| map_error!(DecodeError, "{}", e))?;
+        // a tombstone must lead to a live item or to the end of the list, in both directions
+        for (index, links) in list.tombstones_.iter() {
+            for (mut end, forward) in [(links.0, false), (links.1, true)].iter().copied() {
+                for _ in 0..=list.tombstones_.len() {
+                    match list.tombstone_(end) {
+                        Some((prev, next)) => end = if forward { next } else { prev },
+                        None => break,
+                    }
+                }
+                if end != OUT_OF_BOUNDS && !matches!(list.nodes_.get(end), Some(Some(_))) {
+                    return Err(map_error!(
+                        DecodeError,
+                        "the tombstone at {} has an invalid link",
+                        index
+                    ));
+                }
+            }
+        }
+        Ok(list)
+    }
+}
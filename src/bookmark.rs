@@ -0,0 +1,157 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Bookmarks, positions in the list that are kept valid by the list itself.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+/// A handle to a bookmark of a [`LinkedList`], created by [`LinkedList::add_bookmark()`] or
+/// [`LinkedList::add_bookmark_at()`]. A bookmark is only valid for the list that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bookmark(usize);
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Adds a bookmark at the item at index.
+    ///
+    /// The list keeps the bookmark up to date: inserts don't move it, and if the bookmarked item
+    /// is removed the bookmark moves to the next item (or to the previous item if the tail was
+    /// removed). optimize() remaps it. A bookmark only becomes unset (OUT_OF_BOUNDS) when the
+    /// list is emptied, and stays unset until it is moved with [`LinkedList::move_bookmark()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..5 {
+    ///     let _ = ll.ordered_insert(i, i);
+    /// }
+    /// let third = ll.add_bookmark_at(2).unwrap();
+    /// let _ = ll.ordered_insert(-1, -1);
+    /// assert_eq!(ll.get_k(ll.bookmark(third).unwrap()).unwrap(), &2);
+    /// assert_eq!(ll.bookmark_ordinal(third), Some(3));
+    /// let _ = ll.pop_front();
+    /// let _ = ll.pop_front();
+    /// assert_eq!(ll.bookmark_ordinal(third), Some(1));
+    /// ```
+    pub fn add_bookmark(&mut self, index: usize) -> Result<Bookmark, MapError> {
+        let _ = self.get(index)?;
+        let id = match self.bookmarks_.iter().position(|b| b.is_none()) {
+            Some(id) => {
+                self.bookmarks_[id] = Some(index);
+                id
+            }
+            None => {
+                self.bookmarks_.push(Some(index));
+                self.bookmarks_.len() - 1
+            }
+        };
+        Ok(Bookmark(id))
+    }
+
+    /// Adds a bookmark at the item at the given ordinal position (0 is the head).
+    /// Finding the item is a sequential search from the head.
+    pub fn add_bookmark_at(&mut self, ordinal: usize) -> Result<Bookmark, MapError> {
+        let mut curr_index = self.head_;
+        for _ in 0..ordinal {
+            match self.nodes_.get(curr_index) {
+                Some(Some(node)) => curr_index = node.next_,
+                _ => break,
+            }
+        }
+        self.add_bookmark(curr_index)
+    }
+
+    /// Returns the index of the bookmarked item, or None if the bookmark is unset or has been
+    /// removed.
+    pub fn bookmark(&self, bookmark: Bookmark) -> Option<usize> {
+        match self.bookmarks_.get(bookmark.0) {
+            Some(Some(index)) if *index != OUT_OF_BOUNDS => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Returns the ordinal position (0 is the head) of the bookmarked item.
+    /// This is a sequential search from the head.
+    pub fn bookmark_ordinal(&self, bookmark: Bookmark) -> Option<usize> {
+        let index = self.bookmark(bookmark)?;
+        let mut curr_index = self.head_;
+        let mut ordinal = 0;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            if curr_index == index {
+                return Some(ordinal);
+            }
+            ordinal += 1;
+            curr_index = node.next_;
+        }
+        None
+    }
+
+    /// Moves an existing bookmark to the item at index
+    pub fn move_bookmark(&mut self, bookmark: Bookmark, index: usize) -> Result<(), MapError> {
+        let _ = self.get(index)?;
+        match self.bookmarks_.get_mut(bookmark.0) {
+            Some(Some(b)) => {
+                *b = index;
+                Ok(())
+            }
+            _ => Err(map_error!(InternalError, "unknown bookmark {:?}", bookmark)),
+        }
+    }
+
+    /// Removes a bookmark, returns the index it pointed at
+    pub fn remove_bookmark(&mut self, bookmark: Bookmark) -> Option<usize> {
+        let index = self.bookmarks_.get_mut(bookmark.0)?.take()?;
+        while let Some(None) = self.bookmarks_.last() {
+            let _ = self.bookmarks_.pop();
+        }
+        Some(index).filter(|i| *i != OUT_OF_BOUNDS)
+    }
+
+    /// Moves the bookmarks of the removed node at `index` to one of its old neighbours
+    pub(crate) fn bookmarks_removed_(&mut self, index: usize, prev: usize, next: usize) {
+        let replacement = if next != OUT_OF_BOUNDS { next } else { prev };
+        for b in self.bookmarks_.iter_mut().flatten() {
+            if *b == index {
+                *b = replacement;
+            }
+        }
+    }
+
+    /// Returns the ids of the bookmarks at index
+    pub(crate) fn bookmarks_at_(&self, index: usize) -> Vec<usize> {
+        self.bookmarks_
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == Some(index))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Moves the bookmarks `ids` back to the restored node at index. Bookmarks that have been
+    /// removed in the meantime stay removed.
+    pub(crate) fn bookmarks_restored_(&mut self, index: usize, ids: &[usize]) {
+        for id in ids {
+            if let Some(Some(b)) = self.bookmarks_.get_mut(*id) {
+                *b = index;
+            }
+        }
+    }
+
+    /// Applies an index remapping (`remap[old_index] == new_index`) to the bookmarks
+    pub(crate) fn bookmarks_remapped_(&mut self, remap: &[usize]) {
+        for b in self.bookmarks_.iter_mut().flatten() {
+            *b = remap.get(*b).copied().unwrap_or(OUT_OF_BOUNDS);
+        }
+    }
+}
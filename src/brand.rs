@@ -0,0 +1,124 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The identity of a list, and indices branded with the list that created them.
+
+use crate::LinkedList;
+use std::fmt::Debug;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    OnceLock,
+};
+
+static NEXT_LIST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The identity of a list. The id is allocated the first time it is needed, a cloned list gets
+/// a new id.
+#[derive(Debug, Default)]
+pub(crate) struct ListId {
+    id_: OnceLock<u64>,
+}
+
+impl ListId {
+    pub(crate) const fn new() -> Self {
+        Self {
+            id_: OnceLock::new(),
+        }
+    }
+
+    fn id(&self) -> u64 {
+        *self
+            .id_
+            .get_or_init(|| NEXT_LIST_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Clone for ListId {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+/// An index that remembers which list it belongs to, created by [`LinkedList::brand()`].
+///
+/// In debug builds [`LinkedList::unbrand()`] panics if the index is used with another list.
+/// In release builds the owner is not stored and a BrandedIndex is just a usize.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BrandedIndex {
+    index_: usize,
+    #[cfg(debug_assertions)]
+    owner_: u64,
+}
+
+impl BrandedIndex {
+    #[inline(always)]
+    /// Returns the raw index, without checking the owner
+    pub fn index(&self) -> usize {
+        self.index_
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    #[inline(always)]
+    /// Returns the id of this list, for telling lists apart in logs and assertions. It is
+    /// included in the error messages about invalid indices and in [`crate::LongScan`].
+    ///
+    /// The ids are allocated from a process wide counter, starting at 1, the first time the id
+    /// of a list is needed. A cloned list, or a list given out by a [`crate::ListPool`], gets a
+    /// new id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let a = LinkedList::<i8, i8>::default();
+    /// let b = a.clone();
+    /// assert_ne!(a.list_id(), b.list_id());
+    /// assert_eq!(a.list_id(), a.list_id());
+    /// ```
+    pub fn list_id(&self) -> u64 {
+        self.list_id_.id()
+    }
+
+    #[inline(always)]
+    /// Brands an index of this list, see [`BrandedIndex`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut a = LinkedList::<i8, i8>::default();
+    /// let index = a.ordered_insert(1, 1).unwrap();
+    /// let index = a.brand(index);
+    /// assert_eq!(a.get(a.unbrand(index)).unwrap(), (&1, &1));
+    /// ```
+    pub fn brand(&self, index: usize) -> BrandedIndex {
+        BrandedIndex {
+            index_: index,
+            #[cfg(debug_assertions)]
+            owner_: self.list_id_.id(),
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the raw index of a [`BrandedIndex`].
+    /// Panics in debug builds if the index was branded by another list.
+    pub fn unbrand(&self, index: BrandedIndex) -> usize {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            index.owner_,
+            self.list_id_.id(),
+            "the index {} was branded by another list",
+            index.index_
+        );
+        index.index_
+    }
+}
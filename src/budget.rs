@@ -0,0 +1,165 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Searches with a bounded number of steps, for callers that need to cap the worst case.
+
+use crate::{LinkedList, MapError, ScanOperation, OUT_OF_BOUNDS};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+/// The result of [`LinkedList::lower_bound_budget()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetSearch {
+    /// The search finished, with the same result as lower_bound_pos()
+    Found(Option<usize>),
+    /// The search ran out of steps. `stopped_at` is the last item visited, it is closer to the
+    /// lower bound than the start position was.
+    NotFoundWithinBudget { stopped_at: usize },
+}
+
+/// The result of [`LinkedList::ordered_insert_pos_budget()`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BudgetInsert<K, V> {
+    /// The search finished, the index of the inserted (or already existing) item
+    Done(usize),
+    /// The search ran out of steps and nothing was inserted. The key and the value are handed
+    /// back together with the last item visited.
+    NotFoundWithinBudget { stopped_at: usize, key: K, value: V },
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Same as [`LinkedList::lower_bound_pos()`], but the search gives up after visiting
+    /// `max_steps` items. The search is always sequential, the search strategy is not used.
+    /// An invalid position will search from the tail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{BudgetSearch, LinkedList};
+    /// let mut ll = LinkedList::<i32, ()>::default();
+    /// for i in 0..100 {
+    ///     let _ = ll.ordered_insert_pos(i, (), ll.tail());
+    /// }
+    /// let head = ll.head();
+    /// let rv = ll.lower_bound_budget(&50, head, 10).unwrap();
+    /// let BudgetSearch::NotFoundWithinBudget { stopped_at } = rv else { panic!() };
+    /// assert_eq!(ll.get_k(stopped_at).unwrap(), &9);
+    /// // continue from where the search stopped
+    /// let rv = ll.lower_bound_budget(&50, stopped_at, 100).unwrap();
+    /// assert_eq!(rv, BudgetSearch::Found(ll.lower_bound(&50).unwrap()));
+    /// ```
+    pub fn lower_bound_budget<Q>(
+        &self,
+        key: &Q,
+        position: usize,
+        max_steps: usize,
+    ) -> Result<BudgetSearch, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let (rv, hops) = self.budget_search_(key, position, max_steps);
+        self.report_scan_(ScanOperation::LowerBound, &key, position, hops);
+        Ok(rv)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], but the search gives up after visiting
+    /// `max_steps` items, see [`LinkedList::lower_bound_budget()`]. Inserting into an empty list
+    /// always succeeds.
+    pub fn ordered_insert_pos_budget(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+        max_steps: usize,
+    ) -> Result<BudgetInsert<K, V>, MapError> {
+        let key = self.normalize_key(key);
+        if self.head_ == OUT_OF_BOUNDS {
+            return Ok(BudgetInsert::Done(self.push_back_(key, value)?));
+        }
+        let (rv, hops) = self.budget_search_(&key, position, max_steps);
+        self.report_scan_(ScanOperation::Insert, &key, position, hops);
+        Ok(match rv {
+            BudgetSearch::NotFoundWithinBudget { stopped_at } => {
+                BudgetInsert::NotFoundWithinBudget {
+                    stopped_at,
+                    key,
+                    value,
+                }
+            }
+            // Insert with an already existing key is a 'nop'
+            BudgetSearch::Found(Some(index)) if self.get_k_opt(index) == Some(&key) => {
+                BudgetInsert::Done(index)
+            }
+            BudgetSearch::Found(Some(index)) => {
+                BudgetInsert::Done(self.insert_before_(index, key, value)?)
+            }
+            BudgetSearch::Found(None) => BudgetInsert::Done(self.push_back_(key, value)?),
+        })
+    }
+
+    /// A sequential lower bound search visiting at most `max_steps` items, returns the result
+    /// and the number of visited items.
+    fn budget_search_<Q>(&self, key: &Q, position: usize, max_steps: usize) -> (BudgetSearch, usize)
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let position = if self.get_k_opt(position).is_some() {
+            position
+        } else {
+            self.tail_
+        };
+        let start = match self.nodes_.get(position) {
+            Some(Some(node)) => node,
+            _ => return (BudgetSearch::Found(None), 0),
+        };
+        // search up the list if the start item does not go before `key`
+        let up = key.cmp(start.key_.borrow()) != Ordering::Greater;
+        let mut last_visited = position;
+        let mut curr_index = position;
+        let mut hops = 0_usize;
+        while let Some(Some(sample)) = self.nodes_.get(curr_index) {
+            if hops == max_steps {
+                return (
+                    BudgetSearch::NotFoundWithinBudget {
+                        stopped_at: last_visited,
+                    },
+                    hops,
+                );
+            }
+            hops += 1;
+            let order = key.cmp(sample.key_.borrow());
+            if order == Ordering::Equal {
+                // the keys are unique, this is the lower bound
+                return (BudgetSearch::Found(Some(curr_index)), hops);
+            }
+            let greater = order == Ordering::Greater;
+            if up && greater {
+                // the previous item was the lower bound
+                return (BudgetSearch::Found(Some(last_visited)), hops);
+            }
+            if !up && !greater {
+                return (BudgetSearch::Found(Some(curr_index)), hops);
+            }
+            last_visited = curr_index;
+            curr_index = if up { sample.prev_ } else { sample.next_ };
+        }
+        // walked past the head or the tail
+        if up {
+            (BudgetSearch::Found(Some(last_visited)), hops)
+        } else {
+            (BudgetSearch::Found(None), hops)
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Opt-in counting of the key comparisons made by the searches.
+
+use crate::{LinkedList, ScanOperation};
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+/// The key comparisons of one kind of search, see [`CompareStats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationStats {
+    /// The number of searches
+    pub operations: u64,
+    /// The total number of key comparisons of these searches
+    pub comparisons: u64,
+    /// The largest number of key comparisons of a single search
+    pub max_comparisons: u64,
+}
+
+impl OperationStats {
+    /// Returns the average number of key comparisons per search, 0.0 if there were no searches
+    pub fn average(&self) -> f64 {
+        if self.operations == 0 {
+            0.0
+        } else {
+            self.comparisons as f64 / self.operations as f64
+        }
+    }
+}
+
+/// Key comparison statistics returned by [`LinkedList::compare_stats()`].
+///
+/// The comparisons are the ones made while searching for the position of a key, one per visited
+/// node, i.e. the same count as the `hops` of a [`crate::LongScan`] report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompareStats {
+    /// The searches of ordered_insert(), ordered_insert_pos() and everything built on them
+    pub insert: OperationStats,
+    /// The searches of lower_bound(), lower_bound_pos() and everything built on them
+    pub lower_bound: OperationStats,
+    /// The number of key comparisons of the most recent search
+    pub last: u64,
+}
+
+/// The statistics of a list, only allocated while counting is enabled
+#[derive(Debug, Default)]
+pub(crate) struct CompareCounter(Option<Box<Mutex<CompareStats>>>);
+
+impl CompareCounter {
+    pub(crate) const fn new() -> Self {
+        Self(None)
+    }
+}
+
+impl Clone for CompareCounter {
+    fn clone(&self) -> Self {
+        Self(
+            self.0.as_ref().map(|stats| {
+                Box::new(Mutex::new(*stats.lock().unwrap_or_else(|e| e.into_inner())))
+            }),
+        )
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+{
+    /// Starts counting the key comparisons of the searches, from zero. This is useful for
+    /// measuring how much a position hint saves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i32, ()>::default();
+    /// ll.enable_compare_stats();
+    /// for i in 0..100 {
+    ///     let _ = ll.ordered_insert_pos(i, (), ll.tail()); // good hint
+    /// }
+    /// let good = ll.compare_stats().unwrap().insert;
+    /// ll.enable_compare_stats();
+    /// let _ = ll.ordered_insert_pos(100, (), ll.head()); // bad hint
+    /// let bad = ll.compare_stats().unwrap();
+    /// assert_eq!(good.max_comparisons, 1);
+    /// assert_eq!(bad.last, 100);
+    /// ```
+    pub fn enable_compare_stats(&mut self) {
+        self.compare_stats_ = CompareCounter(Some(Box::default()));
+    }
+
+    /// Stops counting the key comparisons
+    pub fn disable_compare_stats(&mut self) {
+        self.compare_stats_ = CompareCounter::new();
+    }
+
+    /// Returns the key comparison statistics, or None if counting is not enabled.
+    /// See [`LinkedList::enable_compare_stats()`].
+    pub fn compare_stats(&self) -> Option<CompareStats> {
+        self.compare_stats_
+            .0
+            .as_ref()
+            .map(|stats| *stats.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    #[inline(always)]
+    /// Adds the comparisons of a search to the statistics, if counting is enabled
+    pub(crate) fn count_comparisons_(&self, operation: ScanOperation, comparisons: usize) {
+        if let Some(stats) = &self.compare_stats_.0 {
+            let mut stats = stats.lock().unwrap_or_else(|e| e.into_inner());
+            let comparisons = comparisons as u64;
+            stats.last = comparisons;
+            let operation = match operation {
+                ScanOperation::Insert => &mut stats.insert,
+                ScanOperation::LowerBound => &mut stats.lower_bound,
+            };
+            operation.operations += 1;
+            operation.comparisons += comparisons;
+            operation.max_comparisons = operation.max_comparisons.max(comparisons);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A read only pointer into a borrowed list.
+
+use crate::{LinkedList, ListIterator, MapError, OUT_OF_BOUNDS};
+use std::borrow::Borrow;
+use std::fmt::Debug;
+
+/// A read only, pointer style position in a borrowed [`LinkedList`]. It moves like a
+/// [`crate::PIterator`], but it borrows the list instead of sharing it through an
+/// `Rc<RefCell<..>>`, so the accessors can't fail and return references into the list.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::LinkedList;
+/// let mut ll = LinkedList::<i8, i8>::default();
+/// for i in 0..5 {
+///     let _ = ll.ordered_insert(i * 2, i);
+/// }
+/// let mut cursor = ll.lower_bound_cursor(&3).unwrap();
+/// assert_eq!(cursor.get(), Some((&4, &2)));
+/// assert!(cursor.move_prev());
+/// assert_eq!(cursor.get_k(), Some(&2));
+/// assert!(cursor.move_prev() && !cursor.move_prev());
+/// assert!(!cursor.is_ok());
+/// ```
+#[derive(Debug)]
+pub struct Cursor<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a LinkedList<K, V, M>,
+    current_: usize,
+}
+
+impl<K, V, M> Clone for Cursor<'_, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V, M> Copy for Cursor<'_, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+}
+
+impl<'a, K, V, M> Cursor<'a, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    #[inline(always)]
+    /// Returns a cursor at the item at index, is_ok() returns false if the index is not an
+    /// active item
+    pub fn new(list: &'a LinkedList<K, V, M>, index: usize) -> Self {
+        Self {
+            list_: list,
+            current_: index,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the list
+    pub fn list(&self) -> &'a LinkedList<K, V, M> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// returns current index
+    pub fn current(&self) -> usize {
+        self.current_
+    }
+
+    #[inline(always)]
+    /// Returns the current key and value, or None if the cursor is not at an item
+    pub fn get(&self) -> Option<(&'a K, &'a V)> {
+        match self.list_.nodes_.get(self.current_) {
+            Some(Some(node)) => Some((&node.key_, &node.value_)),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the current key, or None if the cursor is not at an item
+    pub fn get_k(&self) -> Option<&'a K> {
+        self.get().map(|(k, _)| k)
+    }
+
+    #[inline(always)]
+    /// Returns the current value, or None if the cursor is not at an item
+    pub fn get_v(&self) -> Option<&'a V> {
+        self.get().map(|(_, v)| v)
+    }
+
+    #[inline(always)]
+    /// Move to the next element, returns is_ok()
+    pub fn move_next(&mut self) -> bool {
+        self.current_ = match self.list_.nodes_.get(self.current_) {
+            Some(Some(node)) => node.next_,
+            _ => OUT_OF_BOUNDS,
+        };
+        self.is_ok()
+    }
+
+    #[inline(always)]
+    /// Move to the previous element, returns is_ok()
+    pub fn move_prev(&mut self) -> bool {
+        self.current_ = match self.list_.nodes_.get(self.current_) {
+            Some(Some(node)) => node.prev_,
+            _ => OUT_OF_BOUNDS,
+        };
+        self.is_ok()
+    }
+
+    #[inline(always)]
+    /// Move to the first element
+    pub fn move_to_head(&mut self) {
+        self.current_ = self.list_.head_;
+    }
+
+    #[inline(always)]
+    /// Move to the last element
+    pub fn move_to_tail(&mut self) {
+        self.current_ = self.list_.tail_;
+    }
+
+    #[inline(always)]
+    /// Return true if the cursor is at an item, i.e. it has *NOT* moved past beginning or end
+    /// of the list
+    pub fn is_ok(&self) -> bool {
+        matches!(self.list_.nodes_.get(self.current_), Some(Some(_)))
+    }
+
+    #[inline(always)]
+    /// Return true if the cursor is at head position or if the list is empty
+    pub fn is_at_head(&self) -> bool {
+        self.current_ == self.list_.head_
+    }
+
+    #[inline(always)]
+    /// Return true if the cursor is at tail position or if the list is empty
+    pub fn is_at_tail(&self) -> bool {
+        self.current_ == self.list_.tail_
+    }
+}
+
+impl<'a, K, V, M> Cursor<'a, K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    #[inline(always)]
+    /// Iterates over the items from the current item to the tail
+    pub fn iter_from_here(&self) -> ListIterator<'a, K, V, M> {
+        self.list_.iter_from(self.current_)
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    #[inline(always)]
+    /// Returns a [`Cursor`] at the head of the list
+    pub fn cursor(&self) -> Cursor<'_, K, V, M> {
+        Cursor::new(self, self.head_)
+    }
+
+    /// Returns a [`Cursor`] positioned at the lower bound item, see
+    /// [`LinkedList::lower_bound()`]. is_ok() returns false if no item is found.
+    pub fn lower_bound_cursor<Q>(&self, key: &Q) -> Result<Cursor<'_, K, V, M>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let position = self.lower_bound(key)?;
+        Ok(Cursor::new(self, position.unwrap_or(OUT_OF_BOUNDS)))
+    }
+
+    /// Returns a [`Cursor`] positioned at the upper bound item, see
+    /// [`LinkedList::upper_bound()`]. is_ok() returns false if no item is found.
+    pub fn upper_bound_cursor<Q>(&self, key: &Q) -> Result<Cursor<'_, K, V, M>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let position = self.upper_bound(key)?;
+        Ok(Cursor::new(self, position.unwrap_or(OUT_OF_BOUNDS)))
+    }
+}
@@ -0,0 +1,123 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Two linked cursors working on a shared list from both ends.
+
+use crate::{LinkedList, MapError, PIterator, SharedList, OUT_OF_BOUNDS};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// A front and a back [`PIterator`] of the same list, for algorithms that trim the list from
+/// both ends at once. The front cursor starts at the head and the back cursor at the tail.
+///
+/// The cursors can be moved freely through the public fields, but items should be removed
+/// with [`CursorPair::remove_front()`] and [`CursorPair::remove_back()`]. These keep the
+/// other cursor from pointing at a removed item.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{CursorPair, LinkedList};
+/// # use std::{cell::RefCell, rc::Rc};
+/// let ll = Rc::new(RefCell::new(LinkedList::<i8, i8>::default()));
+/// for i in 0..10 {
+///     let _ = ll.borrow_mut().ordered_insert(i, i);
+/// }
+/// // prune everything outside of the window [3, 6]
+/// let mut pair = CursorPair::new(Rc::clone(&ll)).unwrap();
+/// while !pair.crossed().unwrap() && pair.front.get_k().unwrap() < 3 {
+///     let _ = pair.remove_front().unwrap();
+/// }
+/// while !pair.crossed().unwrap() && pair.back.get_k().unwrap() > 6 {
+///     let _ = pair.remove_back().unwrap();
+/// }
+/// assert_eq!(ll.borrow().iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+/// ```
+pub struct CursorPair<K, V, M = (), P = Rc<RefCell<LinkedList<K, V, M>>>>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// The cursor moving from the head towards the tail
+    pub front: PIterator<K, V, M, P>,
+    /// The cursor moving from the tail towards the head
+    pub back: PIterator<K, V, M, P>,
+}
+
+impl<K, V, M, P> CursorPair<K, V, M, P>
+where
+    K: Clone + Debug + Unpin + Ord + PartialOrd,
+    V: Clone + Debug + Unpin,
+    M: Default + Unpin,
+    P: SharedList<K, V, M>,
+{
+    /// Creates a front cursor at the head and a back cursor at the tail of the list
+    pub fn new(list: P) -> Result<Self, MapError> {
+        let (head, tail) = {
+            let list = list.try_read()?;
+            (list.head_, list.tail_)
+        };
+        Ok(Self {
+            front: PIterator::new_2(list.clone(), head),
+            back: PIterator::new_2(list, tail),
+        })
+    }
+
+    /// Returns true if both cursors point at the same item
+    pub fn meet(&self) -> Result<bool, MapError> {
+        Ok(self.front.current == self.back.current && self.front.is_ok()?)
+    }
+
+    /// Returns true if the front cursor has moved past the back cursor, i.e. there are no items
+    /// left between (and including) the two cursors. This is also the case if any of the cursors
+    /// has moved out of the list.
+    pub fn crossed(&self) -> Result<bool, MapError> {
+        let list = self.front.list.try_read()?;
+        Ok(
+            match (
+                list.get_k_opt(self.front.current),
+                list.get_k_opt(self.back.current),
+            ) {
+                (Some(front), Some(back)) => front > back,
+                _ => true,
+            },
+        )
+    }
+
+    /// Removes the item at the front cursor and moves the front cursor to the next item.
+    /// If the cursors met, both cursors are moved out of the list.
+    pub fn remove_front(&mut self) -> Result<(K, V), MapError> {
+        let met = self.meet()?;
+        let (_, rv, next) = self.front.list.try_write()?.remove__(self.front.current)?;
+        self.front.current = next;
+        if met {
+            self.front.current = OUT_OF_BOUNDS;
+            self.back.current = OUT_OF_BOUNDS;
+        }
+        Ok(rv)
+    }
+
+    /// Removes the item at the back cursor and moves the back cursor to the previous item.
+    /// If the cursors met, both cursors are moved out of the list.
+    pub fn remove_back(&mut self) -> Result<(K, V), MapError> {
+        let met = self.meet()?;
+        let (prev, rv, _) = self.back.list.try_write()?.remove__(self.back.current)?;
+        self.back.current = prev;
+        if met {
+            self.front.current = OUT_OF_BOUNDS;
+            self.back.current = OUT_OF_BOUNDS;
+        }
+        Ok(rv)
+    }
+
+    #[allow(clippy::type_complexity)]
+    /// Returns the two cursors, as (front, back)
+    pub fn into_inner(self) -> (PIterator<K, V, M, P>, PIterator<K, V, M, P>) {
+        (self.front, self.back)
+    }
+}
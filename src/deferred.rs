@@ -0,0 +1,161 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Inserts into a shared list that are buffered until the list is no longer borrowed.
+
+use crate::{InsertResult, LinkedList, MapError, PIterator, SharedList};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// A queue of inserts into a shared list, created by [`DeferredInserts::new()`] or
+/// [`PIterator::defer_inserts()`].
+///
+/// ordered_insert() only buffers the item, so it can be called while the list is borrowed, e.g.
+/// while walking it with iter(). The buffered items are inserted by [`DeferredInserts::flush()`].
+/// Dropping the queue does not insert anything: a list that is still borrowed or frozen at that
+/// point would lose the items without a trace. Call flush() (or [`DeferredInserts::discard()`])
+/// before the queue is dropped, debug builds panic if a non-empty queue is dropped.
+///
+/// The items are inserted in key order. As with ordered_insert(), inserting an already existing
+/// key is a NOP, and if the same key is buffered several times the first value is used.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{DeferredInserts, LinkedList};
+/// # use std::{cell::RefCell, rc::Rc};
+/// let ll = Rc::new(RefCell::new(LinkedList::<i8, i8>::default()));
+/// for i in 0..3 {
+///     let _ = ll.borrow_mut().ordered_insert(i, i);
+/// }
+/// let mut deferred = DeferredInserts::new(Rc::clone(&ll));
+/// for (k, v) in ll.borrow().iter() {
+///     deferred.ordered_insert(k + 10, *v);
+/// }
+/// assert_eq!(deferred.flush().unwrap(), 3);
+/// assert_eq!(ll.borrow().len(), 6);
+/// ```
+#[derive(Debug)]
+#[must_use = "the buffered items are only inserted by flush()"]
+pub struct DeferredInserts<K, V, M = (), P = Rc<RefCell<LinkedList<K, V, M>>>>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    P: SharedList<K, V, M>,
+{
+    list_: P,
+    queue_: Vec<(K, V)>,
+    phantom_: std::marker::PhantomData<fn() -> M>,
+}
+
+impl<K, V, M, P> DeferredInserts<K, V, M, P>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+    P: SharedList<K, V, M>,
+{
+    /// Creates an empty queue for the shared list. The list is not accessed.
+    pub fn new(list: P) -> Self {
+        Self {
+            list_: list,
+            queue_: Vec::new(),
+            phantom_: std::marker::PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    /// Buffers an item, see [`LinkedList::ordered_insert()`]
+    pub fn ordered_insert(&mut self, key: K, value: V) {
+        self.queue_.push((key, value));
+    }
+
+    #[inline(always)]
+    /// Returns the number of buffered items
+    pub fn len(&self) -> usize {
+        self.queue_.len()
+    }
+
+    #[inline(always)]
+    /// Returns true if there are no buffered items
+    pub fn is_empty(&self) -> bool {
+        self.queue_.is_empty()
+    }
+
+    /// Drops the buffered items without inserting them, and returns them in insertion order
+    pub fn discard(&mut self) -> Vec<(K, V)> {
+        std::mem::take(&mut self.queue_)
+    }
+
+    /// Inserts the buffered items and returns the number of items that were inserted, not
+    /// counting the NOPs.
+    /// Returns an error if the list is still borrowed or frozen, then nothing is inserted. If an
+    /// insert fails, e.g. with CapacityExceeded, that item is dropped and the items after it
+    /// stay in the queue.
+    pub fn flush(&mut self) -> Result<usize, MapError> {
+        if self.queue_.is_empty() {
+            return Ok(0);
+        }
+        let mut list = self.list_.try_write()?;
+        list.check_not_frozen_()?;
+        // the items are popped from the back in key order. Reversing before the stable sort
+        // makes the first of several equal keys come out first.
+        self.queue_.reverse();
+        self.queue_.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut inserted = 0;
+        let mut hint = None;
+        while let Some((key, value)) = self.queue_.pop() {
+            // each insert is hinted with the index of the previous one, strict hints don't
+            // apply to these internal hints
+            let rv = match hint {
+                Some(hint) => {
+                    let key = list.normalize_key(key);
+                    let (index, rejected) = list.insert_pos_(key, value, hint)?;
+                    InsertResult {
+                        index,
+                        inserted: rejected.is_none(),
+                    }
+                }
+                None => list.ordered_insert_result(key, value)?,
+            };
+            hint = Some(rv.index);
+            if rv.inserted {
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+}
+
+impl<K, V, M, P> Drop for DeferredInserts<K, V, M, P>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    P: SharedList<K, V, M>,
+{
+    fn drop(&mut self) {
+        debug_assert!(
+            self.queue_.is_empty() || std::thread::panicking(),
+            "DeferredInserts dropped with {} items that were never flushed",
+            self.queue_.len()
+        );
+    }
+}
+
+impl<K, V, M, P> PIterator<K, V, M, P>
+where
+    K: Clone + Debug + Unpin + Ord + PartialOrd,
+    V: Clone + Debug + Unpin,
+    M: Default + Unpin,
+    P: SharedList<K, V, M>,
+{
+    /// Returns an insert queue for the list of this pointer, see [`DeferredInserts`].
+    pub fn defer_inserts(&self) -> DeferredInserts<K, V, M, P> {
+        DeferredInserts::new(self.list.clone())
+    }
+}
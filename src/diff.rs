@@ -0,0 +1,92 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Differences between two versions of a list.
+
+use crate::LinkedList;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+/// One difference found by [`LinkedList::diff()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change<'a, K, V> {
+    /// The key only exists in the newer list
+    Inserted(&'a K, &'a V),
+    /// The key only exists in the older list
+    Removed(&'a K, &'a V),
+    /// The key exists in both lists, with different values. `key` is the key of the newer list.
+    Changed { key: &'a K, old: &'a V, new: &'a V },
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Compares this (older) list with a newer version of it, e.g. a clone taken as a checkpoint
+    /// and the live list. Returns the inserted, removed and changed items, in key order.
+    /// Both lists are traversed once, in parallel. The slot indices are not compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{Change, LinkedList};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..4 {
+    ///     let _ = ll.ordered_insert(i, i);
+    /// }
+    /// let checkpoint = ll.clone();
+    /// let _ = ll.pop_front();
+    /// let _ = ll.ordered_insert(5, 5);
+    /// *ll.get_or_default(2, ll.head()).unwrap() = 20;
+    /// assert_eq!(
+    ///     checkpoint.diff(&ll),
+    ///     vec![
+    ///         Change::Removed(&0, &0),
+    ///         Change::Changed { key: &2, old: &2, new: &20 },
+    ///         Change::Inserted(&5, &5),
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff<'a, M2>(&'a self, newer: &'a LinkedList<K, V, M2>) -> Vec<Change<'a, K, V>>
+    where
+        V: PartialEq,
+        M2: Default,
+    {
+        let mut rv = Vec::new();
+        let mut old_iter = self.iter().peekable();
+        let mut new_iter = newer.iter().peekable();
+        loop {
+            let order = match (old_iter.peek(), new_iter.peek()) {
+                (Some((old_k, _)), Some((new_k, _))) => old_k.cmp(new_k),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+            match order {
+                Ordering::Less => {
+                    // unwrap is safe, the item was peeked
+                    let (k, v) = old_iter.next().unwrap();
+                    rv.push(Change::Removed(k, v));
+                }
+                Ordering::Greater => {
+                    let (k, v) = new_iter.next().unwrap();
+                    rv.push(Change::Inserted(k, v));
+                }
+                Ordering::Equal => {
+                    let (_, old) = old_iter.next().unwrap();
+                    let (key, new) = new_iter.next().unwrap();
+                    if old != new {
+                        rv.push(Change::Changed { key, old, new });
+                    }
+                }
+            }
+        }
+        rv
+    }
+}
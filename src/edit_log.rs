@@ -0,0 +1,174 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reversible edit operations.
+//!
+//! Every recorded operation can be reverted so that the list is restored to the exact same state,
+//! including slot indices, the free index pool and head/tail. Reverting an operation returns the
+//! operation that re-does it.
+
+use crate::{LinkedList, MapError, Node, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+#[derive(Clone, Debug)]
+pub(crate) enum EditOp<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// A node was inserted at `index`. `appended` is true if the node vector grew.
+    Insert { index: usize, appended: bool },
+    /// A node was removed from `index`, `bookmarks` are the ids of the bookmarks that pointed
+    /// at it.
+    Remove {
+        index: usize,
+        prev: usize,
+        next: usize,
+        key: K,
+        value: V,
+        meta: M,
+        bookmarks: Vec<usize>,
+    },
+    /// The key at `index` was replaced, `key` is the other key.
+    ReplaceKey { index: usize, key: K },
+}
+
+/// The operations recorded by a transaction, oldest first
+pub(crate) type EditLog<K, V, M> = Vec<EditOp<K, V, M>>;
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Revert an operation, returns the operation that will undo the revert.
+    /// Operations must be reverted in the reverse order they were recorded.
+    pub(crate) fn revert_(&mut self, op: EditOp<K, V, M>) -> Result<EditOp<K, V, M>, MapError> {
+        match op {
+            EditOp::Insert { index, appended } => {
+                let bookmarks = self.bookmarks_at_(index);
+                let meta = self.take_meta(index)?;
+                let (prev, (key, value), next) = self.remove__(index)?;
+                // the slot of an undone insert is never a tombstone, and it goes back to
+                // where the insert took it from: the end of the free index pool
+                let _ = self.forget_tombstone_(index);
+                if let Some(pos) = self.id_pool_.iter().rposition(|i| *i == index) {
+                    let _ = self.id_pool_.remove(pos);
+                    self.id_pool_.push(index);
+                }
+                if appended {
+                    // undo the growth of the node vector as well
+                    if self.nodes_.len() != index + 1 || self.id_pool_.last() != Some(&index) {
+                        return Err(map_error!(
+                            InternalError,
+                            "Could not revert insert at index {}. {}:{}",
+                            index,
+                            file!(),
+                            line!()
+                        ));
+                    }
+                    let _ = self.nodes_.pop();
+                    let _ = self.id_pool_.pop();
+                }
+                Ok(EditOp::Remove {
+                    index,
+                    prev,
+                    next,
+                    key,
+                    value,
+                    meta,
+                    bookmarks,
+                })
+            }
+            EditOp::Remove {
+                index,
+                prev,
+                next,
+                key,
+                value,
+                meta,
+                bookmarks,
+            } => {
+                let appended = self.restore_node_(index, (prev, next), key, value, meta)?;
+                self.bookmarks_restored_(index, &bookmarks);
+                Ok(EditOp::Insert { index, appended })
+            }
+            EditOp::ReplaceKey { index, key } => {
+                let key = self.replace_key_(index, key)?;
+                Ok(EditOp::ReplaceKey { index, key })
+            }
+        }
+    }
+
+    /// Put a removed node, and its metadata, back at its old slot and relink it with its old
+    /// (prev, next) neighbours. Returns true if the node vector had to grow.
+    fn restore_node_(
+        &mut self,
+        index: usize,
+        (prev, next): (usize, usize),
+        key: K,
+        value: V,
+        meta: M,
+    ) -> Result<bool, MapError> {
+        let appended = index == self.nodes_.len();
+        if !appended && self.tombstones_.remove(&index).is_none() {
+            match self.id_pool_.iter().rposition(|i| *i == index) {
+                Some(pos) => {
+                    let _ = self.id_pool_.remove(pos);
+                }
+                None => {
+                    return Err(map_error!(
+                        InternalError,
+                        "Could not restore node at occupied index {}. {}:{}",
+                        index,
+                        file!(),
+                        line!()
+                    ))
+                }
+            }
+        }
+        let _ = self.replace_or_push_(
+            index,
+            Node {
+                prev_: prev,
+                next_: next,
+                key_: key,
+                value_: value,
+                meta_: meta,
+            },
+        );
+        if prev == OUT_OF_BOUNDS {
+            self.head_ = index;
+        } else if let Some(Some(node)) = self.nodes_.get_mut(prev) {
+            node.next_ = index;
+        }
+        if next == OUT_OF_BOUNDS {
+            self.tail_ = index;
+        } else if let Some(Some(node)) = self.nodes_.get_mut(next) {
+            node.prev_ = index;
+        }
+        Ok(appended)
+    }
+
+    /// Replace the key at index, returns the old key.
+    /// This will destroy the internal order of element if you replace an element with
+    /// something out of order.
+    pub(crate) fn replace_key_(&mut self, index: usize, key: K) -> Result<K, MapError> {
+        self.check_not_frozen_()?;
+        match self.nodes_.get_mut(index) {
+            Some(Some(node)) => Ok(std::mem::replace(&mut node.key_, key)),
+            _ => Err(map_error!(
+                InternalError,
+                "Invalid index {}. {}:{}",
+                index,
+                file!(),
+                line!()
+            )),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An entry API like the one of the std maps, searching only once.
+
+use crate::{LinkedList, MapError};
+use std::fmt::Debug;
+
+/// A view into a single item of a list, which may be vacant or occupied. Created by
+/// [`LinkedList::entry()`] or [`LinkedList::entry_pos()`].
+///
+/// Inserting into a vacant entry can fail (e.g. with a Frozen or a CapacityExceeded error), so
+/// the inserting methods return a Result.
+#[derive(Debug)]
+pub enum Entry<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// The key is in the list
+    Occupied(OccupiedEntry<'a, K, V, M>),
+    /// The key is not in the list
+    Vacant(VacantEntry<'a, K, V, M>),
+}
+
+/// An item that is in the list, see [`Entry`]
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a mut LinkedList<K, V, M>,
+    index_: usize,
+}
+
+/// A key that is not in the list, see [`Entry`]. The position of the key was found by the
+/// search, so inserting is O(1).
+#[derive(Debug)]
+pub struct VacantEntry<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a mut LinkedList<K, V, M>,
+    key_: K,
+    // the lower bound of the key, None if the key goes last
+    before_: Option<usize>,
+}
+
+impl<'a, K, V, M> Entry<'a, K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Returns the key of the entry
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to the value
+    pub fn or_insert(self, default: V) -> Result<&'a mut V, MapError> {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a mutable reference
+    /// to the value
+    pub fn or_insert_with<F>(self, default: F) -> Result<&'a mut V, MapError>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Inserts the default value if the entry is vacant, and returns a mutable reference to the
+    /// value
+    pub fn or_default(self) -> Result<&'a mut V, MapError>
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Runs `f` on the value if the entry is occupied
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, M> OccupiedEntry<'a, K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    #[inline(always)]
+    /// Returns the index of the item
+    pub fn index(&self) -> usize {
+        self.index_
+    }
+
+    /// Returns the key of the item
+    pub fn key(&self) -> &K {
+        // the index is always an active item
+        self.list_.get_k(self.index_).unwrap()
+    }
+
+    /// Returns the value of the item
+    pub fn get(&self) -> &V {
+        self.list_.get_v(self.index_).unwrap()
+    }
+
+    /// Returns a mutable reference to the value of the item
+    pub fn get_mut(&mut self) -> &mut V {
+        self.list_.get_v_mut(self.index_).unwrap()
+    }
+
+    /// Returns a mutable reference to the value of the item, bound to the lifetime of the list
+    pub fn into_mut(self) -> &'a mut V {
+        self.list_.get_v_mut(self.index_).unwrap()
+    }
+
+    /// Replaces the value of the item and returns the old value
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the item from the list and returns it
+    pub fn remove(self) -> Result<(K, V), MapError> {
+        Ok(self.list_.remove__(self.index_)?.1)
+    }
+}
+
+impl<'a, K, V, M> VacantEntry<'a, K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    #[inline(always)]
+    /// Returns the key that would be inserted
+    pub fn key(&self) -> &K {
+        &self.key_
+    }
+
+    #[inline(always)]
+    /// Returns the key
+    pub fn into_key(self) -> K {
+        self.key_
+    }
+
+    /// Inserts the item and returns its index
+    pub fn insert_index(self, value: V) -> Result<usize, MapError> {
+        Ok(self.insert_(value)?.1)
+    }
+
+    /// Inserts the item and returns a mutable reference to its value
+    pub fn insert(self, value: V) -> Result<&'a mut V, MapError> {
+        let (list, index) = self.insert_(value)?;
+        list.get_v_mut(index)
+    }
+
+    fn insert_(self, value: V) -> Result<(&'a mut LinkedList<K, V, M>, usize), MapError> {
+        let index = match self.before_ {
+            Some(before) => self.list_.insert_before_(before, self.key_, value)?,
+            None => self.list_.push_back_(self.key_, value)?,
+        };
+        Ok((self.list_, index))
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Returns the entry of `key`, for in place manipulation. The key is normalized and the list
+    /// is searched once, inserting into a vacant entry does not search again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<&str, u32>::default();
+    /// for word in ["b", "a", "b", "c", "b"] {
+    ///     *ll.entry(word).unwrap().or_insert(0).unwrap() += 1;
+    /// }
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&"a", &1), (&"b", &3), (&"c", &1)]);
+    /// let _ = ll.entry("a").unwrap().and_modify(|v| *v = 10).or_insert(0);
+    /// assert_eq!(ll.get_v(ll.find("a").unwrap()).unwrap(), &10);
+    /// ```
+    pub fn entry(&mut self, key: K) -> Result<Entry<'_, K, V, M>, MapError> {
+        let key = self.normalize_key(key);
+        self.maintain_jump_table_();
+        let position = self.jump_hint_(&key).unwrap_or(self.head_);
+        self.entry_(key, position)
+    }
+
+    /// Returns the index of the item with `key`, inserting the value returned by `f` if the key
+    /// is missing. Unlike ordered_insert(), the value is only built when it is inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, Vec<i8>>::default();
+    /// let index = ll.get_or_insert_with(1, || vec![1]).unwrap();
+    /// let again = ll.get_or_insert_with(1, || unreachable!()).unwrap();
+    /// assert_eq!(index, again);
+    /// assert_eq!(ll.get_v(index).unwrap(), &vec![1]);
+    /// ```
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> Result<usize, MapError>
+    where
+        F: FnOnce() -> V,
+    {
+        match self.entry(key)? {
+            Entry::Occupied(entry) => Ok(entry.index()),
+            Entry::Vacant(entry) => entry.insert_index(f()),
+        }
+    }
+
+    /// Same as [`LinkedList::entry()`] with a position hint
+    pub fn entry_pos(&mut self, key: K, position: usize) -> Result<Entry<'_, K, V, M>, MapError> {
+        let key = self.normalize_key(key);
+        self.entry_(key, position)
+    }
+
+    fn entry_(&mut self, key: K, position: usize) -> Result<Entry<'_, K, V, M>, MapError> {
+        let lower_bound = self.lower_bound_pos(&key, position)?;
+        Ok(match lower_bound {
+            Some(index) if self.get_k_opt(index) == Some(&key) => Entry::Occupied(OccupiedEntry {
+                list_: self,
+                index_: index,
+            }),
+            before => Entry::Vacant(VacantEntry {
+                list_: self,
+                key_: key,
+                before_: before,
+            }),
+        })
+    }
+}
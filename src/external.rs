@@ -0,0 +1,191 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Values stored in a caller owned Vec, parallel to the slots of the list.
+
+use crate::{LinkedList, MapError};
+use std::borrow::Borrow;
+use std::fmt::Debug;
+
+/// An ordered list of keys whose values live in a `Vec` owned by the caller. The value of the
+/// item at index `i` is `values[i]`, so the value store is parallel to the slots of the list.
+/// The list only manages the keys, the links and the slot indices, every method that touches a
+/// value takes the store as a parameter.
+///
+/// An insert writes the value to the slot of the new item, pushing it if the slot is new.
+/// remove() leaves the value in the store, it is overwritten when the slot is reused. It is up to
+/// the caller to always pass the same store, a store that is too short for the list is an error.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::ExternalValues;
+/// let mut values = Vec::<String>::new();
+/// let mut keys = ExternalValues::<i8>::default();
+/// let b = keys.ordered_insert(2, "b".to_string(), &mut values).unwrap();
+/// let a = keys.ordered_insert(1, "a".to_string(), &mut values).unwrap();
+/// assert_eq!(values[b], "b");
+/// assert_eq!(keys.iter(&values).collect::<Vec<_>>(), vec![(&1, &values[a]), (&2, &values[b])]);
+/// assert_eq!(keys.remove(a).unwrap(), 1);
+/// let c = keys.ordered_insert(3, "c".to_string(), &mut values).unwrap();
+/// assert_eq!((c, values.len()), (a, 2));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExternalValues<K>
+where
+    K: Debug,
+{
+    list_: LinkedList<K, ()>,
+}
+
+impl<K> ExternalValues<K>
+where
+    K: Debug + Ord + PartialOrd,
+{
+    /// Creates an empty list of keys
+    pub fn new() -> Self {
+        Self {
+            list_: LinkedList::default(),
+        }
+    }
+
+    #[inline(always)]
+    /// Read only access to the list of keys
+    pub fn list(&self) -> &LinkedList<K, ()> {
+        &self.list_
+    }
+
+    #[inline(always)]
+    /// Returns the list of keys, the values stay in the store
+    pub fn into_inner(self) -> LinkedList<K, ()> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Returns the number of items
+    pub fn len(&self) -> usize {
+        self.list_.len()
+    }
+
+    #[inline(always)]
+    /// Returns true if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.list_.is_empty()
+    }
+
+    #[inline(always)]
+    /// Returns the index of the item with `key`
+    pub fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        self.list_.find(key)
+    }
+
+    /// Returns the key of the item at index and its value in `values`
+    pub fn get<'v, V>(&self, index: usize, values: &'v [V]) -> Result<(&K, &'v V), MapError> {
+        let key = self.list_.get_k(index)?;
+        match values.get(index) {
+            Some(value) => Ok((key, value)),
+            None => Err(Self::short_store_(values.len(), index)),
+        }
+    }
+
+    /// Returns a mutable reference to the value of the item at index in `values`
+    pub fn get_v_mut<'v, V>(
+        &self,
+        index: usize,
+        values: &'v mut [V],
+    ) -> Result<&'v mut V, MapError> {
+        let _ = self.list_.get_k(index)?;
+        let len = values.len();
+        values
+            .get_mut(index)
+            .ok_or_else(|| Self::short_store_(len, index))
+    }
+
+    /// Iterates over the keys in sorted order, together with their values in `values`. The
+    /// iteration stops at the first item without a value in the store.
+    pub fn iter<'a, V>(&'a self, values: &'a [V]) -> impl Iterator<Item = (&'a K, &'a V)> + 'a {
+        let mut curr_index = self.list_.head_;
+        std::iter::from_fn(move || match self.list_.nodes_.get(curr_index) {
+            Some(Some(node)) => {
+                let value = values.get(curr_index)?;
+                curr_index = node.next_;
+                Some((&node.key_, value))
+            }
+            _ => None,
+        })
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`], the value is written to `values`. Inserting an
+    /// already existing key is a NOP, the store is not touched.
+    pub fn ordered_insert<V>(
+        &mut self,
+        key: K,
+        value: V,
+        values: &mut Vec<V>,
+    ) -> Result<usize, MapError> {
+        let key = self.list_.normalize_key(key);
+        let head = self.list_.head();
+        self.insert_(key, value, values, head)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], the value is written to `values`
+    pub fn ordered_insert_pos<V>(
+        &mut self,
+        key: K,
+        value: V,
+        values: &mut Vec<V>,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        let key = self.list_.normalize_key(key);
+        self.list_.check_hint_(&key, position)?;
+        self.insert_(key, value, values, position)
+    }
+
+    /// Inserts an already normalized key, the value is written to `values`
+    fn insert_<V>(
+        &mut self,
+        key: K,
+        value: V,
+        values: &mut Vec<V>,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        // check the store before the key is inserted
+        let next_index = self.list_.next_free_index();
+        if next_index > values.len() {
+            return Err(Self::short_store_(values.len(), next_index));
+        }
+        let (index, rejected) = self.list_.insert_pos_(key, (), position)?;
+        if rejected.is_none() {
+            if index == values.len() {
+                values.push(value);
+            } else {
+                values[index] = value;
+            }
+        }
+        Ok(index)
+    }
+
+    /// Removes the item at index and returns its key. The value is left in the store.
+    pub fn remove(&mut self, index: usize) -> Result<K, MapError> {
+        Ok(self.list_.remove__(index)?.1 .0)
+    }
+
+    /// Returns the error of a store without a value for the slot at index
+    fn short_store_(len: usize, index: usize) -> MapError {
+        map_error!(
+            InternalError,
+            "the value store has {} values, there is no value for the slot {}",
+            len,
+            index
+        )
+    }
+}
@@ -0,0 +1,81 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Removing the items that match a predicate while iterating.
+
+use crate::{LinkedList, MapError};
+use std::fmt::Debug;
+
+/// The iterator returned by [`LinkedList::extract_if()`]
+#[derive(Debug)]
+pub struct ExtractIf<'a, K, V, M, F>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a mut LinkedList<K, V, M>,
+    my_next_: usize,
+    pred_: F,
+}
+
+impl<K, V, M, F> Iterator for ExtractIf<'_, K, V, M, F>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    F: FnMut(&K, &mut V) -> bool,
+    M: Default,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        while let Some(Some(node)) = self.list_.nodes_.get_mut(self.my_next_) {
+            if (self.pred_)(&node.key_, &mut node.value_) {
+                // the list was checked for freezing when the iterator was created
+                let (_, rv, next) = self.list_.remove__(self.my_next_).ok()?;
+                self.my_next_ = next;
+                return Some(rv);
+            }
+            self.my_next_ = node.next_;
+        }
+        None
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+{
+    /// Returns an iterator that removes and yields the items for which `pred` returns true, in
+    /// sorted order. The complement of [`LinkedList::retain()`].
+    /// `pred` may change the values. Items that are not reached because the iterator is dropped
+    /// early are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..6 {
+    ///     let _ = ll.ordered_insert(i, i);
+    /// }
+    /// let odd = ll.extract_if(|k, _| k % 2 == 1).unwrap().collect::<Vec<_>>();
+    /// assert_eq!(odd, vec![(1, 1), (3, 3), (5, 5)]);
+    /// assert_eq!(ll.len(), 3);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> Result<ExtractIf<'_, K, V, M, F>, MapError>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.check_not_frozen_()?;
+        Ok(ExtractIf {
+            my_next_: self.head_,
+            list_: self,
+            pred_: pred,
+        })
+    }
+}
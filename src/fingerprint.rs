@@ -0,0 +1,250 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Cached sort fingerprints, deciding most key comparisons with one float compare.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+type FingerprintFn<K> = Box<dyn Fn(&K) -> f64 + Send + Sync>;
+
+/// A [`LinkedList`] with a cached sort fingerprint per item, for keys that are expensive to
+/// compare. The fingerprint is a cheap projection of the key, e.g. the first field of a
+/// composite key as an f64. The searches of the wrapper compare the fingerprints first, with
+/// `f64::total_cmp()`, and only compare the keys when the fingerprints are equal.
+///
+/// The projection must follow the order of the keys: if `a < b` then
+/// `fingerprint(a) <= fingerprint(b)`. The fingerprints are kept in a vector parallel to the
+/// slots of the list, kept in sync by the mutating methods of the wrapper.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{Fingerprinted, LinkedList};
+/// // (priority, name)
+/// let mut ll = Fingerprinted::new(LinkedList::<(u32, String), ()>::default(), |k| k.0 as f64);
+/// let _ = ll.ordered_insert((2, "b".to_string()), ());
+/// let _ = ll.ordered_insert((1, "z".to_string()), ());
+/// let _ = ll.ordered_insert((2, "a".to_string()), ());
+/// let keys = ll.list().iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+/// assert_eq!(keys, vec![(1, "z".to_string()), (2, "a".to_string()), (2, "b".to_string())]);
+/// assert!(ll.find(&(1, "z".to_string())).is_some());
+/// ```
+pub struct Fingerprinted<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: LinkedList<K, V>,
+    // slot index -> fingerprint of the key
+    fingerprints_: Vec<f64>,
+    fingerprint_: FingerprintFn<K>,
+}
+
+impl<K, V> Debug for Fingerprinted<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fingerprinted")
+            .field("list_", &self.list_)
+            .field("fingerprints_", &self.fingerprints_)
+            .finish()
+    }
+}
+
+impl<K, V> Fingerprinted<K, V>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+{
+    /// Wraps a list, the fingerprints of the current items are computed
+    pub fn new<F>(list: LinkedList<K, V>, fingerprint: F) -> Self
+    where
+        F: Fn(&K) -> f64 + Send + Sync + 'static,
+    {
+        let fingerprints_ = list
+            .nodes_
+            .iter()
+            .map(|n| n.as_ref().map_or(0.0, |n| fingerprint(&n.key_)))
+            .collect();
+        Self {
+            list_: list,
+            fingerprints_,
+            fingerprint_: Box::new(fingerprint),
+        }
+    }
+
+    #[inline(always)]
+    /// Read only access to the list
+    pub fn list(&self) -> &LinkedList<K, V> {
+        &self.list_
+    }
+
+    #[inline(always)]
+    /// Drops the fingerprints and returns the list
+    pub fn into_inner(self) -> LinkedList<K, V> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Returns the cached fingerprint of the item at index
+    pub fn fingerprint(&self, index: usize) -> Option<f64> {
+        self.list_.get_opt(index)?;
+        self.fingerprints_.get(index).copied()
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::get_v_mut()`], the values are not fingerprinted
+    pub fn get_v_mut(&mut self, index: usize) -> Result<&mut V, MapError> {
+        self.list_.get_v_mut(index)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::lower_bound()`], comparing the fingerprints first
+    pub fn lower_bound(&self, key: &K) -> Option<usize> {
+        self.lower_bound_(key, (self.fingerprint_)(key), OUT_OF_BOUNDS)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::lower_bound_pos()`], comparing the fingerprints first
+    pub fn lower_bound_pos(&self, key: &K, position: usize) -> Option<usize> {
+        self.lower_bound_(key, (self.fingerprint_)(key), position)
+    }
+
+    /// Returns the index of the item with `key`, comparing the fingerprints first
+    pub fn find(&self, key: &K) -> Option<usize> {
+        let fingerprint = (self.fingerprint_)(key);
+        self.lower_bound_(key, fingerprint, OUT_OF_BOUNDS)
+            .filter(|index| self.cmp_(key, fingerprint, *index) == Ordering::Equal)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`], comparing the fingerprints first
+    pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        let key = self.list_.normalize_key(key);
+        self.insert_(key, value, OUT_OF_BOUNDS)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], comparing the fingerprints first.
+    /// Inserting an already existing key is a NOP.
+    pub fn ordered_insert_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        let key = self.list_.normalize_key(key);
+        self.list_.check_hint_(&key, position)?;
+        self.insert_(key, value, position)
+    }
+
+    /// Inserts an already normalized key, and caches its fingerprint
+    fn insert_(&mut self, key: K, value: V, position: usize) -> Result<usize, MapError> {
+        let fingerprint = (self.fingerprint_)(&key);
+        let index = match self.lower_bound_(&key, fingerprint, position) {
+            Some(index) if self.cmp_(&key, fingerprint, index) == Ordering::Equal => {
+                return Ok(index)
+            }
+            Some(index) => self.list_.insert_before_(index, key, value)?,
+            None => self.list_.push_back_(key, value)?,
+        };
+        self.set_fingerprint_(index, fingerprint);
+        Ok(index)
+    }
+
+    /// Replace the key at index and its fingerprint. Note that the new key must keep the list
+    /// in order.
+    pub fn replace_key(&mut self, index: usize, key: K) -> Result<(), MapError> {
+        let fingerprint = (self.fingerprint_)(&key);
+        let _ = self.list_.replace_key_(index, key)?;
+        self.set_fingerprint_(index, fingerprint);
+        Ok(())
+    }
+
+    #[inline(always)]
+    /// Removes the item at index and returns it
+    pub fn remove(&mut self, index: usize) -> Result<(K, V), MapError> {
+        Ok(self.list_.remove__(index)?.1)
+    }
+
+    /// Same as [`LinkedList::pop_front()`]
+    pub fn pop_front(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let head = self.list_.head_;
+        Ok(Some(self.remove(head)?))
+    }
+
+    /// Same as [`LinkedList::pop_back()`]
+    pub fn pop_back(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let tail = self.list_.tail_;
+        Ok(Some(self.remove(tail)?))
+    }
+
+    fn set_fingerprint_(&mut self, index: usize, fingerprint: f64) {
+        if index >= self.fingerprints_.len() {
+            self.fingerprints_.resize(index + 1, 0.0);
+        }
+        self.fingerprints_[index] = fingerprint;
+    }
+
+    /// Compares `key` with the key of the active item at index, the fingerprints first
+    #[inline(always)]
+    fn cmp_(&self, key: &K, fingerprint: f64, index: usize) -> Ordering {
+        match fingerprint.total_cmp(&self.fingerprints_[index]) {
+            Ordering::Equal => match self.list_.nodes_.get(index) {
+                Some(Some(node)) => key.cmp(&node.key_),
+                _ => Ordering::Equal,
+            },
+            order => order,
+        }
+    }
+
+    /// Returns the first item that does not go before `key`, searching from `position`, or
+    /// from the tail if the position is not an active item
+    fn lower_bound_(&self, key: &K, fingerprint: f64, position: usize) -> Option<usize> {
+        let position = if self.list_.get_opt(position).is_some() {
+            position
+        } else {
+            self.list_.tail_
+        };
+        let start = match self.list_.nodes_.get(position) {
+            Some(Some(node)) => node,
+            _ => return None,
+        };
+        if self.cmp_(key, fingerprint, position) != Ordering::Greater {
+            // search up the list, stop at the first key that goes before `key`
+            let mut last_match = position;
+            let mut curr_index = start.prev_;
+            while let Some(Some(sample)) = self.list_.nodes_.get(curr_index) {
+                if self.cmp_(key, fingerprint, curr_index) == Ordering::Greater {
+                    break;
+                }
+                last_match = curr_index;
+                curr_index = sample.prev_;
+            }
+            Some(last_match)
+        } else {
+            // search down the list, stop at the first key that does not go before `key`
+            let mut curr_index = start.next_;
+            while let Some(Some(sample)) = self.list_.nodes_.get(curr_index) {
+                if self.cmp_(key, fingerprint, curr_index) != Ordering::Greater {
+                    return Some(curr_index);
+                }
+                curr_index = sample.next_;
+            }
+            None
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Fixed capacities that are checked when the program is built.
+
+use crate::{LinkedList, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+/// Compile time checks of a fixed capacity `N`, see [`LinkedList::with_fixed_capacity()`].
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::FixedCapacity;
+/// // the indices 0..256 fit in a u8, this line does not build with a capacity of 257
+/// const _: () = assert!(FixedCapacity::<256>::index_fits(u8::BITS));
+/// assert!(!FixedCapacity::<257>::index_fits(u8::BITS));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedCapacity<const N: usize>;
+
+impl<const N: usize> FixedCapacity<N> {
+    /// The capacity `N`. Using this constant fails the build if `N` is zero or if `N` items
+    /// would need the reserved OUT_OF_BOUNDS index.
+    pub const CHECKED: usize = {
+        assert!(N > 0, "a fixed capacity must not be zero");
+        assert!(
+            N < OUT_OF_BOUNDS,
+            "a fixed capacity must be less than OUT_OF_BOUNDS"
+        );
+        N
+    };
+
+    /// Returns true if every index of a list with capacity `N` fits in an unsigned integer of
+    /// `bits` bits, e.g. u16::BITS. Use it in a const assertion to check the index type of a
+    /// device at build time.
+    pub const fn index_fits(bits: u32) -> bool {
+        bits >= usize::BITS || (N as u128) <= (1_u128 << bits)
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// Same as [`LinkedList::with_max_capacity()`] with a capacity checked at build time, see
+    /// [`FixedCapacity`]. Nothing is allocated, so this can be used in const contexts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// const EMPTY: LinkedList<i8, i8> = LinkedList::with_fixed_capacity::<2>();
+    /// let mut ll = EMPTY;
+    /// assert_eq!(ll.max_capacity(), Some(2));
+    /// let _ = ll.ordered_insert(1, 1);
+    /// let _ = ll.ordered_insert(2, 2);
+    /// assert!(ll.ordered_insert(3, 3).is_err());
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use cpp_map::LinkedList;
+    /// const EMPTY: LinkedList<i8, i8> = LinkedList::with_fixed_capacity::<0>();
+    /// ```
+    pub const fn with_fixed_capacity<const N: usize>() -> Self {
+        let mut list = Self::new();
+        list.max_capacity_ = Some(FixedCapacity::<N>::CHECKED);
+        list
+    }
+}
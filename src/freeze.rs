@@ -0,0 +1,128 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Freezing a shared list, so that structural mutations fail while the list is iterated.
+
+use crate::{LinkedList, MapError, PIterator, SharedList};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The number of live [`FreezeGuard`]s of a list. The counter is only allocated when the
+/// first guard is taken, a cloned list starts out unfrozen.
+#[derive(Debug, Default)]
+pub(crate) struct FreezeCount(Option<Arc<AtomicUsize>>);
+
+impl FreezeCount {
+    pub(crate) const fn new() -> Self {
+        Self(None)
+    }
+}
+
+impl Clone for FreezeCount {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+/// An RAII guard that keeps a shared list frozen, created by [`FreezeGuard::new()`] or
+/// [`PIterator::freeze()`].
+///
+/// While a guard is alive every operation that inserts, removes or replaces keys fails with a
+/// `Frozen` error, no matter which `Rc` clone or [`PIterator`] it is called through.
+/// This includes try_clear(), optimize() and purge_tombstones(), they would invalidate the
+/// positions of the PIterators. The infallible clear() does not check the guard. Reading, moving PIterators and changing values is still allowed.
+/// The list is unfrozen when the last guard is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{FreezeGuard, LinkedList, MapErrorKind, PIterator};
+/// # use std::{cell::RefCell, rc::Rc};
+/// let ll = Rc::new(RefCell::new(LinkedList::<i8, i8>::default()));
+/// let _ = ll.borrow_mut().ordered_insert(1, 1);
+/// let mut p = PIterator::new(Rc::clone(&ll)).unwrap();
+/// {
+///     let _guard = p.freeze().unwrap();
+///     assert!(ll.borrow().is_frozen());
+///     let err = ll.borrow_mut().ordered_insert(2, 2).unwrap_err();
+///     assert_eq!(err.kind(), MapErrorKind::Frozen);
+///     assert!(p.remove_current().is_err());
+/// }
+/// assert!(!ll.borrow().is_frozen());
+/// assert!(ll.borrow_mut().ordered_insert(2, 2).is_ok());
+/// ```
+#[derive(Debug)]
+pub struct FreezeGuard {
+    count_: Arc<AtomicUsize>,
+}
+
+impl FreezeGuard {
+    /// Freezes the shared list until the guard is dropped.
+    /// The list must not be borrowed when the guard is created.
+    pub fn new<K, V, M, P>(list: &P) -> Result<Self, MapError>
+    where
+        K: Debug,
+        V: Debug,
+        P: SharedList<K, V, M>,
+    {
+        let mut list = list.try_write()?;
+        let count = Arc::clone(
+            list.freeze_count_
+                .0
+                .get_or_insert_with(|| Arc::new(AtomicUsize::new(0))),
+        );
+        let _ = count.fetch_add(1, Ordering::Relaxed);
+        Ok(Self { count_: count })
+    }
+}
+
+impl Drop for FreezeGuard {
+    fn drop(&mut self) {
+        let _ = self.count_.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    #[inline(always)]
+    /// Returns true if there is a live [`FreezeGuard`] for this list
+    pub fn is_frozen(&self) -> bool {
+        match &self.freeze_count_.0 {
+            Some(count) => count.load(Ordering::Relaxed) > 0,
+            None => false,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns a Frozen error if there is a live [`FreezeGuard`] for this list
+    pub(crate) fn check_not_frozen_(&self) -> Result<(), MapError> {
+        if self.is_frozen() {
+            return Err(map_error!(
+                Frozen,
+                "the list can't be modified while a FreezeGuard is alive"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, M, P> PIterator<K, V, M, P>
+where
+    K: Clone + Debug + Unpin + Ord + PartialOrd,
+    V: Clone + Debug + Unpin,
+    M: Unpin,
+    P: SharedList<K, V, M>,
+{
+    /// Freezes the list of this pointer, see [`FreezeGuard`].
+    pub fn freeze(&self) -> Result<FreezeGuard, MapError> {
+        FreezeGuard::new(&self.list)
+    }
+}
@@ -0,0 +1,188 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Undo/redo history for a [`LinkedList`].
+
+use crate::edit_log::EditLog;
+use crate::{Editor, LinkedList, MapError};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// A [`LinkedList`] that records the inverse of every mutation, so that the most recent
+/// mutations can be undone and redone.
+/// Only the inverse operations are stored, not copies of the list.
+///
+/// Undo and redo restores the exact slot indices, so indices remain valid across undo()/redo().
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{History, LinkedList};
+/// let mut h = History::new(LinkedList::<i8, i8>::default(), 10);
+/// let _ = h.ordered_insert(1, 1);
+/// let _ = h.edit(|editor| {
+///     let _ = editor.ordered_insert(2, 2)?;
+///     editor.ordered_insert(3, 3)
+/// });
+/// assert_eq!(h.list().len(), 3);
+/// assert!(h.undo().unwrap());
+/// assert_eq!(h.list().len(), 1);
+/// assert!(h.redo().unwrap());
+/// assert_eq!(h.list().len(), 3);
+/// ```
+#[derive(Debug)]
+pub struct History<K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: LinkedList<K, V, M>,
+    undo_: VecDeque<EditLog<K, V, M>>,
+    redo_: Vec<EditLog<K, V, M>>,
+    depth_: usize,
+}
+
+impl<K, V, M> History<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Wraps a list, at most `depth` steps will be remembered.
+    pub fn new(list: LinkedList<K, V, M>, depth: usize) -> Self {
+        Self {
+            list_: list,
+            undo_: VecDeque::new(),
+            redo_: Vec::new(),
+            depth_: depth,
+        }
+    }
+
+    #[inline(always)]
+    /// Read only access to the list
+    pub fn list(&self) -> &LinkedList<K, V, M> {
+        &self.list_
+    }
+
+    #[inline(always)]
+    /// Drops the history and returns the list
+    pub fn into_inner(self) -> LinkedList<K, V, M> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Returns the max number of steps remembered
+    pub fn depth(&self) -> usize {
+        self.depth_
+    }
+
+    /// Set the max number of steps remembered, the oldest steps are dropped if needed.
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth_ = depth;
+        while self.undo_.len() > depth {
+            let _ = self.undo_.pop_front();
+        }
+        self.redo_.truncate(depth);
+    }
+
+    #[inline(always)]
+    /// Returns the number of steps that can be undone
+    pub fn undo_len(&self) -> usize {
+        self.undo_.len()
+    }
+
+    #[inline(always)]
+    /// Returns the number of steps that can be redone
+    pub fn redo_len(&self) -> usize {
+        self.redo_.len()
+    }
+
+    /// Forget all recorded steps
+    pub fn clear_history(&mut self) {
+        self.undo_.clear();
+        self.redo_.clear();
+    }
+
+    /// Run a batch of edits as one undo step. See [`LinkedList::transaction()`].
+    pub fn edit<R, F>(&mut self, f: F) -> Result<R, MapError>
+    where
+        F: FnOnce(&mut Editor<'_, K, V, M>) -> Result<R, MapError>,
+    {
+        let (rv, log) = self.list_.transaction_(f)?;
+        if !log.is_empty() {
+            self.redo_.clear();
+            if self.depth_ > 0 {
+                if self.undo_.len() == self.depth_ {
+                    let _ = self.undo_.pop_front();
+                }
+                self.undo_.push_back(log);
+            }
+        }
+        Ok(rv)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`], recorded as one undo step.
+    pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        self.edit(|editor| editor.ordered_insert(key, value))
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert_pos()`], recorded as one undo step.
+    pub fn ordered_insert_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        self.edit(|editor| editor.ordered_insert_pos(key, value, position))
+    }
+
+    #[inline(always)]
+    /// Same as [`Editor::replace_key()`], recorded as one undo step.
+    pub fn replace_key(&mut self, index: usize, key: K) -> Result<(), MapError> {
+        self.edit(|editor| editor.replace_key(index, key))
+    }
+
+    #[inline(always)]
+    /// Same as [`Editor::remove()`], recorded as one undo step. The removed item is kept in the
+    /// undo step, so nothing is returned.
+    pub fn remove(&mut self, index: usize) -> Result<(), MapError> {
+        self.edit(|editor| editor.remove(index).map(|_| ()))
+    }
+
+    /// Undo the most recent step. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> Result<bool, MapError> {
+        if let Some(step) = self.undo_.pop_back() {
+            let step = self.revert_step_(step)?;
+            self.redo_.push(step);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Redo the most recently undone step. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> Result<bool, MapError> {
+        if let Some(step) = self.redo_.pop() {
+            let step = self.revert_step_(step)?;
+            self.undo_.push_back(step);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Revert the operations of a step, newest first. Returns the step that reverts this revert.
+    fn revert_step_(&mut self, mut step: EditLog<K, V, M>) -> Result<EditLog<K, V, M>, MapError> {
+        let mut inverse = Vec::with_capacity(step.len());
+        while let Some(op) = step.pop() {
+            inverse.push(self.list_.revert_(op)?);
+        }
+        Ok(inverse)
+    }
+}
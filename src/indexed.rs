@@ -0,0 +1,232 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A secondary index on a projection of the keys of a [`LinkedList`].
+
+use crate::{LinkedList, MapError};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+type Projection<K, P> = Box<dyn Fn(&K) -> P + Send + Sync>;
+
+/// A [`LinkedList`] with a secondary index from a projection of the keys, e.g. an entity id
+/// extracted from a composite key, to the index of the item. The index is a [`HashMap`] kept in
+/// sync by the mutating methods of the wrapper, so a lookup by projection is O(1).
+///
+/// Every item must have a unique projection, inserting a key that projects to the same value as
+/// another item is an error.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{Indexed, LinkedList};
+/// // (time, entity id)
+/// let mut events = Indexed::new(LinkedList::<(u32, u32), &str>::default(), |k| k.1).unwrap();
+/// let _ = events.ordered_insert((20, 7), "seven");
+/// let _ = events.ordered_insert((10, 3), "three");
+/// assert_eq!(events.get_by(&7), Some((&(20, 7), &"seven")));
+/// let _ = events.pop_front();
+/// assert_eq!(events.find_by(&3), None);
+/// assert!(events.ordered_insert((30, 7), "seven again").is_err());
+/// ```
+pub struct Indexed<K, V, P>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: LinkedList<K, V>,
+    index_: HashMap<P, usize>,
+    projection_: Projection<K, P>,
+}
+
+impl<K, V, P> Debug for Indexed<K, V, P>
+where
+    K: Debug,
+    V: Debug,
+    P: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Indexed")
+            .field("list_", &self.list_)
+            .field("index_", &self.index_)
+            .finish()
+    }
+}
+
+impl<K, V, P> Indexed<K, V, P>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    P: Debug + Hash + Eq,
+{
+    /// Wraps a list, the index is built from the current content of the list.
+    /// Returns an error if two items of the list have the same projection.
+    pub fn new<F>(list: LinkedList<K, V>, projection: F) -> Result<Self, MapError>
+    where
+        F: Fn(&K) -> P + Send + Sync + 'static,
+    {
+        let mut index_ = HashMap::with_capacity(list.len());
+        let mut curr_index = list.head_;
+        while let Some(Some(node)) = list.nodes_.get(curr_index) {
+            if let Some(other) = index_.insert(projection(&node.key_), curr_index) {
+                return Err(map_error!(
+                    InternalError,
+                    "the items at {} and {} have the same projection",
+                    other,
+                    curr_index
+                ));
+            }
+            curr_index = node.next_;
+        }
+        Ok(Self {
+            list_: list,
+            index_,
+            projection_: Box::new(projection),
+        })
+    }
+
+    #[inline(always)]
+    /// Read only access to the list
+    pub fn list(&self) -> &LinkedList<K, V> {
+        &self.list_
+    }
+
+    #[inline(always)]
+    /// Drops the index and returns the list
+    pub fn into_inner(self) -> LinkedList<K, V> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Returns the index of the item with the projection `projected`
+    pub fn find_by<Q>(&self, projected: &Q) -> Option<usize>
+    where
+        P: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index_.get(projected).copied()
+    }
+
+    #[inline(always)]
+    /// Returns the item with the projection `projected`
+    pub fn get_by<Q>(&self, projected: &Q) -> Option<(&K, &V)>
+    where
+        P: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.list_.get_opt(self.find_by(projected)?)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::get_v_mut()`], the values are not part of the index
+    pub fn get_v_mut(&mut self, index: usize) -> Result<&mut V, MapError> {
+        self.list_.get_v_mut(index)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`], indexed
+    pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        let key = self.list_.normalize_key(key);
+        let head = self.list_.head_;
+        self.insert_(key, value, head)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], indexed. Inserting an already existing key
+    /// is a NOP, inserting a new key with the projection of another item is an error.
+    pub fn ordered_insert_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        let key = self.list_.normalize_key(key);
+        self.list_.check_hint_(&key, position)?;
+        self.insert_(key, value, position)
+    }
+
+    /// Inserts an already normalized key, indexed
+    fn insert_(&mut self, key: K, value: V, position: usize) -> Result<usize, MapError> {
+        let projected = (self.projection_)(&key);
+        if let Some(index) = self.index_.get(&projected) {
+            if self.list_.get_k_opt(*index) == Some(&key) {
+                return Ok(*index);
+            }
+            return Err(map_error!(
+                InternalError,
+                "the projection {:?} of {:?} already indexes the item at {}",
+                projected,
+                key,
+                index
+            ));
+        }
+        let (index, rejected) = self.list_.insert_pos_(key, value, position)?;
+        if rejected.is_none() {
+            let _ = self.index_.insert(projected, index);
+        }
+        Ok(index)
+    }
+
+    /// Replace the key at index, indexed. Note that the new key must keep the list in order.
+    pub fn replace_key(&mut self, index: usize, key: K) -> Result<(), MapError> {
+        let projected = (self.projection_)(&key);
+        match self.index_.get(&projected) {
+            Some(other) if *other != index => {
+                return Err(map_error!(
+                    InternalError,
+                    "the projection {:?} of {:?} already indexes the item at {}",
+                    projected,
+                    key,
+                    other
+                ))
+            }
+            _ => (),
+        }
+        let old_key = self.list_.replace_key_(index, key)?;
+        let _ = self.index_.remove(&(self.projection_)(&old_key));
+        let _ = self.index_.insert(projected, index);
+        Ok(())
+    }
+
+    /// Remove the item at index and return it, indexed.
+    pub fn remove(&mut self, index: usize) -> Result<(K, V), MapError> {
+        let (_, (key, value), _) = self.list_.remove__(index)?;
+        let _ = self.index_.remove(&(self.projection_)(&key));
+        Ok((key, value))
+    }
+
+    /// Remove the item with the projection `projected` and return it, indexed.
+    pub fn remove_by<Q>(&mut self, projected: &Q) -> Result<Option<(K, V)>, MapError>
+    where
+        P: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.find_by(projected) {
+            Some(index) => Ok(Some(self.remove(index)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Same as [`LinkedList::pop_front()`], indexed
+    pub fn pop_front(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let head = self.list_.head_;
+        Ok(Some(self.remove(head)?))
+    }
+
+    /// Same as [`LinkedList::pop_back()`], indexed
+    pub fn pop_back(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let tail = self.list_.tail_;
+        Ok(Some(self.remove(tail)?))
+    }
+}
@@ -0,0 +1,142 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A handle to a just inserted item, for fixing up the item and its neighbours.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+/// The item found or inserted by [`LinkedList::ordered_insert_entry()`]. The handle borrows
+/// the list, so the item and its neighbours can be read and changed without new lookups.
+#[derive(Debug)]
+pub struct InsertedEntry<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a mut LinkedList<K, V, M>,
+    index_: usize,
+    inserted_: bool,
+}
+
+impl<'a, K, V, M> InsertedEntry<'a, K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    #[inline(always)]
+    /// Returns the index of the item
+    pub fn index(&self) -> usize {
+        self.index_
+    }
+
+    #[inline(always)]
+    /// Returns true if the item was inserted, false if the key already existed. In that case
+    /// the list was not modified and the handle refers to the existing item.
+    pub fn inserted(&self) -> bool {
+        self.inserted_
+    }
+
+    /// Returns the key and a mutable reference to the value of the item
+    pub fn get_mut(&mut self) -> (&K, &mut V) {
+        // the index is always an active item
+        self.list_.get_pair_mut(self.index_).unwrap()
+    }
+
+    /// Returns the index of the previous item, or None if the item is the head
+    pub fn prev(&self) -> Option<usize> {
+        Some(self.list_.current_node_(self.index_).ok()?.prev_).filter(|i| *i != OUT_OF_BOUNDS)
+    }
+
+    /// Returns the index of the next item, or None if the item is the tail
+    pub fn next(&self) -> Option<usize> {
+        Some(self.list_.current_node_(self.index_).ok()?.next_).filter(|i| *i != OUT_OF_BOUNDS)
+    }
+
+    /// Returns the key and a mutable reference to the value of the previous item
+    pub fn prev_mut(&mut self) -> Option<(&K, &mut V)> {
+        let prev = self.prev()?;
+        self.list_.get_pair_mut(prev).ok()
+    }
+
+    /// Returns the key and a mutable reference to the value of the next item
+    pub fn next_mut(&mut self) -> Option<(&K, &mut V)> {
+        let next = self.next()?;
+        self.list_.get_pair_mut(next).ok()
+    }
+
+    #[inline(always)]
+    /// Returns the list
+    pub fn into_list(self) -> &'a mut LinkedList<K, V, M> {
+        self.list_
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Same as [`LinkedList::ordered_insert()`], but returns a handle to the inserted item (or
+    /// to the existing item with the same key) that gives access to the item and its
+    /// neighbours.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// // the value is the distance to the next key
+    /// let mut ll = LinkedList::<i32, i32>::default();
+    /// let _ = ll.ordered_insert(0, 10);
+    /// let _ = ll.ordered_insert(10, 0);
+    /// let mut entry = ll.ordered_insert_entry(4, 0).unwrap();
+    /// assert!(entry.inserted());
+    /// *entry.get_mut().1 = 6;
+    /// *entry.prev_mut().unwrap().1 = 4;
+    /// assert_eq!(entry.next(), Some(1));
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&0, &4), (&4, &6), (&10, &0)]);
+    /// ```
+    pub fn ordered_insert_entry(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<InsertedEntry<'_, K, V, M>, MapError> {
+        let key = self.normalize_key(key);
+        self.maintain_jump_table_();
+        let position = self.jump_hint_(&key).unwrap_or(self.head_);
+        self.insert_entry_(key, value, position)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], but returns a handle to the inserted item.
+    /// See [`LinkedList::ordered_insert_entry()`].
+    pub fn ordered_insert_entry_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<InsertedEntry<'_, K, V, M>, MapError> {
+        let key = self.normalize_key(key);
+        self.check_hint_(&key, position)?;
+        self.insert_entry_(key, value, position)
+    }
+
+    fn insert_entry_(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<InsertedEntry<'_, K, V, M>, MapError> {
+        let (index, rejected) = self.insert_pos_(key, value, position)?;
+        Ok(InsertedEntry {
+            list_: self,
+            index_: index,
+            inserted_: rejected.is_none(),
+        })
+    }
+}
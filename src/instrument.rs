@@ -0,0 +1,54 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Metrics emitted through the `metrics` crate facade, with the `metrics` feature.
+//! Without the feature every function is an empty inline function.
+//!
+//! | name | kind | labels |
+//! |---|---|---|
+//! | `cpp_map_inserts_total` | counter | |
+//! | `cpp_map_removals_total` | counter | |
+//! | `cpp_map_scan_length` | histogram | `operation`: `insert` or `lower_bound` |
+//! | `cpp_map_borrow_failures_total` | counter | |
+
+use crate::ScanOperation;
+
+#[inline(always)]
+/// An item was inserted
+pub(crate) fn inserted() {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!("cpp_map_inserts_total").increment(1);
+}
+
+#[inline(always)]
+/// An item was removed
+pub(crate) fn removed() {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!("cpp_map_removals_total").increment(1);
+}
+
+#[inline(always)]
+/// A search visited `hops` nodes
+pub(crate) fn scanned(operation: ScanOperation, hops: usize) {
+    #[cfg(feature = "metrics")]
+    {
+        let operation = match operation {
+            ScanOperation::Insert => "insert",
+            ScanOperation::LowerBound => "lower_bound",
+        };
+        ::metrics::histogram!("cpp_map_scan_length", "operation" => operation).record(hops as f64);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (operation, hops);
+}
+
+#[inline(always)]
+/// A shared list could not be accessed
+pub(crate) fn borrow_failed() {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!("cpp_map_borrow_failures_total").increment(1);
+}
@@ -0,0 +1,121 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Starting position guesses for numeric keys, for when no good position hint is available.
+
+use crate::{LinkedList, MapError, TotalF32, TotalF64, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+/// Keys that can be converted to f64, used by [`LinkedList::interpolation_hint()`].
+/// The conversion must be monotonic with the key order, but it does not have to be exact.
+pub trait NumericKey {
+    /// Returns the key as an approximate f64
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! numeric_key {
+    ($($t:ty),*) => {$(
+        impl NumericKey for $t {
+            #[inline(always)]
+            fn to_f64(&self) -> f64 {
+                *self as f64
+            }
+        }
+    )*};
+}
+
+numeric_key!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl NumericKey for TotalF64 {
+    #[inline(always)]
+    fn to_f64(&self) -> f64 {
+        self.0
+    }
+}
+
+impl NumericKey for TotalF32 {
+    #[inline(always)]
+    fn to_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd + NumericKey,
+    V: Debug,
+    M: Default,
+{
+    /// Returns a position hint close to `key`, without following any links.
+    ///
+    /// The slots are not stored in key order, so instead of interpolating a rank the guess probes
+    /// the head, the tail and about sqrt(n) evenly spaced slots for the key numerically closest
+    /// to `key`. Returns OUT_OF_BOUNDS if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<u32, ()>::default();
+    /// for i in 0..1000 {
+    ///     let _ = ll.ordered_insert_pos(i * 2, (), ll.tail());
+    /// }
+    /// let hint = ll.interpolation_hint(&1001);
+    /// let k = *ll.get_k(hint).unwrap();
+    /// assert!(k > 900 && k < 1100);
+    /// ```
+    pub fn interpolation_hint(&self, key: &K) -> usize {
+        let (head, tail) = match (self.get_k_opt(self.head_), self.get_k_opt(self.tail_)) {
+            (Some(head), Some(tail)) => (head, tail),
+            _ => return OUT_OF_BOUNDS,
+        };
+        let target = key.to_f64();
+        let (low, high) = (head.to_f64(), tail.to_f64());
+        if target <= low {
+            return self.head_;
+        }
+        if target >= high {
+            return self.tail_;
+        }
+        let distance = |k: &K| (k.to_f64() - target).abs();
+        let (mut best, mut best_distance) = if target - low < high - target {
+            (self.head_, target - low)
+        } else {
+            (self.tail_, high - target)
+        };
+        let slots = self.nodes_.len();
+        let stride = ((slots as f64).sqrt() as usize).max(1);
+        let mut index = 0;
+        while index < slots {
+            if let Some(Some(node)) = self.nodes_.get(index) {
+                let d = distance(&node.key_);
+                if d < best_distance {
+                    best = index;
+                    best_distance = d;
+                }
+            }
+            index += stride;
+        }
+        best
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert_pos()`] with [`LinkedList::interpolation_hint()`]
+    /// as the position hint.
+    pub fn ordered_insert_interpolated(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        let hint = self.interpolation_hint(&key);
+        self.ordered_insert_pos(key, value, hint)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::lower_bound_pos()`] with [`LinkedList::interpolation_hint()`]
+    /// as the position hint.
+    pub fn lower_bound_interpolated(&self, key: &K) -> Result<Option<usize>, MapError> {
+        let hint = self.interpolation_hint(key);
+        self.lower_bound_pos(key, hint)
+    }
+}
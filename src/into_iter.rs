@@ -0,0 +1,295 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The IntoIterator implementations of a [`LinkedList`], and the iterator over mutable values.
+
+use crate::{LinkedList, ListIterator, Node, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// An owning, double ended iterator over the items of a list in sorted order, created by
+/// `into_iter()` on a [`LinkedList`]. The metadata of the items is dropped.
+///
+/// Every returned item is moved out of its slot, so the keys and values are owned by the caller
+/// as soon as they are returned. The slot vector itself is shrunk as it empties and released
+/// when the iterator is dropped.
+#[derive(Debug)]
+pub struct IntoIter<K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    nodes_: Vec<Option<Node<K, V, M>>>,
+    head_: usize,
+    tail_: usize,
+    len_: usize,
+}
+
+impl<K, V, M> IntoIter<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// Takes the node at index out of its slot
+    fn take_(&mut self, index: usize) -> Option<Node<K, V, M>> {
+        let node = self.nodes_.get_mut(index)?.take()?;
+        self.len_ -= 1;
+        if self.len_ == 0 {
+            self.nodes_ = Vec::new();
+        } else {
+            // the empty slots at the end of the vector can be released
+            while let Some(None) = self.nodes_.last() {
+                let _ = self.nodes_.pop();
+            }
+            if self.nodes_.len() <= self.nodes_.capacity() / 4 {
+                self.nodes_.shrink_to_fit();
+            }
+        }
+        Some(node)
+    }
+}
+
+impl<K, V, M> Iterator for IntoIter<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.take_(self.head_)?;
+        self.head_ = node.next_;
+        if self.len_ == 0 {
+            self.tail_ = OUT_OF_BOUNDS;
+        }
+        Some((node.key_, node.value_))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len_, Some(self.len_))
+    }
+}
+
+impl<K, V, M> DoubleEndedIterator for IntoIter<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.take_(self.tail_)?;
+        self.tail_ = node.prev_;
+        if self.len_ == 0 {
+            self.head_ = OUT_OF_BOUNDS;
+        }
+        Some((node.key_, node.value_))
+    }
+}
+
+impl<K, V, M> ExactSizeIterator for IntoIter<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+}
+
+/// A double ended iterator over the items of a list in sorted order, returning the values as
+/// mutable references, created by [`LinkedList::iter_mut()`]. The keys are read only, so the
+/// order of the list can not be broken.
+///
+/// The iterator walks the links of the list in place, nothing is allocated.
+#[derive(Debug)]
+pub struct IterMut<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    // the slot vector of the list, mutably borrowed for 'a
+    nodes_: *mut Option<Node<K, V, M>>,
+    slots_: usize,
+    head_: usize,
+    tail_: usize,
+    len_: usize,
+    marker_: PhantomData<&'a mut Node<K, V, M>>,
+}
+
+// IterMut is used like a `&'a mut [Option<Node<K, V, M>>]`
+unsafe impl<K, V, M> Send for IterMut<'_, K, V, M>
+where
+    K: Debug + Send,
+    V: Debug + Send,
+    M: Send,
+{
+}
+
+unsafe impl<K, V, M> Sync for IterMut<'_, K, V, M>
+where
+    K: Debug + Sync,
+    V: Debug + Sync,
+    M: Sync,
+{
+}
+
+impl<'a, K, V, M> IterMut<'a, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+    M: Default,
+{
+    pub(crate) fn new(list: &'a mut LinkedList<K, V, M>) -> Self
+    where
+        K: Ord + PartialOrd,
+    {
+        Self {
+            len_: list.len(),
+            head_: list.head_,
+            tail_: list.tail_,
+            slots_: list.nodes_.len(),
+            nodes_: list.nodes_.as_mut_ptr(),
+            marker_: PhantomData,
+        }
+    }
+
+    /// Returns the node at index, split into a key and a mutable value, and counts it as returned
+    fn take_(&mut self, index: usize) -> Option<(&'a K, &'a mut V, usize, usize)> {
+        if self.len_ == 0 || index >= self.slots_ {
+            return None;
+        }
+        // SAFETY: index is inside the slot vector, which is mutably borrowed for 'a. Every
+        // active item is linked exactly once, and the two ends of the walk together return no
+        // more than len_ items, so no slot is returned twice and the references never alias.
+        let slot = unsafe { &mut *self.nodes_.add(index) };
+        let Node {
+            prev_,
+            next_,
+            key_,
+            value_,
+            ..
+        } = slot.as_mut()?;
+        self.len_ -= 1;
+        Some((key_, value_, *prev_, *next_))
+    }
+}
+
+impl<'a, K, V, M> Iterator for IterMut<'a, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+    M: Default,
+{
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value, _, next) = self.take_(self.head_)?;
+        self.head_ = if self.len_ == 0 { OUT_OF_BOUNDS } else { next };
+        Some((key, value))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len_, Some(self.len_))
+    }
+}
+
+impl<'a, K, V, M> DoubleEndedIterator for IterMut<'a, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+    M: Default,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, value, prev, _) = self.take_(self.tail_)?;
+        self.tail_ = if self.len_ == 0 { OUT_OF_BOUNDS } else { prev };
+        Some((key, value))
+    }
+}
+
+impl<'a, K, V, M> ExactSizeIterator for IterMut<'a, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+    M: Default,
+{
+}
+
+impl<K, V, M> IntoIterator for LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, M>;
+
+    /// Consumes the list and returns its items in sorted order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<String, Vec<u8>>::default();
+    /// let _ = ll.ordered_insert("b".to_string(), vec![2]);
+    /// let _ = ll.ordered_insert("a".to_string(), vec![1]);
+    /// let mut iter = ll.into_iter();
+    /// assert_eq!(iter.next_back(), Some(("b".to_string(), vec![2])));
+    /// assert_eq!(iter.collect::<Vec<_>>(), vec![("a".to_string(), vec![1])]);
+    /// ```
+    fn into_iter(mut self) -> Self::IntoIter {
+        IntoIter {
+            len_: self.len(),
+            head_: self.head_,
+            tail_: self.tail_,
+            nodes_: std::mem::take(&mut self.nodes_),
+        }
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = ListIterator<'a, K, V, M>;
+
+    #[inline(always)]
+    /// Same as [`LinkedList::iter()`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i32, i32>::default();
+    /// let _ = ll.ordered_insert(2, 20);
+    /// let _ = ll.ordered_insert(1, 10);
+    /// let mut sum = 0;
+    /// for (k, v) in &ll {
+    ///     sum = sum * 100 + k * v;
+    /// }
+    /// assert_eq!(sum, 1040);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, M> IntoIterator for &'a mut LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V, M>;
+
+    #[inline(always)]
+    /// Same as [`LinkedList::iter_mut()`]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
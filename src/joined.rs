@@ -0,0 +1,115 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Walking two sorted lists together, a sorted merge join.
+
+use crate::{LinkedList, ListIterator};
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::iter::Peekable;
+
+/// An iterator over the union of the keys of two lists, in key order, created by
+/// [`LinkedList::iter_joined()`]. Every key is returned once, together with its value in each
+/// of the lists, or None if the key is missing from that list.
+#[derive(Clone, Debug)]
+pub struct JoinedIterator<'a, K, V1, V2, M1 = (), M2 = ()>
+where
+    K: Debug,
+    V1: Debug,
+    V2: Debug,
+{
+    left_: Peekable<ListIterator<'a, K, V1, M1>>,
+    right_: Peekable<ListIterator<'a, K, V2, M2>>,
+}
+
+impl<'a, K, V1, V2, M1, M2> Iterator for JoinedIterator<'a, K, V1, V2, M1, M2>
+where
+    K: Debug + Ord,
+    V1: Debug,
+    V2: Debug,
+{
+    type Item = (&'a K, Option<&'a V1>, Option<&'a V2>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let order = match (self.left_.peek(), self.right_.peek()) {
+            (Some((left_k, _)), Some((right_k, _))) => left_k.cmp(right_k),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+        // unwrap is safe, the items were peeked
+        Some(match order {
+            Ordering::Less => {
+                let (k, v) = self.left_.next().unwrap();
+                (k, Some(v), None)
+            }
+            Ordering::Greater => {
+                let (k, v) = self.right_.next().unwrap();
+                (k, None, Some(v))
+            }
+            Ordering::Equal => {
+                let (k, left) = self.left_.next().unwrap();
+                let (_, right) = self.right_.next().unwrap();
+                (k, Some(left), Some(right))
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left, left_max) = self.left_.size_hint();
+        let (right, right_max) = self.right_.size_hint();
+        let max = match (left_max, right_max) {
+            (Some(left_max), Some(right_max)) => left_max.checked_add(right_max),
+            _ => None,
+        };
+        (left.max(right), max)
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Walks this list and `other` in parallel, returning every key of either list once, in key
+    /// order, with its value in this list and in `other`. A key that exists in both lists is
+    /// returned with the key of this list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut names = LinkedList::<u32, &str>::default();
+    /// let mut ages = LinkedList::<u32, u8>::default();
+    /// let _ = names.ordered_insert(1, "one");
+    /// let _ = names.ordered_insert(2, "two");
+    /// let _ = ages.ordered_insert(2, 22);
+    /// let _ = ages.ordered_insert(3, 33);
+    /// assert_eq!(
+    ///     names.iter_joined(&ages).collect::<Vec<_>>(),
+    ///     vec![
+    ///         (&1, Some(&"one"), None),
+    ///         (&2, Some(&"two"), Some(&22)),
+    ///         (&3, None, Some(&33)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter_joined<'a, V2, M2>(
+        &'a self,
+        other: &'a LinkedList<K, V2, M2>,
+    ) -> JoinedIterator<'a, K, V, V2, M, M2>
+    where
+        V2: Debug,
+        M2: Default,
+    {
+        JoinedIterator {
+            left_: self.iter().peekable(),
+            right_: other.iter().peekable(),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An optional table of every k-th node, used as a starting point for searches without a
+//! position hint.
+
+use crate::{LinkedList, OUT_OF_BOUNDS};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+/// The indices of about every sqrt(n)-th node, in sorted order.
+/// Inserted nodes are not added to the table, the table is rebuilt when the length of the list
+/// has drifted too far from the length it was built for. Removed nodes are replaced by their
+/// successor, so the table always refers to live nodes.
+#[derive(Clone, Debug)]
+pub(crate) struct JumpTable {
+    indices_: Vec<usize>,
+    built_len_: usize,
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Enables or disables the jump table.
+    ///
+    /// The jump table holds the index of about every sqrt(n)-th node, so that
+    /// ordered_insert() and lower_bound() (the searches without a position hint) can binary
+    /// search the table and then scan at most about sqrt(n) nodes. Hinted operations and slot
+    /// indices are not affected.
+    ///
+    /// Note that this requires the keys to be in a strict order, the table is binary searched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i32, ()>::default();
+    /// ll.set_jump_table(true);
+    /// for i in 0..1000 {
+    ///     let _ = ll.ordered_insert(i, ());
+    /// }
+    /// let lb = ll.lower_bound(&500).unwrap().unwrap();
+    /// assert_eq!(ll.get_k(lb).unwrap(), &500);
+    /// ```
+    pub fn set_jump_table(&mut self, enabled: bool) {
+        if enabled {
+            self.rebuild_jump_table_();
+        } else {
+            self.jump_table_ = None;
+        }
+    }
+
+    #[inline(always)]
+    /// Returns true if the jump table is enabled
+    pub fn has_jump_table(&self) -> bool {
+        self.jump_table_.is_some()
+    }
+
+    /// Rebuild the jump table from scratch
+    pub(crate) fn rebuild_jump_table_(&mut self) {
+        let len = self.len();
+        let stride = ((len as f64).sqrt() as usize).max(1);
+        let mut indices = Vec::with_capacity(len / stride + 1);
+        let mut curr_index = self.head_;
+        let mut skip = 0_usize;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            if skip == 0 {
+                indices.push(curr_index);
+                skip = stride;
+            }
+            skip -= 1;
+            curr_index = node.next_;
+        }
+        self.jump_table_ = Some(JumpTable {
+            indices_: indices,
+            built_len_: len,
+        });
+    }
+
+    /// Rebuild the jump table if the list length has drifted too far since the last rebuild
+    pub(crate) fn maintain_jump_table_(&mut self) {
+        if let Some(table) = &self.jump_table_ {
+            let len = self.len();
+            if len > table.built_len_ * 2 + 8 || len * 2 + 8 < table.built_len_ {
+                self.rebuild_jump_table_();
+            }
+        }
+    }
+
+    /// Update the jump table after the node at `index` has been removed, `next` is the index of
+    /// its old successor.
+    pub(crate) fn jump_table_removed_(&mut self, index: usize, next: usize) {
+        let next_is_live = matches!(self.nodes_.get(next), Some(Some(_)));
+        if let Some(table) = &mut self.jump_table_ {
+            if let Some(pos) = table.indices_.iter().position(|i| *i == index) {
+                if next_is_live && table.indices_.get(pos + 1) != Some(&next) {
+                    table.indices_[pos] = next;
+                } else {
+                    let _ = table.indices_.remove(pos);
+                }
+            }
+        }
+    }
+
+    /// Returns the last node of the jump table whose key goes before `key`, or the head.
+    /// Returns None if the jump table is disabled or the list is empty.
+    pub(crate) fn jump_hint_<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let table = self.jump_table_.as_ref()?;
+        if self.head_ == OUT_OF_BOUNDS {
+            return None;
+        }
+        let before = table.indices_.partition_point(|i| {
+            matches!(self.nodes_.get(*i), Some(Some(node)) if key.cmp(node.key_.borrow()) == Ordering::Greater)
+        });
+        if before == 0 {
+            Some(self.head_)
+        } else {
+            Some(table.indices_[before - 1])
+        }
+    }
+}
@@ -17,29 +17,284 @@
 //!
 //! The current implementation uses a double linked std::vec::Vec list, and it only supports sequential search.
 //!
+//! Floating point keys can be wrapped in [`TotalF64`] or [`TotalF32`]:
+//! ```
+//! # use cpp_map::{LinkedList, TotalF64};
+//! let mut ll = LinkedList::<TotalF64, usize>::default();
+//! let _ = ll.ordered_insert(TotalF64(2.5), 0);
+//! let _ = ll.ordered_insert(TotalF64(-1.0), 1);
+//! assert_eq!(ll.peek_front_k().unwrap().get(), -1.0);
+//! ```
+//!
+//! Keys that only sort by some of their fields can use `#[derive(SortByKey)]` (requires the
+//! `derive` feature), it implements `Ord`, `PartialOrd`, `Eq` and `PartialEq` using the fields
+//! marked with `#[sort_key]`.
+//!
+//! The `rich_error` feature (on by default) makes [`MapError`] carry error messages. Without it
+//! `MapError` is the fieldless [`MapErrorKind`], thiserror is not used and no messages are formatted.
+//!
+use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::marker::PhantomData;
 use std::rc::Rc;
 
+/// Constructs a [`MapError`], the message is only formatted with the `rich_error` feature.
+#[cfg(feature = "rich_error")]
+macro_rules! map_error {
+    (ParseError, $line:expr, $($arg:tt)+) => {
+        $crate::MapError::ParseError($line, format!($($arg)+))
+    };
+    ($variant:ident, $($arg:tt)+) => {
+        $crate::MapError::$variant(format!($($arg)+))
+    };
+}
+
+/// Constructs a [`MapError`], the message is only formatted with the `rich_error` feature.
+#[cfg(not(feature = "rich_error"))]
+macro_rules! map_error {
+    (ParseError, $line:expr, $($arg:expr),+ $(,)?) => {{
+        let _ = ($line, $(&$arg),+);
+        $crate::MapError::ParseError
+    }};
+    ($variant:ident, $($arg:expr),+ $(,)?) => {{
+        let _ = ($(&$arg),+);
+        $crate::MapError::$variant
+    }};
+}
+
+mod aggregate;
+mod arena;
+mod binary;
+mod bookmark;
+mod brand;
+mod budget;
+mod compare_stats;
+mod cursor;
+mod cursor_pair;
+mod deferred;
+mod diff;
+mod edit_log;
+mod entry;
+mod external;
+mod extract_if;
+mod fingerprint;
+mod fixed;
+mod freeze;
+mod history;
+mod indexed;
+mod inserted;
+mod instrument;
+mod interpolation;
+mod into_iter;
+mod joined;
+mod jump_table;
+mod merge;
+mod node_handle;
+mod normalize;
+mod pool;
+mod recover;
+mod remap;
+mod scan_warning;
+mod search;
+mod set;
+mod shadow;
+mod shared;
+mod slot_reuse;
+mod strict_hints;
+mod text;
+mod tombstone;
+mod total_float;
+mod trace;
+mod transaction;
+mod view;
+pub use aggregate::{Aggregated, Count, Monoid};
+pub use arena::{ArenaList, ListArena};
+pub use binary::BinaryCodec;
+pub use bookmark::Bookmark;
+pub use brand::BrandedIndex;
+use brand::ListId;
+pub use budget::{BudgetInsert, BudgetSearch};
+use compare_stats::CompareCounter;
+pub use compare_stats::{CompareStats, OperationStats};
+pub use cursor::Cursor;
+pub use cursor_pair::CursorPair;
+pub use deferred::DeferredInserts;
+pub use diff::Change;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use external::ExternalValues;
+pub use extract_if::ExtractIf;
+pub use fingerprint::Fingerprinted;
+pub use fixed::FixedCapacity;
+use freeze::FreezeCount;
+pub use freeze::FreezeGuard;
+pub use history::History;
+pub use indexed::Indexed;
+pub use inserted::InsertedEntry;
+pub use interpolation::NumericKey;
+pub use into_iter::{IntoIter, IterMut};
+pub use joined::JoinedIterator;
+use jump_table::JumpTable;
+pub use node_handle::NodeHandle;
+use normalize::KeyNormalizer;
+pub use pool::ListPool;
+pub use recover::Recovery;
+pub use remap::IndexRemap;
+use scan_warning::ScanWarning;
+pub use scan_warning::{LongScan, ScanOperation};
+pub use search::{EqualPolicy, SearchStrategy};
+pub use set::LinkedSet;
+pub use shadow::Shadowed;
+pub use shared::SharedList;
+pub use slot_reuse::SlotReuse;
+pub use total_float::{TotalF32, TotalF64};
+pub use trace::{TraceFrame, TraceOp, Tracer};
+pub use transaction::Editor;
+pub use view::{View, ViewIterator};
+
+#[cfg(feature = "derive")]
+pub use cpp_map_derive::SortByKey;
+
 /// Indicates that an iterator has passed beyond the limits of the list.
 pub const OUT_OF_BOUNDS: usize = usize::MAX;
 
+/// The kind of a [`MapError`], without any error message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MapErrorKind {
+    InternalError,
+    ParseError,
+    DecodeError,
+    OrderViolation,
+    CapacityExceeded,
+    Frozen,
+    BorrowError,
+    BorrowMutError,
+    HintMismatch,
+    WouldBlock,
+}
+
+impl std::fmt::Display for MapErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InternalError => write!(f, "error: Some error with the linked list"),
+            Self::ParseError => write!(f, "error: Could not parse line"),
+            Self::DecodeError => write!(f, "error: Could not decode data"),
+            Self::OrderViolation => write!(f, "error: The key would break the sort order"),
+            Self::CapacityExceeded => write!(f, "error: The max capacity of the list is exceeded"),
+            Self::Frozen => write!(f, "error: The list is frozen"),
+            Self::BorrowError => write!(f, "error: already mutably borrowed"),
+            Self::BorrowMutError => write!(f, "error: already borrowed"),
+            Self::HintMismatch => {
+                write!(
+                    f,
+                    "error: The position hint does not fit the key or the list"
+                )
+            }
+            Self::WouldBlock => write!(f, "error: The list is locked"),
+        }
+    }
+}
+
+impl std::error::Error for MapErrorKind {}
+
+#[cfg(feature = "rich_error")]
 #[derive(thiserror::Error, Debug)]
 pub enum MapError {
-    #[error("error: Some error with the linked list")]
+    #[error("error: Some error with the linked list: {0}")]
     InternalError(String),
+    #[error("error: Could not parse line {0}: {1}")]
+    ParseError(usize, String),
+    #[error("error: Could not decode data: {0}")]
+    DecodeError(String),
+    #[error("error: The key would break the sort order: {0}")]
+    OrderViolation(String),
+    #[error("error: The max capacity of the list is exceeded: {0}")]
+    CapacityExceeded(String),
+    #[error("error: The list is frozen: {0}")]
+    Frozen(String),
     #[error(transparent)]
     BorrowError(#[from] std::cell::BorrowError),
     #[error(transparent)]
     BorrowMutError(#[from] std::cell::BorrowMutError),
+    #[error("error: The position hint does not fit the key or the list: {0}")]
+    HintMismatch(String),
+    #[error("error: The list is locked: {0}")]
+    WouldBlock(String),
+}
+
+#[cfg(feature = "rich_error")]
+impl MapError {
+    /// Returns the kind of error
+    pub fn kind(&self) -> MapErrorKind {
+        match self {
+            Self::InternalError(_) => MapErrorKind::InternalError,
+            Self::ParseError(..) => MapErrorKind::ParseError,
+            Self::DecodeError(_) => MapErrorKind::DecodeError,
+            Self::OrderViolation(_) => MapErrorKind::OrderViolation,
+            Self::CapacityExceeded(_) => MapErrorKind::CapacityExceeded,
+            Self::Frozen(_) => MapErrorKind::Frozen,
+            Self::BorrowError(_) => MapErrorKind::BorrowError,
+            Self::BorrowMutError(_) => MapErrorKind::BorrowMutError,
+            Self::HintMismatch(_) => MapErrorKind::HintMismatch,
+            Self::WouldBlock(_) => MapErrorKind::WouldBlock,
+        }
+    }
+}
+
+#[cfg(feature = "rich_error")]
+impl From<MapErrorKind> for MapError {
+    /// Converts the lightweight error into the rich error, the message will be the
+    /// description of the kind. The cell borrow errors can't be constructed, so they are converted
+    /// into InternalError.
+    fn from(kind: MapErrorKind) -> Self {
+        match kind {
+            MapErrorKind::ParseError => Self::ParseError(0, kind.to_string()),
+            MapErrorKind::DecodeError => Self::DecodeError(kind.to_string()),
+            MapErrorKind::OrderViolation => Self::OrderViolation(kind.to_string()),
+            MapErrorKind::CapacityExceeded => Self::CapacityExceeded(kind.to_string()),
+            MapErrorKind::Frozen => Self::Frozen(kind.to_string()),
+            MapErrorKind::HintMismatch => Self::HintMismatch(kind.to_string()),
+            MapErrorKind::WouldBlock => Self::WouldBlock(kind.to_string()),
+            _ => Self::InternalError(kind.to_string()),
+        }
+    }
+}
+
+/// Without the `rich_error` feature the error type is the fieldless [`MapErrorKind`], and
+/// no error messages are formatted.
+#[cfg(not(feature = "rich_error"))]
+pub type MapError = MapErrorKind;
+
+#[cfg(not(feature = "rich_error"))]
+impl MapErrorKind {
+    #[inline(always)]
+    /// Returns the kind of error, i.e. self. Same as the rich error `MapError::kind()`
+    pub fn kind(&self) -> MapErrorKind {
+        *self
+    }
+}
+
+#[cfg(not(feature = "rich_error"))]
+impl From<std::cell::BorrowError> for MapErrorKind {
+    fn from(_: std::cell::BorrowError) -> Self {
+        Self::BorrowError
+    }
+}
+
+#[cfg(not(feature = "rich_error"))]
+impl From<std::cell::BorrowMutError> for MapErrorKind {
+    fn from(_: std::cell::BorrowMutError) -> Self {
+        Self::BorrowMutError
+    }
 }
 
 #[cfg(test)]
 mod test;
 
 #[derive(Clone, Debug)]
-struct Node<K, V>
+struct Node<K, V, M>
 where
     K: Debug,
     V: Debug,
@@ -48,38 +303,227 @@ where
     next_: usize,
     key_: K,
     value_: V,
+    meta_: M,
 }
 
 /// A double linked min list.
 /// The head (top/front) of the list is the first item. Sorted Order::Less than other items.
 /// The tail (bottom/back) is the last item of the list. Sorted Order::Greater than other items.
+///
+/// Every node carries a piece of user metadata of type `M`, see [`LinkedList::set_meta()`].
+/// The default `()` is zero-sized, so a list without metadata pays nothing for it. The metadata
+/// is not part of the key/value pair, it starts out as `M::default()` for every inserted item
+/// and is dropped together with the item.
 #[derive(Clone, Debug)]
-pub struct LinkedList<K, V>
+pub struct LinkedList<K, V, M = ()>
 where
     K: Debug,
     V: Debug,
 {
     head_: usize,
     tail_: usize,
-    nodes_: Vec<Option<Node<K, V>>>,
+    nodes_: Vec<Option<Node<K, V, M>>>,
     id_pool_: Vec<usize>,
+    scan_warning_: Option<ScanWarning>,
+    compare_stats_: CompareCounter,
+    list_id_: ListId,
+    key_normalizer_: Option<KeyNormalizer<K>>,
+    search_strategy_: SearchStrategy,
+    equal_policy_: EqualPolicy,
+    jump_table_: Option<JumpTable>,
+    max_capacity_: Option<usize>,
+    freeze_count_: FreezeCount,
+    // bookmark id -> index, None for removed bookmarks
+    bookmarks_: Vec<Option<usize>>,
+    slot_reuse_: SlotReuse,
+    defer_slot_reuse_: bool,
+    strict_hints_: bool,
+    // removed slot index -> (old prev, old next)
+    tombstones_: BTreeMap<usize, (usize, usize)>,
 }
 
-impl<K, V> Default for LinkedList<K, V>
+impl<K, V, M> Default for LinkedList<K, V, M>
 where
     K: Debug,
     V: Debug,
 {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, M> Extend<(K, V)> for LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Inserts every pair by Order, each search starts from the previously inserted item, so
+    /// a sorted stream is inserted in O(n). As with ordered_insert(), inserting an already
+    /// existing key is a NOP. Extend can't report errors, a pair that can't be inserted (e.g.
+    /// into a frozen or full list) is dropped, use [`LinkedList::extend_with_hints()`] to see
+    /// the errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, char>::default();
+    /// ll.extend((0..3).map(|i| (i, 'a')).chain(vec![(1, 'b'), (-1, 'b')]));
+    /// assert_eq!(
+    ///     ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+    ///     vec![(-1, 'b'), (0, 'a'), (1, 'a'), (2, 'a')]
+    /// );
+    /// ```
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut last = self.tail_;
+        for (key, value) in iter {
+            let key = self.normalize_key(key);
+            if let Ok((index, _)) = self.insert_pos_(key, value, last) {
+                last = index;
+            }
+        }
+    }
+}
+
+impl<K, V, M> std::iter::FromIterator<(K, V)> for LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Builds a list from (key, value) pairs. As long as the keys arrive in increasing order the
+    /// pairs are pushed at the tail without any search, an out of order pair is inserted with
+    /// the previously inserted item as the position hint. As with ordered_insert(), a repeated
+    /// key is a NOP, the first value is kept. See
+    /// [`LinkedList::from_iter_report_duplicates()`] for a version returning the duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let ll: LinkedList<i8, char> = vec![(1, 'a'), (3, 'b'), (2, 'c'), (3, 'd')]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &'a'), (&2, &'c'), (&3, &'b')]);
+    /// ```
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        let mut list = Self::with_capacity(iter.size_hint().0);
+        let mut last = OUT_OF_BOUNDS;
+        for (key, value) in iter {
+            let sorted = list.peek_back_k().map_or(true, |tail| &key > tail);
+            // a list without a max capacity never fails to insert
+            let rv = if sorted {
+                list.push_back_(key, value)
+            } else {
+                list.insert_pos_(key, value, last).map(|(index, _)| index)
+            };
+            if let Ok(index) = rv {
+                last = index;
+            }
+        }
+        list
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// Constructs a new, empty LinkedList<K,V>. The list will not allocate until elements are
+    /// inserted, and it can be used in const contexts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// const EMPTY: LinkedList<i8, i8> = LinkedList::new();
+    /// let mut ll = EMPTY;
+    /// let _ = ll.ordered_insert(1, 1);
+    /// assert_eq!(ll.len(), 1);
+    /// ```
+    pub const fn new() -> Self {
         Self {
             head_: OUT_OF_BOUNDS,
             tail_: OUT_OF_BOUNDS,
             nodes_: Vec::new(),
             id_pool_: Vec::new(),
+            scan_warning_: None,
+            compare_stats_: CompareCounter::new(),
+            list_id_: ListId::new(),
+            key_normalizer_: None,
+            search_strategy_: SearchStrategy::Linear,
+            equal_policy_: EqualPolicy::StopAtFirst,
+            jump_table_: None,
+            max_capacity_: None,
+            freeze_count_: FreezeCount::new(),
+            bookmarks_: Vec::new(),
+            slot_reuse_: SlotReuse::Lifo,
+            defer_slot_reuse_: false,
+            strict_hints_: false,
+            tombstones_: BTreeMap::new(),
         }
     }
 }
 
+/// Capacity information returned by [`LinkedList::capacity_info()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capacity {
+    /// The capacity of the node vector
+    pub nodes: usize,
+    /// The capacity of the free index vector
+    pub free_list: usize,
+    /// The number of inserted elements
+    pub live: usize,
+    /// The number of elements that can be inserted without reallocating the node vector
+    pub spare: usize,
+}
+
+/// Fragmentation metrics returned by [`LinkedList::fragmentation()`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fragmentation {
+    /// The number of inserted elements
+    pub live: usize,
+    /// The number of free slots, including tombstones
+    pub free: usize,
+    /// live / (live + free), 1.0 for an empty list
+    pub live_ratio: f64,
+    /// The longest run of consecutive free slots
+    pub longest_free_run: usize,
+    /// The average distance in slots between logically adjacent elements, 1.0 is a perfectly
+    /// ordered layout. 0.0 for lists with less than two elements.
+    pub average_link_distance: f64,
+}
+
+/// The result of [`LinkedList::lower_bound_hint()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LowerBound {
+    /// The lower bound, or None if every key goes before the searched key
+    pub index: Option<usize>,
+    /// The last index the search visited: the lower bound if there is one, otherwise the tail
+    /// (OUT_OF_BOUNDS for an empty list). Use it as the position of a following
+    /// ordered_insert_pos() of the same key.
+    pub hint: usize,
+}
+
+/// The result of [`LinkedList::ordered_insert_result()`] and
+/// [`LinkedList::ordered_insert_or_assign()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InsertResult {
+    /// The index of the inserted item, or of the item that already had the key
+    pub index: usize,
+    /// False if the key already existed
+    pub inserted: bool,
+}
+
 #[derive(Clone, Debug)]
 /// borrow checker work-around
 struct EraseOperation {
@@ -92,54 +536,485 @@ struct EraseOperation {
 }
 
 #[allow(dead_code)]
-impl<'a, K: 'a, V: 'a> LinkedList<K, V>
+impl<K, V, M> LinkedList<K, V, M>
 where
     K: Debug + Ord + PartialOrd,
     V: Debug,
+    M: Default,
 {
     /// Constructs a new, empty LinkedList<K,V> with the specified capacity.
     /// The LinkedList will be able to hold exactly capacity elements without reallocating.
     /// If capacity is 0, the list will not allocate.
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            head_: OUT_OF_BOUNDS,
-            tail_: OUT_OF_BOUNDS,
             nodes_: Vec::with_capacity(capacity),
             id_pool_: Vec::with_capacity(capacity),
+            ..Self::new()
         }
     }
 
-    pub fn iter(&self) -> ListIterator<'_, K, V> {
+    /// Constructs a new, empty LinkedList<K,V> that can never hold more than `max_capacity`
+    /// elements. All the memory is allocated up front, and inserts that would need more room
+    /// fail with a `CapacityExceeded` error instead of reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, MapErrorKind};
+    /// let mut ll = LinkedList::<i8, i8>::with_max_capacity(2);
+    /// let _ = ll.ordered_insert(1, 1).unwrap();
+    /// let _ = ll.ordered_insert(2, 2).unwrap();
+    /// assert_eq!(ll.ordered_insert(3, 3).unwrap_err().kind(), MapErrorKind::CapacityExceeded);
+    /// let _ = ll.pop_front();
+    /// assert!(ll.ordered_insert(3, 3).is_ok());
+    /// ```
+    pub fn with_max_capacity(max_capacity: usize) -> Self {
+        let mut list = Self::with_capacity(max_capacity);
+        list.max_capacity_ = Some(max_capacity);
+        list
+    }
+
+    #[inline(always)]
+    /// Returns the max capacity set by [`LinkedList::with_max_capacity()`]
+    pub fn max_capacity(&self) -> Option<usize> {
+        self.max_capacity_
+    }
+
+    pub fn iter(&self) -> ListIterator<'_, K, V, M> {
         ListIterator {
             list_: self,
             my_next_: self.head_,
+            position_: 0,
+        }
+    }
+
+    #[inline(always)]
+    /// Iterates over the keys in sorted order, see [`LinkedList::iter()`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, char>::default();
+    /// let _ = ll.ordered_insert(2, 'b');
+    /// let _ = ll.ordered_insert(1, 'a');
+    /// assert_eq!(ll.keys().collect::<Vec<_>>(), vec![&1, &2]);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    #[inline(always)]
+    /// Iterates over the values in the sorted order of the keys, see [`LinkedList::iter()`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, char>::default();
+    /// let _ = ll.ordered_insert(2, 'b');
+    /// let _ = ll.ordered_insert(1, 'a');
+    /// assert_eq!(ll.values().collect::<String>(), "ab");
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, v)| v)
+    }
+
+    #[inline(always)]
+    /// Iterates over the items in sorted order, the values can be updated in place. The keys
+    /// are read only. The links are walked in place, nothing is allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i32>::default();
+    /// let _ = ll.ordered_insert(2, 20);
+    /// let _ = ll.ordered_insert(1, 10);
+    /// let mut running = 0;
+    /// for (k, v) in ll.iter_mut() {
+    ///     running += *k as i32;
+    ///     *v += running;
+    /// }
+    /// assert_eq!(ll.values().collect::<Vec<_>>(), vec![&11, &23]);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, M> {
+        IterMut::new(self)
+    }
+
+    #[inline(always)]
+    /// Iterates over mutable references to the values in the sorted order of the keys, without
+    /// allocating, see [`LinkedList::iter_mut()`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, String>::default();
+    /// let _ = ll.ordered_insert(2, "b".to_string());
+    /// let _ = ll.ordered_insert(1, "a".to_string());
+    /// ll.values_mut().for_each(|v| v.make_ascii_uppercase());
+    /// assert_eq!(ll.values().cloned().collect::<String>(), "AB");
+    /// ```
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> + '_ {
+        self.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Iterates over the items in sorted order, starting at the item at `index`.
+    /// An invalid index gives an empty iterator.
+    /// The position of the item is found by walking back to the head, see
+    /// [`ListIterator::position()`].
+    pub fn iter_from(&self, index: usize) -> ListIterator<'_, K, V, M> {
+        if self.get_opt(index).is_none() {
+            return ListIterator {
+                list_: self,
+                my_next_: OUT_OF_BOUNDS,
+                position_: self.len(),
+            };
+        }
+        let mut position = 0;
+        let mut curr_index = index;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            curr_index = node.prev_;
+            if curr_index != OUT_OF_BOUNDS {
+                position += 1;
+            }
+        }
+        ListIterator {
+            list_: self,
+            my_next_: index,
+            position_: position,
+        }
+    }
+
+    /// Iterates over the items ordered by value, as defined by `cmp`. Items with equal values
+    /// are returned in key order.
+    /// The order is materialized as a temporary vector of references, the list is not modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, u32>::default();
+    /// for (k, v) in [(1, 30), (2, 10), (3, 50), (4, 10), (5, 40)] {
+    ///     let _ = ll.ordered_insert(k, v);
+    /// }
+    /// // the top 3 values
+    /// let top = ll.iter_by_value(|a, b| b.cmp(a)).take(3).collect::<Vec<_>>();
+    /// assert_eq!(top, vec![(&3, &50), (&5, &40), (&1, &30)]);
+    /// let bottom = ll.iter_by_value(u32::cmp).take(2).collect::<Vec<_>>();
+    /// assert_eq!(bottom, vec![(&2, &10), (&4, &10)]);
+    /// ```
+    pub fn iter_by_value<F>(
+        &self,
+        mut cmp: F,
+    ) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator + '_
+    where
+        F: FnMut(&V, &V) -> Ordering,
+    {
+        let mut items = self.iter().collect::<Vec<_>>();
+        // a stable sort keeps the key order of equal values
+        items.sort_by(|a, b| cmp(a.1, b.1));
+        items.into_iter()
+    }
+
+    /// Calls a closure on each item, in sorted order. The value is mutable, the key is not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.ordered_insert(1, 1);
+    /// let _ = ll.ordered_insert(2, 2);
+    /// ll.for_each_mut(|k, v| *v += *k);
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &2), (&2, &4)]);
+    /// ```
+    pub fn for_each_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V),
+    {
+        let mut curr_index = self.head_;
+        while let Some(Some(node)) = self.nodes_.get_mut(curr_index) {
+            f(&node.key_, &mut node.value_);
+            curr_index = node.next_;
         }
     }
 
+    /// Calls a fallible closure on each item, in sorted order. The value is mutable, the key is
+    /// not. The traversal stops at the first error, and that error is returned.
+    pub fn try_for_each<F>(&mut self, mut f: F) -> Result<(), MapError>
+    where
+        F: FnMut(&K, &mut V) -> Result<(), MapError>,
+    {
+        let mut curr_index = self.head_;
+        while curr_index != OUT_OF_BOUNDS {
+            match self.nodes_.get_mut(curr_index) {
+                Some(Some(node)) => {
+                    f(&node.key_, &mut node.value_)?;
+                    curr_index = node.next_;
+                }
+                _ => {
+                    return Err(map_error!(
+                        InternalError,
+                        "Broken link to index:{}. {}:{}",
+                        curr_index,
+                        file!(),
+                        line!()
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
     #[inline(always)]
     /// Returns the number of inserted elements
     pub fn len(&self) -> usize {
-        self.nodes_.len() - self.id_pool_.len()
+        self.nodes_.len() - self.id_pool_.len() - self.tombstones_.len()
     }
 
     /// Returns the capacity or the vectors
+    #[deprecated(since = "0.1.2", note = "use capacity_info() instead")]
     pub fn capacity(&self) -> (usize, usize) {
         (self.nodes_.capacity(), self.id_pool_.capacity())
     }
 
+    /// Returns the capacity of the internal vectors and the number of items that can be
+    /// inserted without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::with_capacity(4);
+    /// let _ = ll.ordered_insert(1, 1);
+    /// let _ = ll.ordered_insert(2, 2);
+    /// let _ = ll.pop_front();
+    /// let c = ll.capacity_info();
+    /// assert_eq!(c.nodes, 4);
+    /// assert_eq!(c.live, 1);
+    /// assert_eq!(c.spare, 3);
+    /// ```
+    pub fn capacity_info(&self) -> Capacity {
+        Capacity {
+            nodes: self.nodes_.capacity(),
+            free_list: self.id_pool_.capacity(),
+            live: self.len(),
+            spare: self.nodes_.capacity() - self.nodes_.len() + self.id_pool_.len(),
+        }
+    }
+
+    /// Returns metrics describing how fragmented the node vector is, in O(n). This can be used
+    /// to decide when to call [`LinkedList::optimize()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..4 {
+    ///     let _ = ll.ordered_insert(3 - i, i); // slots in reverse order
+    /// }
+    /// let _ = ll.pop_back(); // slot 0
+    /// let f = ll.fragmentation();
+    /// assert_eq!((f.live, f.free, f.longest_free_run), (3, 1, 1));
+    /// assert_eq!(f.live_ratio, 0.75);
+    /// assert_eq!(f.average_link_distance, 1.0);
+    /// let _ = ll.optimize().unwrap();
+    /// assert_eq!(ll.fragmentation().free, 0);
+    /// ```
+    pub fn fragmentation(&self) -> Fragmentation {
+        let live = self.len();
+        let free = self.nodes_.len() - live;
+        let mut longest_free_run = 0;
+        let mut run = 0;
+        for node in self.nodes_.iter() {
+            if node.is_some() {
+                run = 0;
+            } else {
+                run += 1;
+                longest_free_run = longest_free_run.max(run);
+            }
+        }
+        let mut distance_sum = 0_usize;
+        let mut curr_index = self.head_;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            if node.next_ != OUT_OF_BOUNDS {
+                distance_sum += node.next_.abs_diff(curr_index);
+            }
+            curr_index = node.next_;
+        }
+        Fragmentation {
+            live,
+            free,
+            live_ratio: if self.nodes_.is_empty() {
+                1.0
+            } else {
+                live as f64 / self.nodes_.len() as f64
+            },
+            longest_free_run,
+            average_link_distance: if live < 2 {
+                0.0
+            } else {
+                distance_sum as f64 / (live - 1) as f64
+            },
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the free slot indices. These slots will be reused by the next insert
+    /// operations, last index first. See [`LinkedList::set_slot_reuse()`].
+    pub fn free_slots(&self) -> &[usize] {
+        &self.id_pool_
+    }
+
+    /// Returns the indices of all the occupied slots, in slot order (not sorted order).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.ordered_insert(2, 0); // 0
+    /// let _ = ll.ordered_insert(1, 1); // 1
+    /// let _ = ll.ordered_insert(3, 2); // 2
+    /// let _ = ll.pop_front();
+    /// assert_eq!(ll.live_slot_indices().collect::<Vec<_>>(), vec![0, 2]);
+    /// assert_eq!(ll.free_slots(), &[1]);
+    /// assert_eq!(ll.slot_count(), 3);
+    /// ```
+    pub fn live_slot_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.nodes_
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.as_ref().map(|_| i))
+    }
+
+    #[inline(always)]
+    /// Returns the number of slots (free and occupied), i.e. the high-water mark of the list.
+    /// All valid indices are less than this number.
+    pub fn slot_count(&self) -> usize {
+        self.nodes_.len()
+    }
+
     #[inline(always)]
     /// Returns true if the list is empty
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
-    /// Clears the list.
+    /// Clears the list, including the tombstones.
     /// Warning: any Pointer object referring to this list will be corrupted.
+    /// clear() does not check for a [`FreezeGuard`], use
+    /// [`LinkedList::try_clear()`] on a list that may be frozen.
     pub fn clear(&mut self) {
         self.head_ = OUT_OF_BOUNDS;
         self.tail_ = OUT_OF_BOUNDS;
         self.nodes_.clear();
         self.id_pool_.clear();
+        self.tombstones_.clear();
+        // an empty remapping unsets every bookmark
+        self.bookmarks_remapped_(&[]);
+        if self.jump_table_.is_some() {
+            self.rebuild_jump_table_();
+        }
+    }
+
+    /// Same as [`LinkedList::clear()`], but returns a Frozen error, and leaves the list
+    /// untouched, if there is a live [`FreezeGuard`] for this list.
+    pub fn try_clear(&mut self) -> Result<(), MapError> {
+        self.check_not_frozen_()?;
+        self.clear();
+        Ok(())
+    }
+
+    /// Swaps the content and the settings of two lists in O(1), like C++ `std::map::swap`.
+    /// No items are moved, and indices and bookmarks follow the items to the other list.
+    /// The freeze state stays with the list, swapping a frozen list returns a Frozen error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut current = LinkedList::<i8, i8>::default();
+    /// let mut next = LinkedList::<i8, i8>::default();
+    /// let index = current.ordered_insert(1, 1).unwrap();
+    /// let _ = next.ordered_insert(2, 2);
+    /// current.swap(&mut next).unwrap();
+    /// assert_eq!(current.iter().collect::<Vec<_>>(), vec![(&2, &2)]);
+    /// assert_eq!(next.get(index).unwrap(), (&1, &1));
+    /// ```
+    pub fn swap(&mut self, other: &mut Self) -> Result<(), MapError> {
+        self.check_not_frozen_()?;
+        other.check_not_frozen_()?;
+        std::mem::swap(self, other);
+        // the freeze guards belong to the list instance, not to the content
+        std::mem::swap(&mut self.freeze_count_, &mut other.freeze_count_);
+        Ok(())
+    }
+
+    /// Rewrites the nodes so that the physical order of the slots matches the sorted order,
+    /// and removes all the free slots and tombstones. This makes iteration and sequential searches more cache
+    /// friendly after heavy churn.
+    /// Returns the index remapping: `remap[old_index] == new_index`, free slots are mapped to
+    /// OUT_OF_BOUNDS. See [`LinkedList::compact()`] for a version returning an [`IndexRemap`].
+    /// Returns a Frozen error if there is a live [`FreezeGuard`] for this
+    /// list.
+    /// Warning: any Pointer object or index referring to this list will be invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, OUT_OF_BOUNDS};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.ordered_insert(3, 0); // 0
+    /// let _ = ll.ordered_insert(1, 1); // 1
+    /// let _ = ll.ordered_insert(2, 2); // 2
+    /// let _ = ll.ordered_insert(0, 3); // 3
+    /// let _ = ll.pop_front();
+    /// let remap = ll.optimize().unwrap();
+    /// assert_eq!(remap, vec![2, 0, 1, OUT_OF_BOUNDS]);
+    /// assert_eq!(ll.get(0).unwrap(), (&1, &1));
+    /// assert_eq!(ll.tail(), 2);
+    /// ```
+    pub fn optimize(&mut self) -> Result<Vec<usize>, MapError> {
+        self.check_not_frozen_()?;
+        let mut old_nodes = std::mem::take(&mut self.nodes_);
+        let mut remap = vec![OUT_OF_BOUNDS; old_nodes.len()];
+        let mut nodes = Vec::with_capacity(old_nodes.capacity());
+
+        let mut curr_index = self.head_;
+        while let Some(Some(mut node)) = old_nodes.get_mut(curr_index).map(|n| n.take()) {
+            let new_index = nodes.len();
+            remap[curr_index] = new_index;
+            curr_index = node.next_;
+            node.prev_ = if new_index == 0 {
+                OUT_OF_BOUNDS
+            } else {
+                new_index - 1
+            };
+            node.next_ = new_index + 1;
+            nodes.push(Some(node));
+        }
+        if let Some(Some(last)) = nodes.last_mut() {
+            last.next_ = OUT_OF_BOUNDS;
+        }
+        if nodes.is_empty() {
+            self.head_ = OUT_OF_BOUNDS;
+            self.tail_ = OUT_OF_BOUNDS;
+        } else {
+            self.head_ = 0;
+            self.tail_ = nodes.len() - 1;
+        }
+        self.nodes_ = nodes;
+        self.id_pool_.clear();
+        self.tombstones_.clear();
+        self.bookmarks_remapped_(&remap);
+        if self.jump_table_.is_some() {
+            self.rebuild_jump_table_();
+        }
+        Ok(remap)
     }
 
     /// Returns the next free index.
@@ -156,25 +1031,19 @@ where
     #[inline(always)]
     /// Returns the item key at index
     pub fn get_k(&self, index: usize) -> Result<&K, MapError> {
-        let rv = self
-            .nodes_
-            .get(index)
-            .ok_or_else(|| MapError::InternalError("error, item not found".to_string()))?
-            .as_ref()
-            .ok_or_else(|| MapError::InternalError("error, item was not active".to_string()))?;
-        Ok(&rv.key_)
+        match self.nodes_.get(index) {
+            Some(Some(node)) => Ok(&node.key_),
+            _ => Err(self.invalid_index_(index)),
+        }
     }
 
     #[inline(always)]
     /// Returns the item value at index
     pub fn get_v(&self, index: usize) -> Result<&V, MapError> {
-        let rv = self
-            .nodes_
-            .get(index)
-            .ok_or_else(|| MapError::InternalError("error, item not found".to_string()))?
-            .as_ref()
-            .ok_or_else(|| MapError::InternalError("error, item was not active".to_string()))?;
-        Ok(&rv.value_)
+        match self.nodes_.get(index) {
+            Some(Some(node)) => Ok(&node.value_),
+            _ => Err(self.invalid_index_(index)),
+        }
     }
 
     #[inline(always)]
@@ -196,20 +1065,222 @@ where
     ///
     /// ```
     pub fn get(&self, index: usize) -> Result<(&K, &V), MapError> {
-        if index == OUT_OF_BOUNDS {
-            return Err(MapError::InternalError(format!(
-                "Invalid pointer (moved past start/end). {}:{}",
-                file!(),
-                line!()
-            )));
+        match self.nodes_.get(index) {
+            Some(Some(node)) => Ok((&node.key_, &node.value_)),
+            _ => Err(self.invalid_index_(index)),
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the item key and value at index, or None if the index is not an active item.
+    /// Unlike get() this does not treat an invalid index as an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, OUT_OF_BOUNDS};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let index = ll.ordered_insert(1,1).unwrap();
+    /// assert_eq!(ll.get_opt(index), Some((&1,&1)));
+    /// let _ = ll.pop_front();
+    /// assert_eq!(ll.get_opt(index), None);
+    /// assert_eq!(ll.get_opt(OUT_OF_BOUNDS), None);
+    /// ```
+    pub fn get_opt(&self, index: usize) -> Option<(&K, &V)> {
+        match self.nodes_.get(index) {
+            Some(Some(node)) => Some((&node.key_, &node.value_)),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the item key at index, or None if the index is not an active item.
+    pub fn get_k_opt(&self, index: usize) -> Option<&K> {
+        self.get_opt(index).map(|(k, _)| k)
+    }
+
+    #[inline(always)]
+    /// Returns the item value at index, or None if the index is not an active item.
+    pub fn get_v_opt(&self, index: usize) -> Option<&V> {
+        self.get_opt(index).map(|(_, v)| v)
+    }
+
+    #[inline(always)]
+    /// Returns the item key and a mutable reference to the item value at index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let index = ll.ordered_insert(1,1).unwrap();
+    /// let (k, v) = ll.get_pair_mut(index).unwrap();
+    /// *v += *k;
+    /// assert_eq!(ll.get(index).unwrap(), (&1,&2));
+    /// ```
+    pub fn get_pair_mut(&mut self, index: usize) -> Result<(&K, &mut V), MapError> {
+        let rv = self.node_mut_(index)?;
+        Ok((&rv.key_, &mut rv.value_))
+    }
+
+    #[inline(always)]
+    /// Returns a mutable reference to the item value at index. The links and the key are not
+    /// touched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let index = ll.ordered_insert(1,1).unwrap();
+    /// *ll.get_v_mut(index).unwrap() = 5;
+    /// assert_eq!(ll.get(index).unwrap(), (&1,&5));
+    /// ```
+    pub fn get_v_mut(&mut self, index: usize) -> Result<&mut V, MapError> {
+        Ok(self.get_pair_mut(index)?.1)
+    }
+
+    #[inline(always)]
+    /// Overwrites the item value at index, the old value is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let index = ll.ordered_insert(1,1).unwrap();
+    /// ll.update_value(index, 5).unwrap();
+    /// assert_eq!(ll.get(index).unwrap(), (&1,&5));
+    /// ```
+    pub fn update_value(&mut self, index: usize, value: V) -> Result<(), MapError> {
+        *self.get_v_mut(index)? = value;
+        Ok(())
+    }
+
+    #[inline(always)]
+    /// Replaces the item value at index and returns the old value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, Vec<i8>>::default();
+    /// let index = ll.ordered_insert(1, vec![1, 2]).unwrap();
+    /// let mut old = ll.replace_value(index, vec![3]).unwrap();
+    /// assert_eq!(old, vec![1, 2]);
+    /// old.clear();
+    /// assert_eq!(ll.get_v(index).unwrap(), &vec![3]);
+    /// ```
+    pub fn replace_value(&mut self, index: usize, value: V) -> Result<V, MapError> {
+        Ok(std::mem::replace(self.get_v_mut(index)?, value))
+    }
+
+    #[inline(always)]
+    /// Runs a closure on the item key and a mutable reference to the item value at index,
+    /// returns the closure result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let index = ll.ordered_insert(3,1).unwrap();
+    /// let old = ll.apply(index, |k, v| std::mem::replace(v, *k)).unwrap();
+    /// assert_eq!(old, 1);
+    /// assert_eq!(ll.get(index).unwrap(), (&3,&3));
+    /// assert!(ll.apply(index + 1, |_, _| ()).is_err());
+    /// ```
+    pub fn apply<R, F>(&mut self, index: usize, f: F) -> Result<R, MapError>
+    where
+        F: FnOnce(&K, &mut V) -> R,
+    {
+        let (k, v) = self.get_pair_mut(index)?;
+        Ok(f(k, v))
+    }
+
+    #[inline(always)]
+    /// Returns the metadata of the item at index, or None if the index is not an active item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8, Option<&str>>::default();
+    /// let index = ll.ordered_insert(1,1).unwrap();
+    /// assert_eq!(ll.get_meta(index), Some(&None));
+    /// assert_eq!(ll.set_meta(index, Some("circle event")).unwrap(), None);
+    /// assert_eq!(ll.get_meta(index), Some(&Some("circle event")));
+    /// assert_eq!(ll.take_meta(index).unwrap(), Some("circle event"));
+    /// assert_eq!(ll.get_meta(index), Some(&None));
+    /// assert_eq!(ll.get_meta(index + 1), None);
+    /// ```
+    pub fn get_meta(&self, index: usize) -> Option<&M> {
+        match self.nodes_.get(index) {
+            Some(Some(node)) => Some(&node.meta_),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns a mutable reference to the metadata of the item at index, or None if the index
+    /// is not an active item.
+    pub fn get_meta_mut(&mut self, index: usize) -> Option<&mut M> {
+        match self.nodes_.get_mut(index) {
+            Some(Some(node)) => Some(&mut node.meta_),
+            _ => None,
+        }
+    }
+
+    /// Sets the metadata of the item at index, returns the previous metadata.
+    /// Note that set_meta() is not recorded by transactions, an undo will not restore the
+    /// previous metadata.
+    pub fn set_meta(&mut self, index: usize, meta: M) -> Result<M, MapError> {
+        Ok(std::mem::replace(&mut self.node_mut_(index)?.meta_, meta))
+    }
+
+    /// Returns the metadata of the item at index, and resets it to `M::default()`.
+    pub fn take_meta(&mut self, index: usize) -> Result<M, MapError> {
+        Ok(std::mem::take(&mut self.node_mut_(index)?.meta_))
+    }
+
+    /// Returns the active node at the index of a PIterator
+    fn current_node_(&self, index: usize) -> Result<&Node<K, V, M>, MapError> {
+        match self.nodes_.get(index) {
+            Some(Some(node)) => Ok(node),
+            _ => Err(self.invalid_index_(index)),
+        }
+    }
+
+    /// Returns the active node at index
+    fn node_mut_(&mut self, index: usize) -> Result<&mut Node<K, V, M>, MapError> {
+        if !matches!(self.nodes_.get(index), Some(Some(_))) {
+            return Err(self.invalid_index_(index));
+        }
+        // unwrap is safe, the index was checked
+        Ok(self.nodes_[index].as_mut().unwrap())
+    }
+
+    /// Returns the error describing why index is not an active item of this list
+    fn invalid_index_(&self, index: usize) -> MapError {
+        match self.nodes_.get(index) {
+            _ if index == OUT_OF_BOUNDS => map_error!(
+                InternalError,
+                "Invalid pointer (moved past start/end) in list {}",
+                self.list_id()
+            ),
+            None => map_error!(
+                InternalError,
+                "error, item {} not found in list {}",
+                index,
+                self.list_id()
+            ),
+            Some(_) => map_error!(
+                InternalError,
+                "error, item {} was not active in list {}",
+                index,
+                self.list_id()
+            ),
         }
-        let rv = self
-            .nodes_
-            .get(index)
-            .ok_or_else(|| MapError::InternalError("error, item not found".to_string()))?
-            .as_ref()
-            .ok_or_else(|| MapError::InternalError("error, item was not active".to_string()))?;
-        Ok((&rv.key_, &rv.value_))
     }
 
     #[inline(always)]
@@ -219,29 +1290,25 @@ where
             .nodes_
             .get(index)
             .as_ref()
-            .ok_or_else(|| MapError::InternalError("error, item not found".to_string()))?
+            .ok_or_else(|| map_error!(InternalError, "error, item not found"))?
             .as_ref()
-            .ok_or_else(|| MapError::InternalError("error, item was None".to_string()))?
+            .ok_or_else(|| map_error!(InternalError, "error, item was None"))?
             .prev_;
 
         let node = self
             .nodes_
             .get(prev)
-            .ok_or_else(|| MapError::InternalError("error, prev item not found".to_string()))?
+            .ok_or_else(|| map_error!(InternalError, "error, prev item not found"))?
             .as_ref();
         Ok(&node
-            .ok_or_else(|| MapError::InternalError("error, item was not active".to_string()))?
+            .ok_or_else(|| map_error!(InternalError, "error, item was not active"))?
             .key_)
     }
 
     /// Add an item at the front of the list
     /// Note that this ignores the order of items, use with care.
     fn push_front_(&mut self, key: K, value: V) -> Result<usize, MapError> {
-        let insertion_index = if !self.id_pool_.is_empty() {
-            self.id_pool_.pop().unwrap()
-        } else {
-            self.nodes_.len()
-        };
+        let insertion_index = self.next_insertion_index_()?;
         let new_node = if let Some(ref mut prev_head) = self.nodes_.get_mut(self.head_) {
             if let Some(prev_head) = prev_head {
                 //println!("prev_head:{:?}", prev_head);
@@ -251,16 +1318,18 @@ where
                     prev_: OUT_OF_BOUNDS,
                     key_: key,
                     value_: value,
+                    meta_: M::default(),
                 };
                 self.head_ = insertion_index;
                 prev_head.prev_ = insertion_index;
                 new_node
             } else {
-                return Err(MapError::InternalError(format!(
+                return Err(map_error!(
+                    InternalError,
                     "Should not happen error™ at {}:{}",
                     file!(),
                     line!()
-                )));
+                ));
             }
         } else {
             // This will be the first element in the list
@@ -272,16 +1341,38 @@ where
                 prev_: OUT_OF_BOUNDS,
                 key_: key,
                 value_: value,
+                meta_: M::default(),
             }
         };
         //println!("push_front Pushed {:?} at index:{}", new_node, curr_len);
         Ok(self.replace_or_push_(insertion_index, new_node))
     }
 
+    #[inline(always)]
+    /// Returns a free slot index, or the index of a new slot at the end of the node vector.
+    /// Note that the returned slot must be used.
+    fn next_insertion_index_(&mut self) -> Result<usize, MapError> {
+        self.check_not_frozen_()?;
+        if let Some(index) = self.id_pool_.pop() {
+            return Ok(index);
+        }
+        if let Some(max_capacity) = self.max_capacity_ {
+            if self.nodes_.len() >= max_capacity {
+                return Err(map_error!(
+                    CapacityExceeded,
+                    "the list is limited to {} items",
+                    max_capacity
+                ));
+            }
+        }
+        Ok(self.nodes_.len())
+    }
+
     #[inline(always)]
     /// insert at position or append at back of the list
     /// Note that this ignores the order of items, use with care.
-    fn replace_or_push_(&mut self, insertion_index: usize, new_node: Node<K, V>) -> usize {
+    fn replace_or_push_(&mut self, insertion_index: usize, new_node: Node<K, V, M>) -> usize {
+        instrument::inserted();
         if insertion_index == self.nodes_.len() {
             self.nodes_.push(Some(new_node));
         } else {
@@ -302,11 +1393,7 @@ where
             return self.push_front_(key, value);
         }
 
-        let insertion_index = if !self.id_pool_.is_empty() {
-            self.id_pool_.pop().unwrap()
-        } else {
-            self.nodes_.len()
-        };
+        let insertion_index = self.next_insertion_index_()?;
 
         let new_node = if let Some(ref mut next_node) = self.nodes_.get_mut(index) {
             if let Some(ref mut next_node) = next_node {
@@ -317,15 +1404,17 @@ where
                     prev_: next_node.prev_,
                     key_: key,
                     value_: value,
+                    meta_: M::default(),
                 };
                 next_node.prev_ = insertion_index;
                 new_node
             } else {
-                return Err(MapError::InternalError(format!(
+                return Err(map_error!(
+                    InternalError,
                     "Should not happen error™ at {}:{}",
                     file!(),
                     line!()
-                )));
+                ));
             }
         } else {
             // This will be the first element in the list
@@ -337,6 +1426,7 @@ where
                 prev_: OUT_OF_BOUNDS,
                 key_: key,
                 value_: value,
+                meta_: M::default(),
             }
         };
         let prev_node = new_node.prev_;
@@ -353,19 +1443,21 @@ where
                 if let Some(prev_node) = prev_node {
                     prev_node.next_ = insertion_index;
                 } else {
-                    return Err(MapError::InternalError(format!(
+                    return Err(map_error!(
+                        InternalError,
                         "Should not happen error™ at {}:{}",
                         file!(),
                         line!()
-                    )));
+                    ));
                 }
             } else {
                 // this case should have been handled by the initial push_front()
-                return Err(MapError::InternalError(format!(
+                return Err(map_error!(
+                    InternalError,
                     "Should not happen error™ at {}:{}",
                     file!(),
                     line!()
-                )));
+                ));
             }
         } else {
             // We just pushed at the first position
@@ -375,13 +1467,67 @@ where
         Ok(insertion_index)
     }
 
+    /// Add an item at the back of the list in O(1). The key must be greater than the key of the
+    /// current tail, or an OrderViolation error is returned and nothing is inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.push_back_ordered(1, 1).unwrap();
+    /// let _ = ll.push_back_ordered(2, 2).unwrap();
+    /// assert!(ll.push_back_ordered(2, 3).is_err());
+    /// assert!(ll.push_back_ordered(0, 0).is_err());
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &1), (&2, &2)]);
+    /// ```
+    pub fn push_back_ordered(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        let key = self.normalize_key(key);
+        if let Some(tail) = self.peek_back_k() {
+            if key.cmp(tail) != Ordering::Greater {
+                return Err(map_error!(
+                    OrderViolation,
+                    "push_back_ordered() key {:?} is not greater than the tail key {:?}",
+                    key,
+                    tail
+                ));
+            }
+        }
+        self.push_back_(key, value)
+    }
+
+    /// Add an item at the front of the list in O(1). The key must be less than the key of the
+    /// current head, or an OrderViolation error is returned and nothing is inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.push_front_ordered(2, 2).unwrap();
+    /// let _ = ll.push_front_ordered(1, 1).unwrap();
+    /// assert!(ll.push_front_ordered(1, 3).is_err());
+    /// assert!(ll.push_front_ordered(3, 3).is_err());
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &1), (&2, &2)]);
+    /// ```
+    pub fn push_front_ordered(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        let key = self.normalize_key(key);
+        if let Some(head) = self.peek_front_k() {
+            if key.cmp(head) != Ordering::Less {
+                return Err(map_error!(
+                    OrderViolation,
+                    "push_front_ordered() key {:?} is not less than the head key {:?}",
+                    key,
+                    head
+                ));
+            }
+        }
+        self.push_front_(key, value)
+    }
+
     /// Add an item at the back of the list
     fn push_back_(&mut self, key: K, value: V) -> Result<usize, MapError> {
-        let insertion_index = if !self.id_pool_.is_empty() {
-            self.id_pool_.pop().unwrap()
-        } else {
-            self.nodes_.len()
-        };
+        let insertion_index = self.next_insertion_index_()?;
         let new_node = if let Some(prev_tail) = self.nodes_.get_mut(self.tail_) {
             if let Some(prev_tail) = prev_tail {
                 //println!("prev_tail:{:?}", prev_tail);
@@ -391,16 +1537,18 @@ where
                     prev_: self.tail_,
                     key_: key,
                     value_: value,
+                    meta_: M::default(),
                 };
                 self.tail_ = insertion_index;
                 prev_tail.next_ = insertion_index;
                 new_node
             } else {
-                return Err(MapError::InternalError(format!(
+                return Err(map_error!(
+                    InternalError,
                     "Should not happen error™ at {}:{}",
                     file!(),
                     line!()
-                )));
+                ));
             }
         } else {
             // This will be the first element in the list
@@ -412,6 +1560,7 @@ where
                 prev_: OUT_OF_BOUNDS,
                 key_: key,
                 value_: value,
+                meta_: M::default(),
             }
         };
         //println!("push_back Pushed {:?} at index:{}", new_node, insertion_index);
@@ -446,7 +1595,10 @@ where
     /// assert_eq!(ll.get(ll.head()).unwrap(), (&0,&0));
     /// ```
     pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
-        self.ordered_insert_pos(key, value, self.head_)
+        let key = self.normalize_key(key);
+        self.maintain_jump_table_();
+        let position = self.jump_hint_(&key).unwrap_or(self.head_);
+        self.ordered_insert_pos_(key, value, position)
     }
 
     /// Insert item by Order (lesser first) with a position hint.
@@ -470,9 +1622,149 @@ where
         value: V,
         position: usize,
     ) -> Result<usize, MapError> {
+        let key = self.normalize_key(key);
+        self.check_hint_(&key, position)?;
+        self.ordered_insert_pos_(key, value, position)
+    }
+
+    #[inline(always)]
+    /// Insert an already normalized key by Order (lesser first) with a position hint.
+    fn ordered_insert_pos_(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        Ok(self.insert_pos_(key, value, position)?.0)
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`], but reports if the item was inserted or if the
+    /// key already existed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{InsertResult, LinkedList};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let rv = ll.ordered_insert_result(1, 1).unwrap();
+    /// assert!(rv.inserted);
+    /// let rv = ll.ordered_insert_result(1, 100).unwrap(); // <- this is a NOP
+    /// assert_eq!(rv, InsertResult { index: 0, inserted: false });
+    /// assert_eq!(ll.get(rv.index).unwrap(), (&1, &1));
+    /// ```
+    pub fn ordered_insert_result(&mut self, key: K, value: V) -> Result<InsertResult, MapError> {
+        let key = self.normalize_key(key);
+        self.maintain_jump_table_();
+        let position = self.jump_hint_(&key).unwrap_or(self.head_);
+        let (index, rejected) = self.insert_pos_(key, value, position)?;
+        Ok(InsertResult {
+            index,
+            inserted: rejected.is_none(),
+        })
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], but reports if the item was inserted or if
+    /// the key already existed.
+    pub fn ordered_insert_pos_result(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<InsertResult, MapError> {
+        let key = self.normalize_key(key);
+        self.check_hint_(&key, position)?;
+        let (index, rejected) = self.insert_pos_(key, value, position)?;
+        Ok(InsertResult {
+            index,
+            inserted: rejected.is_none(),
+        })
+    }
+
+    #[inline(always)]
+    /// Insert item by Order (lesser first), or overwrite the value if the key already exists.
+    /// The existing key is kept. This is C++ std::map::insert_or_assign().
+    /// Reports if the item was inserted or if the value was assigned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let rv = ll.ordered_insert_or_assign(1, 1).unwrap();
+    /// assert!(rv.inserted);
+    /// let rv = ll.ordered_insert_or_assign(1, 100).unwrap();
+    /// assert!(!rv.inserted);
+    /// assert_eq!(ll.get(rv.index).unwrap(), (&1, &100));
+    /// ```
+    pub fn ordered_insert_or_assign(&mut self, key: K, value: V) -> Result<InsertResult, MapError> {
+        let key = self.normalize_key(key);
+        self.maintain_jump_table_();
+        let position = self.jump_hint_(&key).unwrap_or(self.head_);
+        self.insert_or_assign_pos_(key, value, position)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_or_assign()`] with a position hint.
+    pub fn ordered_insert_or_assign_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<InsertResult, MapError> {
+        let key = self.normalize_key(key);
+        self.check_hint_(&key, position)?;
+        self.insert_or_assign_pos_(key, value, position)
+    }
+
+    #[inline(always)]
+    /// Insert or assign an already normalized key with a position hint.
+    fn insert_or_assign_pos_(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<InsertResult, MapError> {
+        match self.insert_pos_(key, value, position)? {
+            (index, None) => Ok(InsertResult {
+                index,
+                inserted: true,
+            }),
+            (index, Some((_, value))) => {
+                self.update_value(index, value)?;
+                Ok(InsertResult {
+                    index,
+                    inserted: false,
+                })
+            }
+        }
+    }
+
+    /// Insert an already normalized key by Order (lesser first) with a position hint.
+    /// If the key already exists the list is not modified, and the rejected key and value are
+    /// returned together with the index of the existing item.
+    fn insert_pos_(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<(usize, Option<(K, V)>), MapError> {
         if self.head_ == OUT_OF_BOUNDS {
             // list is empty, ignore position and insert
-            return self.push_back_(key, value);
+            return Ok((self.push_back_(key, value)?, None));
+        }
+        if self.search_strategy_ == SearchStrategy::Galloping && self.get_opt(position).is_some() {
+            let (lower_bound, hops) = self.gallop_lower_bound_(&key, position);
+            self.report_scan_(ScanOperation::Insert, &key, position, hops);
+            return match lower_bound {
+                Some(index)
+                    if self.get_k_opt(index).map(|k| key.cmp(k)) == Some(Ordering::Equal) =>
+                {
+                    let index = self.apply_equal_policy_(&key, index);
+                    Ok((index, Some((key, value)))) // Insert with an already existing key is a 'nop'
+                }
+                Some(index) => Ok((self.insert_before_(index, key, value)?, None)),
+                None => Ok((self.push_back_(key, value)?, None)),
+            };
         }
         //println!("insert at position {}, key={:?} head={}", position, key, self.head_);
         let mut insert_before: Option<usize> = None;
@@ -486,27 +1778,27 @@ where
                     .unwrap()
                     .as_ref()
                     .ok_or_else(|| {
-                        MapError::InternalError(format!(
-                            "head_ item was None {}:{}",
-                            file!(),
-                            line!()
-                        ))
+                        map_error!(InternalError, "head_ item was None {}:{}", file!(), line!())
                     })?,
             ),
         };
 
         let cmp = key.cmp(&first_node.key_);
         //println!("curr_index:{}, first_node.key={:?}, cmp={:?}", curr_index, first_node.key, cmp);
+        let mut hops = 0_usize;
 
         #[allow(clippy::collapsible_else_if)] // false positive?
         if (cmp == Ordering::Greater) || (cmp == Ordering::Equal) {
             //println!("search down, insert after equals");
             // we are searching down the list, stop at first Less
             while let Some(Some(sample)) = self.nodes_.get(curr_index) {
+                hops += 1;
                 // move past Ordering::Equal
                 match key.cmp(&sample.key_) {
                     Ordering::Equal => {
-                        return Ok(curr_index); // Insert with an already existing key is a 'nop'
+                        self.report_scan_(ScanOperation::Insert, &key, position, hops);
+                        let index = self.apply_equal_policy_(&key, curr_index);
+                        return Ok((index, Some((key, value)))); // Insert with an already existing key is a 'nop'
                     }
                     Ordering::Less => {
                         insert_before = Some(curr_index);
@@ -524,9 +1816,12 @@ where
             //println!("search up, insert after equals. tmp insert_before:{:?}", insert_before);
             // we are searching up the list, stop at first Equal or Greater
             while let Some(Some(sample)) = self.nodes_.get(curr_index) {
+                hops += 1;
                 match key.cmp(&sample.key_) {
                     Ordering::Equal => {
-                        return Ok(curr_index); // Insert with an already existing key is a 'nop'
+                        self.report_scan_(ScanOperation::Insert, &key, position, hops);
+                        let index = self.apply_equal_policy_(&key, curr_index);
+                        return Ok((index, Some((key, value)))); // Insert with an already existing key is a 'nop'
                     }
                     Ordering::Less => {
                         insert_before = Some(curr_index);
@@ -541,75 +1836,608 @@ where
             }
         }
 
+        self.report_scan_(ScanOperation::Insert, &key, position, hops);
         if let Some(insert_before) = insert_before {
             //println!("inserting before {}", insert_before);
-            self.insert_before_(insert_before, key, value)
+            Ok((self.insert_before_(insert_before, key, value)?, None))
         } else {
             //println!("pushing at the back");
-            self.push_back_(key, value)
+            Ok((self.push_back_(key, value)?, None))
+        }
+    }
+
+    /// Insert item by Order (lesser first), using the position of a [`PIterator`] as the
+    /// position hint. This is the `map.insert(it, value)` of C++.
+    /// Any kind of [`SharedList`] PIterator can be used. Returns a HintMismatch error if the
+    /// PIterator belongs to another list.
+    /// Note that insert(key, value) is a NOP if the key already exists, not even the new value will be used.
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, PIterator};
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// let ll = Rc::new(RefCell::new(LinkedList::<i8, i8>::default()));
+    /// let _ = ll.borrow_mut().ordered_insert(1, 1);
+    /// let p = PIterator::new(Rc::clone(&ll)).unwrap();
+    /// let two = ll.borrow_mut().ordered_insert_hint(2, 2, &p).unwrap();
+    /// assert_eq!(ll.borrow().tail(), two);
+    /// let mut other = LinkedList::<i8, i8>::default();
+    /// assert!(other.ordered_insert_hint(2, 2, &p).is_err());
+    /// ```
+    pub fn ordered_insert_hint<P>(
+        &mut self,
+        key: K,
+        value: V,
+        hint: &PIterator<K, V, M, P>,
+    ) -> Result<usize, MapError>
+    where
+        P: SharedList<K, V, M>,
+    {
+        if !hint.list.is_list(self) {
+            return Err(map_error!(
+                HintMismatch,
+                "the PIterator hint belongs to another list. {}:{}",
+                file!(),
+                line!()
+            ));
+        }
+        self.ordered_insert_pos(key, value, hint.current)
+    }
+
+    /// Inserts every `(key, value, hint)` triple with [`LinkedList::ordered_insert_pos()`].
+    /// A hint that is not a valid index (e.g. OUT_OF_BOUNDS) is replaced by the index of the
+    /// previous successful insert. Returns the result of every insert, in order.
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, OUT_OF_BOUNDS};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let rv = ll.extend_with_hints(vec![(1, 1, OUT_OF_BOUNDS), (3, 3, OUT_OF_BOUNDS), (2, 2, 0)]);
+    /// assert_eq!(rv.len(), 3);
+    /// assert!(rv.iter().all(|r| r.is_ok()));
+    /// assert_eq!(ll.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn extend_with_hints<I>(&mut self, iter: I) -> Vec<Result<usize, MapError>>
+    where
+        I: IntoIterator<Item = (K, V, usize)>,
+    {
+        let iter = iter.into_iter();
+        let mut rv = Vec::with_capacity(iter.size_hint().0);
+        let mut last = self.tail_;
+        for (key, value, hint) in iter {
+            let position = if let Some(Some(_)) = self.nodes_.get(hint) {
+                hint
+            } else {
+                last
+            };
+            let result = self.ordered_insert_pos(key, value, position);
+            if let Ok(index) = result {
+                last = index;
+            }
+            rv.push(result);
+        }
+        rv
+    }
+
+    /// Builds a list from (key, value) pairs. The pairs rejected because their key was already
+    /// inserted (the NOP-on-duplicate rule) are returned, in iteration order, instead of being
+    /// silently dropped. Every search starts from the previously inserted item.
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let (ll, rejected) =
+    ///     LinkedList::<i8, char>::from_iter_report_duplicates(vec![(1, 'a'), (2, 'b'), (1, 'c')])
+    ///         .unwrap();
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &'a'), (&2, &'b')]);
+    /// assert_eq!(rejected, vec![(1, 'c')]);
+    /// ```
+    pub fn from_iter_report_duplicates<I>(iter: I) -> Result<(Self, Vec<(K, V)>), MapError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut list = Self::default();
+        let mut rejected = Vec::new();
+        let mut last = OUT_OF_BOUNDS;
+        for (key, value) in iter {
+            let (index, duplicate) = list.insert_pos_(key, value, last)?;
+            if let Some(duplicate) = duplicate {
+                rejected.push(duplicate);
+            }
+            last = index;
+        }
+        Ok((list, rejected))
+    }
+
+    /// Returns a mutable reference to the value of `key`. If the key is missing, V::default()
+    /// is first inserted at the correct position, using `hint` as a position hint.
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<char, u32>::default();
+    /// for c in "abracadabra".chars() {
+    ///     *ll.get_or_default(c, ll.tail()).unwrap() += 1;
+    /// }
+    /// assert_eq!(ll.len(), 5);
+    /// assert_eq!(ll.get(ll.head()).unwrap(), (&'a', &5));
+    /// assert_eq!(ll.get(ll.tail()).unwrap(), (&'r', &2));
+    /// ```
+    pub fn get_or_default(&mut self, key: K, hint: usize) -> Result<&mut V, MapError>
+    where
+        V: Default,
+    {
+        let index = self.ordered_insert_pos(key, V::default(), hint)?;
+        Ok(self.get_pair_mut(index)?.1)
+    }
+
+    /// Insert item by Order (lesser first). If the key already exists, the new value is merged
+    /// into the existing value with `merge(existing, new)` instead of being dropped.
+    /// Returns the index of the inserted or merged item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, Vec<char>>::default();
+    /// let merge = |existing: &mut Vec<char>, new: Vec<char>| existing.extend(new);
+    /// let a = ll.ordered_insert_merge(1, vec!['a'], merge).unwrap();
+    /// let b = ll.ordered_insert_merge(1, vec!['b'], merge).unwrap();
+    /// assert_eq!(a, b);
+    /// assert_eq!(ll.get_v(a).unwrap(), &vec!['a', 'b']);
+    /// ```
+    pub fn ordered_insert_merge<F>(&mut self, key: K, value: V, merge: F) -> Result<usize, MapError>
+    where
+        F: FnOnce(&mut V, V),
+    {
+        let key = self.normalize_key(key);
+        self.maintain_jump_table_();
+        let position = self.jump_hint_(&key).unwrap_or(self.head_);
+        self.insert_merge_(key, value, position, merge)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_merge()`], with a position hint.
+    pub fn ordered_insert_merge_pos<F>(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+        merge: F,
+    ) -> Result<usize, MapError>
+    where
+        F: FnOnce(&mut V, V),
+    {
+        let key = self.normalize_key(key);
+        self.check_hint_(&key, position)?;
+        self.insert_merge_(key, value, position, merge)
+    }
+
+    /// Insert an already normalized key, or merge the value into the existing item
+    fn insert_merge_<F>(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+        merge: F,
+    ) -> Result<usize, MapError>
+    where
+        F: FnOnce(&mut V, V),
+    {
+        let (index, rejected) = self.insert_pos_(key, value, position)?;
+        if let Some((_, value)) = rejected {
+            merge(self.get_pair_mut(index)?.1, value);
+        }
+        Ok(index)
+    }
+
+    /// Returns the first element in the container whose key is not considered to go
+    /// before position (i.e., either it is equivalent or goes after).
+    /// If 'search_from_head' is true the search will be performed from the head otherwise from the tail.
+    /// Returns None if no data is found
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// ll.ordered_insert(1,1);
+    /// ll.ordered_insert(2,2);
+    /// ll.ordered_insert(3,3);
+    /// let lb = ll.get(ll.lower_bound(&2).unwrap().unwrap()).unwrap();
+    /// assert_eq!(lb, (&2,&2));
+    /// let lb = ll.get(ll.lower_bound(&0).unwrap().unwrap()).unwrap();
+    /// assert_eq!(lb, (&1,&1));
+    /// let lb = ll.get(ll.lower_bound(&1).unwrap().unwrap()).unwrap();
+    /// assert_eq!(lb, (&1,&1));
+    /// let lb = ll.get(ll.lower_bound(&3).unwrap().unwrap()).unwrap();
+    /// assert_eq!(lb, (&3,&3));
+    /// assert!( ll.lower_bound(&4).unwrap().is_none());
+    /// ```
+    pub fn lower_bound<Q>(&self, key: &Q) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        #[cfg(feature = "console_debug")]
+        {
+            let mut iter = self.iter();
+            let mut flips = 0_usize;
+            let mut last_cmp = iter.next().map(|(first, _)| key.cmp(first.borrow()));
+
+            for (node, _) in iter {
+                let cmp = Some(key.cmp(node.borrow()));
+                if cmp != last_cmp {
+                    last_cmp = cmp;
+                    flips += 1;
+                }
+            }
+            if flips > 1 {
+                println!("\nkey={:?}", key);
+                for (n, _) in self.iter() {
+                    let n: &Q = n.borrow();
+                    println!("key.cmp({:?})=={:?}-{:?}", n, n.cmp(key), key.cmp(n));
+                }
+            }
+        }
+
+        if let Some(position) = self.jump_hint_(key) {
+            return self.lower_bound_pos(key, position);
         }
+        // sequential search from the rear
+        if self.tail_ == OUT_OF_BOUNDS {
+            return Ok(None);
+        }
+        let mut last_match: Option<usize> = None;
+        let mut curr_index = self.tail_;
+        let mut hops = 0_usize;
+        while let Some(Some(sample)) = self.nodes_.get(curr_index) {
+            hops += 1;
+            if key.cmp(sample.key_.borrow()) != Ordering::Greater {
+                //println!("ignoring :{:?} ", sample.key);
+                last_match = Some(curr_index);
+                curr_index = sample.prev_;
+            } else {
+                break;
+            }
+        }
+        self.report_scan_(ScanOperation::LowerBound, &key, self.tail_, hops);
+        Ok(self.equal_policy_bound_(key, last_match))
+    }
+
+    /// Returns the first element in the container whose key is not considered to go
+    /// before position (i.e., either it is equivalent or goes after).
+    /// The search starts at `position` and moves in the appropriate direction, so a good position
+    /// hint makes this much faster than lower_bound(). An invalid position will search from the tail.
+    /// Returns None if no data is found
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..10 {
+    ///     let _ = ll.ordered_insert_pos(i * 2, i, ll.tail());
+    /// }
+    /// let lb = ll.lower_bound_pos(&7, 2).unwrap().unwrap();
+    /// assert_eq!(ll.get(lb).unwrap(), (&8, &4));
+    /// let lb = ll.lower_bound_pos(&1, lb).unwrap().unwrap();
+    /// assert_eq!(ll.get(lb).unwrap(), (&2, &1));
+    /// assert!(ll.lower_bound_pos(&19, lb).unwrap().is_none());
+    /// ```
+    pub fn lower_bound_pos<Q>(&self, key: &Q, position: usize) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let start = match self.nodes_.get(position) {
+            Some(Some(node)) => node,
+            _ => return self.lower_bound(key),
+        };
+        if self.search_strategy_ == SearchStrategy::Galloping {
+            let (rv, hops) = self.gallop_lower_bound_(key, position);
+            self.report_scan_(ScanOperation::LowerBound, &key, position, hops);
+            return Ok(self.equal_policy_bound_(key, rv));
+        }
+        let mut hops = 1_usize;
+        let rv = if key.cmp(start.key_.borrow()) != Ordering::Greater {
+            // search up the list, stop at the first key that goes before `key`
+            let mut last_match = position;
+            let mut curr_index = start.prev_;
+            while let Some(Some(sample)) = self.nodes_.get(curr_index) {
+                hops += 1;
+                if key.cmp(sample.key_.borrow()) == Ordering::Greater {
+                    break;
+                }
+                last_match = curr_index;
+                curr_index = sample.prev_;
+            }
+            Some(last_match)
+        } else {
+            // search down the list, stop at the first key that does not go before `key`
+            let mut rv = None;
+            let mut curr_index = start.next_;
+            while let Some(Some(sample)) = self.nodes_.get(curr_index) {
+                hops += 1;
+                if key.cmp(sample.key_.borrow()) != Ordering::Greater {
+                    rv = Some(curr_index);
+                    break;
+                }
+                curr_index = sample.next_;
+            }
+            rv
+        };
+        self.report_scan_(ScanOperation::LowerBound, &key, position, hops);
+        Ok(self.equal_policy_bound_(key, rv))
+    }
+
+    /// Returns the first element in the container whose key is considered to go after `key`,
+    /// like C++ `std::map::upper_bound`.
+    /// Returns None if no data is found
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..5 {
+    ///     let _ = ll.ordered_insert(i * 2, i);
+    /// }
+    /// assert_eq!(ll.get_k(ll.upper_bound(&4).unwrap().unwrap()).unwrap(), &6);
+    /// assert_eq!(ll.get_k(ll.upper_bound(&5).unwrap().unwrap()).unwrap(), &6);
+    /// assert_eq!(ll.get_k(ll.upper_bound(&-1).unwrap().unwrap()).unwrap(), &0);
+    /// assert!(ll.upper_bound(&8).unwrap().is_none());
+    /// ```
+    pub fn upper_bound<Q>(&self, key: &Q) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let lower_bound = self.lower_bound(key)?;
+        self.skip_equal_(key, lower_bound)
+    }
+
+    /// Same as [`LinkedList::upper_bound()`], but the search starts at `position`.
+    /// An invalid position will search from the tail.
+    pub fn upper_bound_pos<Q>(&self, key: &Q, position: usize) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let lower_bound = self.lower_bound_pos(key, position)?;
+        self.skip_equal_(key, lower_bound)
+    }
+
+    /// Returns the lower bound and the upper bound of `key`, like C++ `std::map::equal_range`.
+    /// The list is only searched once, the keys are unique so the upper bound is either the
+    /// lower bound or the item after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let a = ll.ordered_insert(1, 1).unwrap();
+    /// let b = ll.ordered_insert(3, 3).unwrap();
+    /// assert_eq!(ll.equal_range(&1).unwrap(), (Some(a), Some(b)));
+    /// assert_eq!(ll.equal_range(&2).unwrap(), (Some(b), Some(b)));
+    /// assert_eq!(ll.equal_range(&3).unwrap(), (Some(b), None));
+    /// ```
+    pub fn equal_range<Q>(&self, key: &Q) -> Result<(Option<usize>, Option<usize>), MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let lower_bound = self.lower_bound(key)?;
+        Ok((lower_bound, self.skip_equal_(key, lower_bound)?))
+    }
+
+    /// Turns a lower bound into an upper bound, the keys are unique so only one item can be
+    /// equal to `key`.
+    fn skip_equal_<Q>(&self, key: &Q, lower_bound: Option<usize>) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        match lower_bound {
+            Some(index) if key.cmp(self.get_k(index)?.borrow()) == Ordering::Equal => {
+                let next = self.current_node_(index)?.next_;
+                Ok(Some(next).filter(|next| *next != OUT_OF_BOUNDS))
+            }
+            rv => Ok(rv),
+        }
+    }
+
+    /// Same as [`LinkedList::lower_bound_pos()`], but the position where the search ended is
+    /// returned together with the lower bound, so that a failed lookup can be followed by an
+    /// insert that doesn't have to search again.
+    /// Pass OUT_OF_BOUNDS as position to search like lower_bound() does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..10 {
+    ///     let _ = ll.ordered_insert_pos(i * 2, i, ll.tail());
+    /// }
+    /// let lb = ll.lower_bound_hint(&7, ll.head()).unwrap();
+    /// assert_eq!(ll.get_k(lb.index.unwrap()).unwrap(), &8);
+    /// let index = ll.ordered_insert_pos(7, 0, lb.hint).unwrap();
+    /// assert_eq!(ll.get_prev_k(index).unwrap(), &6);
+    /// let lb = ll.lower_bound_hint(&21, lb.hint).unwrap();
+    /// assert_eq!((lb.index, lb.hint), (None, ll.tail()));
+    /// ```
+    pub fn lower_bound_hint<Q>(&self, key: &Q, position: usize) -> Result<LowerBound, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        // a search that finds nothing has walked past the tail
+        let index = self.lower_bound_pos(key, position)?;
+        Ok(LowerBound {
+            index,
+            hint: index.unwrap_or(self.tail_),
+        })
     }
 
-    /// Returns the first element in the container whose key is not considered to go
-    /// before position (i.e., either it is equivalent or goes after).
-    /// If 'search_from_head' is true the search will be performed from the head otherwise from the tail.
-    /// Returns None if no data is found
+    /// Returns the index of the item with `key`, or None if the key is not in the list.
+    /// The search is the same as the one ordered_insert() does, but the key is not normalized,
+    /// see [`LinkedList::find_normalized()`].
+    ///
     /// # Examples
     ///
     /// ```
     /// # use cpp_map::LinkedList;
     /// let mut ll = LinkedList::<i8, i8>::default();
-    /// ll.ordered_insert(1,1);
-    /// ll.ordered_insert(2,2);
-    /// ll.ordered_insert(3,3);
-    /// let lb = ll.get(ll.lower_bound(2).unwrap().unwrap()).unwrap();
-    /// assert_eq!(lb, (&2,&2));
-    /// let lb = ll.get(ll.lower_bound(0).unwrap().unwrap()).unwrap();
-    /// assert_eq!(lb, (&1,&1));
-    /// let lb = ll.get(ll.lower_bound(1).unwrap().unwrap()).unwrap();
-    /// assert_eq!(lb, (&1,&1));
-    /// let lb = ll.get(ll.lower_bound(3).unwrap().unwrap()).unwrap();
-    /// assert_eq!(lb, (&3,&3));
-    /// assert!( ll.lower_bound(4).unwrap().is_none());
+    /// for i in 0..5 {
+    ///     let _ = ll.ordered_insert(i * 2, i);
+    /// }
+    /// let index = ll.find(&4).unwrap();
+    /// assert_eq!(ll.get(index).unwrap(), (&4, &2));
+    /// assert!(ll.find(&5).is_none());
+    /// assert_eq!(ll.find_pos(&8, index), Some(ll.tail()));
     /// ```
-    pub fn lower_bound(&self, key: K) -> Result<Option<usize>, MapError> {
-        #[cfg(feature = "console_debug")]
-        {
-            let mut iter = self.iter();
-            let mut flips = 0_usize;
-            let mut last_cmp = iter.next().map(|(first, _)| key.cmp(first));
+    pub fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        self.find_pos(key, self.jump_hint_(key).unwrap_or(self.head_))
+    }
 
-            for (node, _) in iter {
-                let cmp = Some(key.cmp(node));
-                if cmp != last_cmp {
-                    last_cmp = cmp;
-                    flips += 1;
+    #[inline(always)]
+    /// Returns true if the list contains `key`. See [`LinkedList::find()`].
+    /// The key is not normalized, see [`LinkedList::contains_key_normalized()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<String, i8>::default();
+    /// let _ = ll.ordered_insert("a".to_string(), 1);
+    /// assert!(ll.contains_key("a"));
+    /// assert!(!ll.contains_key("b"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Same as [`LinkedList::find()`], but the search starts at `position`.
+    /// An invalid position will search from the tail.
+    pub fn find_pos<Q>(&self, key: &Q, position: usize) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        self.lower_bound_pos(key, position)
+            .ok()
+            .flatten()
+            .filter(|i| self.get_k_opt(*i).map(|k| key.cmp(k.borrow())) == Some(Ordering::Equal))
+    }
+
+    /// Returns the lower bound of every key in `sorted_keys`, in the same order. The keys must
+    /// be sorted (duplicates are allowed), the answers are then found in a single forward
+    /// traversal of the list instead of one search per key.
+    /// Returns an OrderViolation error if a key goes before the previous key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..5 {
+    ///     let _ = ll.ordered_insert_pos(i * 2, i, ll.tail());
+    /// }
+    /// let lbs = ll.lower_bound_many(&[-1, 3, 3, 8, 9]).unwrap();
+    /// let keys = lbs.iter().map(|lb| lb.map(|i| *ll.get_k(i).unwrap())).collect::<Vec<_>>();
+    /// assert_eq!(keys, vec![Some(0), Some(4), Some(4), Some(8), None]);
+    /// assert!(ll.lower_bound_many(&[3, 2]).is_err());
+    /// ```
+    pub fn lower_bound_many<'a, Q, I>(&self, sorted_keys: I) -> Result<Vec<Option<usize>>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized + 'a,
+        I: IntoIterator<Item = &'a Q>,
+    {
+        let sorted_keys = sorted_keys.into_iter();
+        let mut rv = Vec::with_capacity(sorted_keys.size_hint().0);
+        let mut prev_key: Option<&Q> = None;
+        let mut curr_index = self.head_;
+        for key in sorted_keys {
+            if let Some(prev_key) = prev_key {
+                if key.cmp(prev_key) == Ordering::Less {
+                    return Err(map_error!(
+                        OrderViolation,
+                        "lower_bound_many() key {:?} goes before the previous key {:?}",
+                        key,
+                        prev_key
+                    ));
                 }
             }
-            if flips > 1 {
-                println!("\nkey={:?}", key);
-                for (n, _) in self.iter() {
-                    println!("key.cmp({:?})=={:?}-{:?}", n, n.cmp(&key), key.cmp(n));
+            prev_key = Some(key);
+            // move past every key that goes before `key`
+            while let Some(Some(sample)) = self.nodes_.get(curr_index) {
+                if key.cmp(sample.key_.borrow()) != Ordering::Greater {
+                    break;
                 }
+                curr_index = sample.next_;
             }
+            rv.push(if curr_index == OUT_OF_BOUNDS {
+                None
+            } else {
+                Some(curr_index)
+            });
         }
+        Ok(rv)
+    }
 
-        // sequential search from the rear
-        if self.tail_ == OUT_OF_BOUNDS {
-            return Ok(None);
-        }
-        let mut last_match: Option<usize> = None;
-        let mut curr_index = self.tail_;
-        while let Some(Some(sample)) = self.nodes_.get(curr_index) {
-            if key.cmp(&sample.key_) != Ordering::Greater {
-                //println!("ignoring :{:?} ", sample.key);
-                last_match = Some(curr_index);
-                curr_index = sample.prev_;
-            } else {
-                return Ok(last_match);
+    /// Returns the item with the key nearest to `key`, as measured by `distance(key, item_key)`.
+    /// Only the lower bound of `key` and its predecessor are measured, so the distance must grow
+    /// as the keys move away from `key` in the sort order. On a tie the lesser key is returned.
+    /// Returns None if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i32, char>::default();
+    /// let _ = ll.ordered_insert(10, 'a');
+    /// let _ = ll.ordered_insert(20, 'b');
+    /// let distance = |a: &i32, b: &i32| (a - b).abs();
+    /// assert_eq!(ll.get_v(ll.nearest(&14, distance).unwrap().unwrap()).unwrap(), &'a');
+    /// assert_eq!(ll.get_v(ll.nearest(&16, distance).unwrap().unwrap()).unwrap(), &'b');
+    /// assert_eq!(ll.get_v(ll.nearest(&15, distance).unwrap().unwrap()).unwrap(), &'a');
+    /// assert_eq!(ll.get_v(ll.nearest(&99, distance).unwrap().unwrap()).unwrap(), &'b');
+    /// ```
+    pub fn nearest<Q, D, F>(&self, key: &Q, distance: F) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+        D: PartialOrd,
+        F: Fn(&Q, &K) -> D,
+    {
+        let (before, after) = match self.lower_bound(key)? {
+            Some(lb) => (
+                self.nodes_[lb].as_ref().map_or(OUT_OF_BOUNDS, |n| n.prev_),
+                lb,
+            ),
+            None => (self.tail_, OUT_OF_BOUNDS),
+        };
+        Ok(match (self.get_k_opt(before), self.get_k_opt(after)) {
+            (Some(b), Some(a)) => {
+                if distance(key, a) < distance(key, b) {
+                    Some(after)
+                } else {
+                    Some(before)
+                }
             }
-        }
-        Ok(last_match)
+            (Some(_), None) => Some(before),
+            (None, Some(_)) => Some(after),
+            (None, None) => None,
+        })
     }
 
     #[inline(always)]
@@ -644,6 +2472,63 @@ where
         self.remove_(self.tail_)
     }
 
+    /// Removes the item with `key` and returns it, or None if the key is not in the list.
+    /// The slot is recycled like any removed item. The key is not normalized, see
+    /// [`LinkedList::remove_by_key_normalized()`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.ordered_insert(1, 0);
+    /// let _ = ll.ordered_insert(2, 1);
+    /// assert_eq!(ll.remove_by_key(&1).unwrap(), Some((1, 0)));
+    /// assert_eq!(ll.remove_by_key(&1).unwrap(), None);
+    /// assert_eq!(ll.len(), 1);
+    /// ```
+    pub fn remove_by_key<Q>(&mut self, key: &Q) -> Result<Option<(K, V)>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        self.check_not_frozen_()?;
+        match self.find(key) {
+            Some(index) => self.remove_(index),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes every item for which `f` returns false, in a single pass in sorted order.
+    /// The slots of the removed items are recycled. Returns the number of removed items.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..6 {
+    ///     let _ = ll.ordered_insert(i, i * 10);
+    /// }
+    /// assert_eq!(ll.retain(|k, v| k % 2 == 0 && *v < 40).unwrap(), 4);
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&0, &0), (&2, &20)]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F) -> Result<usize, MapError>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.check_not_frozen_()?;
+        let mut removed = 0_usize;
+        let mut curr_index = self.head_;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            if f(&node.key_, &node.value_) {
+                curr_index = node.next_;
+            } else {
+                curr_index = self.remove__(curr_index)?.2;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
     #[inline(always)]
     /// Peek the head key
     ///
@@ -680,6 +2565,50 @@ where
         }
     }
 
+    #[inline(always)]
+    /// Returns the head key and a mutable reference to the head value
+    ///
+    /// # Examples
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// assert!(ll.first_mut().is_none());
+    /// let _ = ll.ordered_insert(1, 0); // 0
+    /// let _ = ll.ordered_insert(2, 1); // 1
+    /// if let Some((_, v)) = ll.first_mut() {
+    ///     *v = 10;
+    /// }
+    /// assert_eq!(ll.get(ll.head()).unwrap(), (&1_i8, &10_i8));
+    /// ```
+    pub fn first_mut(&mut self) -> Option<(&K, &mut V)> {
+        match self.nodes_.get_mut(self.head_) {
+            Some(Some(node)) => Some((&node.key_, &mut node.value_)),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the tail key and a mutable reference to the tail value
+    ///
+    /// # Examples
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// assert!(ll.last_mut().is_none());
+    /// let _ = ll.ordered_insert(1, 0); // 0
+    /// let _ = ll.ordered_insert(2, 1); // 1
+    /// if let Some((_, v)) = ll.last_mut() {
+    ///     *v = 10;
+    /// }
+    /// assert_eq!(ll.get(ll.tail()).unwrap(), (&2_i8, &10_i8));
+    /// ```
+    pub fn last_mut(&mut self) -> Option<(&K, &mut V)> {
+        match self.nodes_.get_mut(self.tail_) {
+            Some(Some(node)) => Some((&node.key_, &mut node.value_)),
+            _ => None,
+        }
+    }
+
     #[inline(always)]
     /// Return the tail index
     pub fn tail(&self) -> usize {
@@ -701,12 +2630,14 @@ where
 
     /// Disconnect and remove the item at index, return item value if found
     fn remove__(&mut self, index: usize) -> Result<(usize, (K, V), usize), MapError> {
+        self.check_not_frozen_()?;
         if self.head_ == OUT_OF_BOUNDS {
-            return Err(MapError::InternalError(format!(
+            return Err(map_error!(
+                InternalError,
                 "Could not find element to remove {}:{}",
                 file!(),
                 line!()
-            )));
+            ));
         }
         //println!("remove {} before:{:?}", index, self);
         let rv = if self.head_ != OUT_OF_BOUNDS {
@@ -724,11 +2655,12 @@ where
                             // node had a next
                             operation.change_next_ = Some((node.next_, node.prev_));
                         } else {
-                            return Err(MapError::InternalError(format!(
+                            return Err(map_error!(
+                                InternalError,
                                 "Should not happen error™ at {}:{}",
                                 file!(),
                                 line!()
-                            )));
+                            ));
                         }
                     }
 
@@ -738,27 +2670,31 @@ where
                             // node had a prev
                             operation.change_prev_ = Some((node.prev_, node.next_));
                         } else {
-                            return Err(MapError::InternalError(format!(
+                            return Err(map_error!(
+                                InternalError,
                                 "Should not happen error™ at {}:{}",
                                 file!(),
                                 line!()
-                            )));
+                            ));
                         }
                     }
                     Some(operation)
                 } else {
-                    return Err(MapError::InternalError(format!(
+                    return Err(map_error!(
+                        InternalError,
                         "Should not happen error™ at {}:{}",
                         file!(),
                         line!()
-                    )));
+                    ));
                 }
             } else {
                 // index was not found, todo: report error?
                 None
             };
             if let Some(operation) = operation {
-                Some(self.erase_node_(operation)?)
+                let rv = self.erase_node_(operation)?;
+                instrument::removed();
+                Some(rv)
             } else {
                 None
             }
@@ -767,11 +2703,12 @@ where
             None
         };
         rv.ok_or_else(|| {
-            MapError::InternalError(format!(
+            map_error!(
+                InternalError,
                 "Could not find element to remove {}:{}",
                 file!(),
                 line!()
-            ))
+            )
         })
     }
 
@@ -793,11 +2730,12 @@ where
                         node.next_ = new_next;
                     }
                     _ => {
-                        return Err(MapError::InternalError(format!(
+                        return Err(map_error!(
+                            InternalError,
                             "Should not happen error™ at {}:{}",
                             file!(),
                             line!()
-                        )))
+                        ))
                     }
                 };
                 match self.nodes_.get_mut(next_i) {
@@ -805,11 +2743,12 @@ where
                         node.prev_ = new_prev;
                     }
                     _ => {
-                        return Err(MapError::InternalError(format!(
+                        return Err(map_error!(
+                            InternalError,
                             "Should not happen error™ at {}:{}",
                             file!(),
                             line!()
-                        )))
+                        ))
                     }
                 };
             }
@@ -819,11 +2758,12 @@ where
                     self.head_ = new_head;
                 }
                 _ => {
-                    return Err(MapError::InternalError(format!(
+                    return Err(map_error!(
+                        InternalError,
                         "Should not happen error™ at {}:{}",
                         file!(),
                         line!()
-                    )))
+                    ))
                 }
             },
             (Some((new_tail, new_tail_next)), None) => match self.nodes_.get_mut(new_tail) {
@@ -832,11 +2772,12 @@ where
                     self.tail_ = new_tail;
                 }
                 _ => {
-                    return Err(MapError::InternalError(format!(
+                    return Err(map_error!(
+                        InternalError,
                         "Should not happen error™ at {}:{}",
                         file!(),
                         line!()
-                    )))
+                    ))
                 }
             },
             (None, None) => {
@@ -848,7 +2789,9 @@ where
             Some(old_head) => {
                 // Replace the node with None
                 if let Some(old_head) = old_head.take() {
-                    self.id_pool_.push(operation.erase_);
+                    self.release_slot_(operation.erase_, old_head.prev_, old_head.next_);
+                    self.jump_table_removed_(operation.erase_, old_head.next_);
+                    self.bookmarks_removed_(operation.erase_, old_head.prev_, old_head.next_);
                     return Ok((
                         old_head.prev_,
                         (old_head.key_, old_head.value_),
@@ -856,36 +2799,38 @@ where
                     ));
                 }
 
-                return Err(MapError::InternalError(format!(
+                Err(map_error!(
+                    InternalError,
                     "Should not happen error™ at {}:{}",
                     file!(),
                     line!()
-                )));
-            }
-            _ => {
-                return Err(MapError::InternalError(format!(
-                    "Should not happen error™, element to erase not found {} at {}:{}",
-                    operation.erase_,
-                    file!(),
-                    line!()
-                )))
+                ))
             }
+            _ => Err(map_error!(
+                InternalError,
+                "Should not happen error™, element to erase not found {} at {}:{}",
+                operation.erase_,
+                file!(),
+                line!()
+            )),
         }
     }
 }
 
 #[derive(Clone, Debug)]
 /// A double ended iterator
-pub struct ListIterator<'a, K: 'a, V: 'a>
+pub struct ListIterator<'a, K: 'a, V: 'a, M: 'a = ()>
 where
     K: Debug,
     V: Debug,
 {
-    list_: &'a LinkedList<K, V>,
+    list_: &'a LinkedList<K, V, M>,
     my_next_: usize,
+    // the position in the list of my_next_, len() when the iterator is done
+    position_: usize,
 }
 
-impl<'a, K: 'a, V: 'a> std::iter::Iterator for ListIterator<'a, K, V>
+impl<'a, K: 'a, V: 'a, M: 'a> std::iter::Iterator for ListIterator<'a, K, V, M>
 where
     K: Debug,
     V: Debug,
@@ -902,18 +2847,96 @@ where
         if let Some(node) = self.list_.nodes_.get(self.my_next_)? {
             if self.my_next_ == self.list_.tail_ {
                 self.my_next_ = OUT_OF_BOUNDS;
+                self.position_ = self.list_len_();
             } else {
-                self.my_next_ = node.next_
+                self.my_next_ = node.next_;
+                self.position_ += 1;
             }
             Some((&node.key_, &node.value_))
         } else {
             self.my_next_ = OUT_OF_BOUNDS;
+            self.position_ = self.list_len_();
             None
         }
     }
 }
 
-impl<'a, K: 'a, V: 'a> DoubleEndedIterator for ListIterator<'a, K, V>
+impl<'a, K: 'a, V: 'a, M: 'a> ListIterator<'a, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    #[inline]
+    /// Returns the item next() will return, without consuming it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.ordered_insert(1, 1);
+    /// let _ = ll.ordered_insert(2, 2);
+    /// let mut iter = ll.iter();
+    /// assert_eq!(iter.peek(), Some((&1, &1)));
+    /// let index = iter.peek_index().unwrap();
+    /// assert_eq!(iter.next(), Some((&1, &1)));
+    /// assert_eq!(iter.peek(), Some((&2, &2)));
+    /// // restart at the first item
+    /// assert_eq!(ll.iter_from(index).count(), 2);
+    /// ```
+    pub fn peek(&self) -> Option<(&'a K, &'a V)> {
+        match self.list_.nodes_.get(self.my_next_) {
+            Some(Some(node)) => Some((&node.key_, &node.value_)),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    /// Returns the index of the item next() will return, or None if the iterator is done.
+    /// The index can be used as a position hint, or with [`LinkedList::iter_from()`] to
+    /// restart the iteration.
+    pub fn peek_index(&self) -> Option<usize> {
+        match self.list_.nodes_.get(self.my_next_) {
+            Some(Some(_)) => Some(self.my_next_),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the position in the list of the item next() will return, i.e. the number of
+    /// items before it. Returns len() when the iterator is done.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..4 {
+    ///     let _ = ll.ordered_insert(i, i);
+    /// }
+    /// let mut iter = ll.iter();
+    /// let _ = iter.next();
+    /// assert_eq!((iter.position(), iter.remaining_len()), (1, 3));
+    /// ```
+    pub fn position(&self) -> usize {
+        self.position_
+    }
+
+    #[inline(always)]
+    /// Returns the number of items left to iterate over with next(), this is O(1)
+    pub fn remaining_len(&self) -> usize {
+        self.list_len_().saturating_sub(self.position_)
+    }
+
+    #[inline(always)]
+    /// Same as LinkedList::len(), without the Ord bound
+    fn list_len_(&self) -> usize {
+        let list = self.list_;
+        list.nodes_.len() - list.id_pool_.len() - list.tombstones_.len()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, M: 'a> DoubleEndedIterator for ListIterator<'a, K, V, M>
 where
     K: Debug,
     V: Debug,
@@ -927,9 +2950,15 @@ where
             } else {
                 self.my_next_ = node.prev_
             }
+            if self.my_next_ == OUT_OF_BOUNDS {
+                self.position_ = self.list_len_();
+            } else {
+                self.position_ = self.position_.saturating_sub(1);
+            }
             Some((&node.key_, &node.value_))
         } else {
             self.my_next_ = OUT_OF_BOUNDS;
+            self.position_ = self.list_len_();
             None
         }
     }
@@ -938,65 +2967,117 @@ where
 /// An effort to emulate a C++ std::map iterator in Rust.
 /// It will have functionality like:
 /// prev(), next(), get(), erase(), lower_bound(), replace_key()
-pub struct PIterator<K, V>
+///
+/// The list is reached through a [`SharedList`] handle `P`, by default
+/// `Rc<RefCell<LinkedList>>`.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{LinkedList, PIterator};
+/// # use std::{cell::RefCell, sync::{Arc, Mutex}};
+/// let ll = RefCell::new(LinkedList::<i8, i8>::default());
+/// let _ = ll.borrow_mut().ordered_insert(1, 1);
+/// let p = PIterator::new(&ll).unwrap();
+/// assert_eq!(p.get_k().unwrap(), 1);
+///
+/// let ll = Arc::new(Mutex::new(LinkedList::<i8, i8>::default()));
+/// let _ = ll.lock().unwrap().ordered_insert(2, 2);
+/// let p = PIterator::new(Arc::clone(&ll)).unwrap();
+/// let handle = std::thread::spawn(move || p.get_k().unwrap());
+/// assert_eq!(handle.join().unwrap(), 2);
+/// ```
+pub struct PIterator<K, V, M = (), P = Rc<RefCell<LinkedList<K, V, M>>>>
 where
     K: Debug,
     V: Debug,
 {
     current: usize,
-    list: Rc<RefCell<LinkedList<K, V>>>,
+    list: P,
+    #[allow(clippy::type_complexity)]
+    phantom_: PhantomData<fn() -> (K, V, M)>,
 }
 
 #[allow(dead_code)]
-impl<K, V> PIterator<K, V>
+impl<K, V, M, P> PIterator<K, V, M, P>
 where
     K: Clone + Debug + Unpin + Ord + PartialOrd,
     V: Clone + Debug + Unpin,
+    M: Default + Unpin,
+    P: SharedList<K, V, M>,
 {
     /// Initiates the pointer with a list, set current to the head of the list.
-    pub fn new(list: Rc<RefCell<LinkedList<K, V>>>) -> Result<Self, MapError> {
-        let head = list.try_borrow()?.head_;
-        Ok(Self {
-            current: head,
-            list,
-        })
+    pub fn new(list: P) -> Result<Self, MapError> {
+        let head = list.try_read()?.head_;
+        Ok(Self::new_2(list, head))
     }
 
     /// Initiates the pointer with a list, set index.
-    pub fn new_2(list: Rc<RefCell<LinkedList<K, V>>>, current: usize) -> Self {
-        Self { current, list }
+    pub fn new_2(list: P, current: usize) -> Self {
+        Self {
+            current,
+            list,
+            phantom_: PhantomData,
+        }
     }
 
     #[inline(always)]
     /// Returns a clone of the key at current position
     pub fn get_k(&self) -> Result<K, MapError> {
-        Ok(self.list.try_borrow()?.get(self.current)?.0.clone())
+        Ok(self.list.try_read()?.get(self.current)?.0.clone())
     }
 
     #[inline(always)]
     /// Returns a clone of the value at current position
     pub fn get_v(&self) -> Result<V, MapError> {
-        Ok(self.list.try_borrow()?.get(self.current)?.1.clone())
+        Ok(self.list.try_read()?.get(self.current)?.1.clone())
+    }
+
+    #[inline(always)]
+    /// Returns a clone of the metadata at current position
+    pub fn get_meta(&self) -> Result<M, MapError>
+    where
+        M: Clone,
+    {
+        Ok(self
+            .list
+            .try_read()?
+            .current_node_(self.current)?
+            .meta_
+            .clone())
+    }
+
+    #[inline(always)]
+    /// Sets the metadata at current position, returns the previous metadata.
+    pub fn set_meta(&mut self, meta: M) -> Result<M, MapError> {
+        self.list.try_write()?.set_meta(self.current, meta)
     }
 
     #[allow(clippy::should_implement_trait)]
     #[inline(always)]
     /// Move to the next element.
+    /// If the current element was removed with deferred slot reuse, move to the element that
+    /// followed it.
     /// Note that this is NOT a Rust iterator next() method.
     /// Always check validity of the iterator with is_ok() after next()
     // todo: change the return value to Result<bool, MapError>
     pub fn next(&mut self) -> Result<(), MapError> {
-        let list_borrow = self.list.try_borrow()?;
+        let list_borrow = self.list.try_read()?;
         match list_borrow.nodes_.get(self.current) {
             Some(Some(node)) => self.current = node.next_,
-            // Some(None) nodes should be inaccessible
+            // a removed node, still reachable with deferred slot reuse
+            Some(None) if list_borrow.is_tombstone(self.current) => {
+                self.current = list_borrow.step_from_tombstone_(self.current, true)
+            }
+            // other Some(None) nodes should be inaccessible
             Some(None) => {
-                return Err(MapError::InternalError(format!(
+                return Err(map_error!(
+                    InternalError,
                     "next() failed at index:{}. {}:{}",
                     self.current,
                     file!(),
                     line!()
-                )));
+                ));
             }
             None => self.current = OUT_OF_BOUNDS,
         }
@@ -1005,20 +3086,27 @@ where
 
     #[inline(always)]
     /// Move to the previous element
+    /// If the current element was removed with deferred slot reuse, move to the element that
+    /// preceded it.
     /// Always check validity of the iterator with is_ok() after prev()
     // todo: change the return value to Result<bool, MapError>
     pub fn prev(&mut self) -> Result<(), MapError> {
-        let list_borrow = self.list.try_borrow()?;
+        let list_borrow = self.list.try_read()?;
         match list_borrow.nodes_.get(self.current) {
             Some(Some(node)) => self.current = node.prev_,
-            // Some(None) nodes should be inaccessible
+            // a removed node, still reachable with deferred slot reuse
+            Some(None) if list_borrow.is_tombstone(self.current) => {
+                self.current = list_borrow.step_from_tombstone_(self.current, false)
+            }
+            // other Some(None) nodes should be inaccessible
             Some(None) => {
-                return Err(MapError::InternalError(format!(
+                return Err(map_error!(
+                    InternalError,
                     "prev() failed at index:{}. {}:{}",
                     self.current,
                     file!(),
                     line!()
-                )));
+                ));
             }
             None => self.current = OUT_OF_BOUNDS,
         }
@@ -1028,14 +3116,14 @@ where
     #[inline(always)]
     /// Move to the first element
     pub fn move_to_head(&mut self) -> Result<(), MapError> {
-        self.current = self.list.try_borrow()?.head_;
+        self.current = self.list.try_read()?.head_;
         Ok(())
     }
 
     #[inline(always)]
     /// Move to the last element
     pub fn move_to_tail(&mut self) -> Result<(), MapError> {
-        self.current = self.list.try_borrow()?.tail_;
+        self.current = self.list.try_read()?.tail_;
         Ok(())
     }
 
@@ -1044,7 +3132,7 @@ where
     pub fn is_ok(&self) -> Result<bool, MapError> {
         Ok(self.current != OUT_OF_BOUNDS
             && matches!(
-                self.list.try_borrow()?.nodes_.get(self.current),
+                self.list.try_read()?.nodes_.get(self.current),
                 Some(Some(_))
             ))
     }
@@ -1052,20 +3140,21 @@ where
     #[inline(always)]
     /// Return true if pointer is at head position or if the list is empty
     pub fn is_at_head(&self) -> Result<bool, MapError> {
-        Ok(self.current == self.list.try_borrow()?.head_)
+        Ok(self.current == self.list.try_read()?.head_)
     }
 
     #[inline(always)]
     /// Return true if pointer is at tail position or if the list is empty
     pub fn is_at_tail(&self) -> Result<bool, MapError> {
-        Ok(self.current == self.list.try_borrow()?.tail_)
+        Ok(self.current == self.list.try_read()?.tail_)
     }
 
     #[inline(always)]
     /// Replace current key. This will destroy the internal order of element if you
     /// replace an element with something out of order.
     pub fn replace_key(&mut self, key: K) -> Result<(), MapError> {
-        let mut list = std::pin::Pin::new(self.list.try_borrow_mut()?);
+        let mut list = std::pin::Pin::new(self.list.try_write()?);
+        list.check_not_frozen_()?;
         if let Some(Some(ref mut node)) = list.nodes_.get_mut(self.current) {
             node.key_ = key;
         }
@@ -1078,12 +3167,71 @@ where
         self.current
     }
 
+    /// Insert a new element directly before the current element, without searching for the
+    /// position. The caller is responsible for the order: with debug assertions enabled it is
+    /// checked that the key goes between the previous and the current key.
+    /// If the list is empty the element is simply inserted. The pointer is not moved.
+    /// Returns the index of the new element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, PIterator};
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// let ll = Rc::new(RefCell::new(LinkedList::<i8, i8>::default()));
+    /// let mut p = PIterator::new(Rc::clone(&ll)).unwrap();
+    /// let two = p.insert_before_current(2, 2).unwrap();
+    /// p.move_to_head().unwrap();
+    /// let _ = p.insert_before_current(1, 1).unwrap();
+    /// let _ = p.insert_after_current(3, 3).unwrap();
+    /// assert_eq!(p.current(), two);
+    /// assert_eq!(p.iter_from_here().collect::<Vec<_>>(), vec![(2, 2), (3, 3)]);
+    /// ```
+    pub fn insert_before_current(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        let mut list = self.list.try_write()?;
+        let key = list.normalize_key(key);
+        if list.is_empty() {
+            return list.push_back_(key, value);
+        }
+        let prev = list.current_node_(self.current)?.prev_;
+        debug_assert!(
+            list.get_k_opt(prev).map_or(true, |p| p < &key) && &key < list.get_k(self.current)?,
+            "insert_before_current() key {:?} is out of order",
+            key
+        );
+        list.insert_before_(self.current, key, value)
+    }
+
+    /// Insert a new element directly after the current element, without searching for the
+    /// position. The caller is responsible for the order: with debug assertions enabled it is
+    /// checked that the key goes between the current and the next key.
+    /// If the list is empty the element is simply inserted. The pointer is not moved.
+    /// Returns the index of the new element.
+    pub fn insert_after_current(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        let mut list = self.list.try_write()?;
+        let key = list.normalize_key(key);
+        if list.is_empty() {
+            return list.push_back_(key, value);
+        }
+        let next = list.current_node_(self.current)?.next_;
+        debug_assert!(
+            list.get_k(self.current)? < &key && list.get_k_opt(next).map_or(true, |n| &key < n),
+            "insert_after_current() key {:?} is out of order",
+            key
+        );
+        if next == OUT_OF_BOUNDS {
+            list.push_back_(key, value)
+        } else {
+            list.insert_before_(next, key, value)
+        }
+    }
+
     #[inline(always)]
     /// Remove the current element and return it. Move current to the old prev value if exist.
     /// Else pick old next index.
     /// Note: make sure that there are no other Pointer objects at this position.
     pub fn remove_current(&mut self) -> Result<(K, V), MapError> {
-        let rv = self.list.try_borrow_mut()?.remove__(self.current)?;
+        let rv = self.list.try_write()?.remove__(self.current)?;
         if rv.0 != OUT_OF_BOUNDS {
             self.current = rv.0;
         } else {
@@ -1092,47 +3240,220 @@ where
         Ok(rv.1)
     }
 
+    /// Removes every item from `first` up to, but not including, `last`, like C++
+    /// `std::map::erase(first, last)`. A `last` that has moved out of the list removes everything
+    /// from `first` to the tail. Returns the number of removed items.
+    ///
+    /// Both pointers must belong to the same list and `first` must not go after `last`, otherwise
+    /// a HintMismatch or an OrderViolation error is returned and nothing is removed.
+    /// Note: `first`, and any other Pointer object at a removed item, is invalid afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, PIterator};
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// let ll = Rc::new(RefCell::new(LinkedList::<i8, i8>::default()));
+    /// for i in 0..6 {
+    ///     let _ = ll.borrow_mut().ordered_insert(i, i);
+    /// }
+    /// let first = PIterator::lower_bound(Rc::clone(&ll), &1).unwrap();
+    /// let last = PIterator::lower_bound(Rc::clone(&ll), &4).unwrap();
+    /// assert!(PIterator::erase_range(&last, &first).is_err());
+    /// assert_eq!(PIterator::erase_range(&first, &last).unwrap(), 3);
+    /// assert_eq!(ll.borrow().iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![0, 4, 5]);
+    /// ```
+    pub fn erase_range(first: &Self, last: &Self) -> Result<usize, MapError> {
+        if !first.list.same_list(&last.list) {
+            return Err(map_error!(
+                HintMismatch,
+                "erase_range() pointers belong to different lists"
+            ));
+        }
+        let mut list = first.list.try_write()?;
+        list.check_not_frozen_()?;
+        let last_key = list.get_k_opt(last.current);
+        let order = match (list.get_k_opt(first.current), last_key) {
+            (None, None) => return Ok(0),
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(first_key), Some(last_key)) => first_key.cmp(last_key),
+        };
+        if order == Ordering::Greater {
+            return Err(map_error!(
+                OrderViolation,
+                "erase_range() first {} goes after last {}",
+                first.current,
+                last.current
+            ));
+        }
+        let last = if last_key.is_some() {
+            last.current
+        } else {
+            OUT_OF_BOUNDS
+        };
+        let mut removed = 0_usize;
+        let mut current = first.current;
+        while current != last && current != OUT_OF_BOUNDS {
+            current = list.remove__(current)?.2;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
     #[inline(always)]
     /// Returns a new Pointer positioned at the lower bound item.
     /// Lower bound item is the first element in the container whose key is not considered to go
     /// before position (i.e., either it is equivalent or goes after).
     /// Returns a Pointer where is_ok() returns false if no data is found
-    pub fn lower_bound(list: Rc<RefCell<LinkedList<K, V>>>, key: K) -> Result<Self, MapError> {
-        let position = list.try_borrow()?.lower_bound(key)?;
-        if let Some(position) = position {
-            Ok(Self {
-                list,
-                current: position,
-            })
-        } else {
-            // Return a Pointer that is out of bounds
-            Ok(Self {
-                list,
-                current: OUT_OF_BOUNDS,
-            })
-        }
+    pub fn lower_bound<Q>(list: P, key: &Q) -> Result<Self, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let position = list.try_read()?.lower_bound(key)?;
+        // Return a Pointer that is out of bounds if no data is found
+        Ok(Self::new_2(list, position.unwrap_or(OUT_OF_BOUNDS)))
+    }
+
+    #[inline(always)]
+    /// Returns a new Pointer positioned at the upper bound item, the first element in the
+    /// container whose key is considered to go after `key`.
+    /// Returns a Pointer where is_ok() returns false if no data is found
+    pub fn upper_bound<Q>(list: P, key: &Q) -> Result<Self, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let position = list.try_read()?.upper_bound(key)?;
+        Ok(Self::new_2(list, position.unwrap_or(OUT_OF_BOUNDS)))
+    }
+
+    /// Moves this pointer to the lower bound item, searching from the current position.
+    /// Lower bound item is the first element in the container whose key is not considered to go
+    /// before position (i.e., either it is equivalent or goes after).
+    /// is_ok() returns false if no data is found.
+    /// If the pointer is not at a valid position the search starts from the tail.
+    pub fn seek_lower_bound<Q>(&mut self, key: &Q) -> Result<(), MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let position = self.list.try_read()?.lower_bound_pos(key, self.current)?;
+        self.current = position.unwrap_or(OUT_OF_BOUNDS);
+        Ok(())
+    }
+
+    #[inline(always)]
+    /// Converts the pointer into a Rust iterator returning clones of (key, value), starting at
+    /// the current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, PIterator};
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// let ll = Rc::new(RefCell::new(LinkedList::<i8, i8>::default()));
+    /// for i in 0..5 {
+    ///     let _ = ll.borrow_mut().ordered_insert(i, i * 10);
+    /// }
+    /// let p = PIterator::lower_bound(Rc::clone(&ll), &3).unwrap();
+    /// assert_eq!(
+    ///     p.into_iter_cloned().collect::<Vec<_>>(),
+    ///     vec![(3, 30), (4, 40)]
+    /// );
+    /// ```
+    pub fn into_iter_cloned(self) -> PIteratorCloned<K, V, M, P> {
+        PIteratorCloned { pointer_: self }
+    }
+
+    #[inline(always)]
+    /// Returns a Rust iterator returning clones of (key, value), starting at the current position.
+    /// This pointer is not moved.
+    pub fn iter_from_here(&self) -> PIteratorCloned<K, V, M, P> {
+        self.clone().into_iter_cloned()
+    }
+}
+
+/// A Rust iterator returning clones of (key, value), created by
+/// [`PIterator::into_iter_cloned()`] or [`PIterator::iter_from_here()`].
+/// The iteration stops at the end of the list, or if the list could not be borrowed.
+#[derive(Clone, Debug)]
+pub struct PIteratorCloned<K, V, M = (), P = Rc<RefCell<LinkedList<K, V, M>>>>
+where
+    K: Debug + Unpin + Ord + PartialOrd,
+    V: Debug + Unpin,
+    P: SharedList<K, V, M>,
+{
+    pointer_: PIterator<K, V, M, P>,
+}
+
+impl<K, V, M, P> std::iter::Iterator for PIteratorCloned<K, V, M, P>
+where
+    K: Clone + Debug + Unpin + Ord + PartialOrd,
+    V: Clone + Debug + Unpin,
+    M: Default + Unpin,
+    P: SharedList<K, V, M>,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let item = {
+            let list = self.pointer_.list.try_read().ok()?;
+            let (k, v) = list.get(self.pointer_.current).ok()?;
+            (k.clone(), v.clone())
+        };
+        self.pointer_.next().ok()?;
+        Some(item)
     }
 }
 
-impl<K, V> Debug for PIterator<K, V>
+impl<K, V, M, P> Debug for PIterator<K, V, M, P>
 where
     K: Debug + Unpin + Ord + PartialOrd,
     V: Debug + Unpin,
+    P: SharedList<K, V, M>,
 {
+    /// Prints the index, the head/tail status and the current key (if the list can be borrowed).
+    /// E.g. `PIterator(3, head, key:1)`, `PIterator(OUT_OF_BOUNDS)`
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "PIterator({})", self.current)
+        if self.current == OUT_OF_BOUNDS {
+            return write!(f, "PIterator(OUT_OF_BOUNDS)");
+        }
+        let list = if let Ok(list) = self.list.try_read() {
+            list
+        } else {
+            return write!(
+                f,
+                "PIterator({}, <list already mutably borrowed>)",
+                self.current
+            );
+        };
+        write!(f, "PIterator({}", self.current)?;
+        if self.current == list.head_ {
+            write!(f, ", head")?;
+        }
+        if self.current == list.tail_ {
+            write!(f, ", tail")?;
+        }
+        match list.nodes_.get(self.current) {
+            Some(Some(node)) => write!(f, ", key:{:?})", node.key_),
+            _ => write!(f, ", <invalid index>)"),
+        }
     }
 }
 
-impl<K, V> Clone for PIterator<K, V>
+impl<K, V, M, P> Clone for PIterator<K, V, M, P>
 where
     K: Debug + Unpin + Ord + PartialOrd,
     V: Debug + Unpin,
+    P: SharedList<K, V, M>,
 {
     fn clone(&self) -> Self {
         Self {
             current: self.current,
-            list: Rc::clone(&self.list),
+            list: self.list.clone(),
+            phantom_: PhantomData,
         }
     }
 }
@@ -0,0 +1,194 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Moving the items of one list into another, C++17 `std::map::merge`, and splitting a list.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::borrow::Borrow;
+use std::fmt::Debug;
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Moves every item of `other` whose key is not in this list into this list, like C++17
+    /// `std::map::merge`. The items with a key that already exists are left in `other`.
+    /// Keys, values and metadata are moved, not cloned. Returns the number of moved items.
+    ///
+    /// Both lists are traversed once, in parallel. The keys are moved as they are, the key
+    /// normalizer of this list is not applied.
+    /// If this list runs out of capacity a CapacityExceeded error is returned, the items moved
+    /// until then stay moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut a = LinkedList::<i8, char>::default();
+    /// let mut b = LinkedList::<i8, char>::default();
+    /// for (k, v) in [(1, 'a'), (3, 'a')] {
+    ///     let _ = a.ordered_insert(k, v);
+    /// }
+    /// for (k, v) in [(0, 'b'), (1, 'b'), (2, 'b'), (4, 'b')] {
+    ///     let _ = b.ordered_insert(k, v);
+    /// }
+    /// assert_eq!(a.merge_from(&mut b).unwrap(), 3);
+    /// assert_eq!(
+    ///     a.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+    ///     vec![(0, 'b'), (1, 'a'), (2, 'b'), (3, 'a'), (4, 'b')]
+    /// );
+    /// assert_eq!(b.iter().collect::<Vec<_>>(), vec![(&1, &'b')]);
+    /// ```
+    pub fn merge_from(&mut self, other: &mut Self) -> Result<usize, MapError> {
+        self.check_not_frozen_()?;
+        other.check_not_frozen_()?;
+        let mut moved = 0_usize;
+        // the first item of self that doesn't go before the current item of other
+        let mut position = self.head_;
+        let mut other_index = other.head_;
+        while let Some(Some(node)) = other.nodes_.get(other_index) {
+            while let Some(Some(sample)) = self.nodes_.get(position) {
+                if sample.key_ >= node.key_ {
+                    break;
+                }
+                position = sample.next_;
+            }
+            if self.get_k_opt(position) == Some(&node.key_) {
+                other_index = node.next_;
+                continue;
+            }
+            // check the capacity before the item is removed from other
+            if let Some(max_capacity) = self.max_capacity_ {
+                if self.id_pool_.is_empty() && self.nodes_.len() >= max_capacity {
+                    return Err(map_error!(
+                        CapacityExceeded,
+                        "the list is limited to {} items",
+                        max_capacity
+                    ));
+                }
+            }
+            let meta = other.take_meta(other_index)?;
+            let (_, (key, value), next) = other.remove__(other_index)?;
+            let index = if position == OUT_OF_BOUNDS {
+                self.push_back_(key, value)?
+            } else {
+                self.insert_before_(position, key, value)?
+            };
+            self.node_mut_(index)?.meta_ = meta;
+            moved += 1;
+            other_index = next;
+        }
+        self.maintain_jump_table_();
+        other.maintain_jump_table_();
+        Ok(moved)
+    }
+
+    /// Moves every item of `other` into this list, leaving `other` empty. Inserting a key that
+    /// already exists is a NOP as usual, so those items of `other` are dropped.
+    /// Returns the number of moved items. See [`LinkedList::merge_from()`], this is the same
+    /// followed by `other.try_clear()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut a = LinkedList::<i8, char>::default();
+    /// let mut b = LinkedList::<i8, char>::default();
+    /// let _ = a.ordered_insert(1, 'a');
+    /// let _ = b.ordered_insert(1, 'b');
+    /// let _ = b.ordered_insert(2, 'b');
+    /// assert_eq!(a.append(&mut b).unwrap(), 1);
+    /// assert_eq!(a.iter().collect::<Vec<_>>(), vec![(&1, &'a'), (&2, &'b')]);
+    /// assert!(b.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) -> Result<usize, MapError> {
+        let moved = self.merge_from(other)?;
+        other.try_clear()?;
+        Ok(moved)
+    }
+
+    /// Splits the list at the first item with a key that does not go before `key`, like
+    /// `BTreeMap::split_off`. That item and every item after it are moved to the returned list,
+    /// the smaller keys stay in this list.
+    ///
+    /// The moved items get compact slots in the new list, their slots in this list are
+    /// recycled. Keys, values and metadata are moved, not cloned. The new list has the default
+    /// settings. Returns a Frozen error, and moves nothing, if this list is frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, char>::default();
+    /// for (k, v) in [(1, 'a'), (3, 'b'), (5, 'c'), (7, 'd')] {
+    ///     let _ = ll.ordered_insert(k, v);
+    /// }
+    /// let tail = ll.split_off(&4).unwrap();
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &'a'), (&3, &'b')]);
+    /// assert_eq!(tail.iter().collect::<Vec<_>>(), vec![(&5, &'c'), (&7, &'d')]);
+    /// ```
+    pub fn split_off<Q>(&mut self, key: &Q) -> Result<Self, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        self.check_not_frozen_()?;
+        let position = self.lower_bound(key)?.unwrap_or(OUT_OF_BOUNDS);
+        self.split_off_at_(position)
+    }
+
+    /// Moves the item at index and every item after it to a new list, see
+    /// [`LinkedList::split_off()`]. Useful when the index of the split item is already known,
+    /// the list is not searched.
+    /// Returns an error, and moves nothing, if the index is not an active item or if the list is
+    /// frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, char>::default();
+    /// let _ = ll.ordered_insert(1, 'a');
+    /// let index = ll.ordered_insert(3, 'b').unwrap();
+    /// let _ = ll.ordered_insert(5, 'c');
+    /// let tail = ll.split_off_before(index).unwrap();
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &'a')]);
+    /// assert_eq!(tail.iter().collect::<Vec<_>>(), vec![(&3, &'b'), (&5, &'c')]);
+    /// assert!(ll.split_off_before(index).is_err());
+    /// ```
+    pub fn split_off_before(&mut self, index: usize) -> Result<Self, MapError> {
+        self.check_not_frozen_()?;
+        if self.get_opt(index).is_none() {
+            return Err(self.invalid_index_(index));
+        }
+        self.split_off_at_(index)
+    }
+
+    /// Moves the item at `position` and every item after it to a new list, OUT_OF_BOUNDS moves
+    /// nothing
+    fn split_off_at_(&mut self, position: usize) -> Result<Self, MapError> {
+        let mut count = 0_usize;
+        let mut curr_index = position;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            count += 1;
+            curr_index = node.next_;
+        }
+        let mut other = Self::with_capacity(count);
+        let mut curr_index = position;
+        while curr_index != OUT_OF_BOUNDS {
+            let meta = self.take_meta(curr_index)?;
+            let (_, (key, value), next) = self.remove__(curr_index)?;
+            let index = other.push_back_(key, value)?;
+            other.node_mut_(index)?.meta_ = meta;
+            curr_index = next;
+        }
+        self.maintain_jump_table_();
+        Ok(other)
+    }
+}
@@ -0,0 +1,94 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Node handles, items detached from a list that can be inserted again without copying.
+
+use crate::{LinkedList, MapError};
+use std::borrow::Borrow;
+use std::fmt::Debug;
+
+/// An item extracted from a list by [`LinkedList::extract()`], the C++17 `node_type`.
+/// The key can be changed while the item is detached, and the handle can be inserted into any
+/// list with [`LinkedList::insert_node()`]. Neither the key nor the value is cloned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeHandle<K, V, M = ()> {
+    /// The key of the item
+    pub key: K,
+    /// The value of the item
+    pub value: V,
+    /// The metadata of the item, see [`LinkedList::set_meta()`]
+    pub meta: M,
+}
+
+impl<K, V, M> NodeHandle<K, V, M> {
+    /// Returns the key and the value, dropping the metadata
+    pub fn into_pair(self) -> (K, V) {
+        (self.key, self.value)
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Removes the item with `key` and returns it as a [`NodeHandle`], including its metadata.
+    /// Returns Ok(None) if the key is not in the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// # #[derive(Debug, PartialEq)]
+    /// # struct NotClone(u8);
+    /// let mut a = LinkedList::<i8, NotClone>::default();
+    /// let mut b = LinkedList::<i8, NotClone>::default();
+    /// let _ = a.ordered_insert(1, NotClone(1));
+    /// let _ = b.ordered_insert(3, NotClone(3));
+    /// let mut node = a.extract(&1).unwrap().unwrap();
+    /// node.key = 2;
+    /// let (index, rejected) = b.insert_node(node, b.head()).unwrap();
+    /// assert!(rejected.is_none() && a.is_empty());
+    /// assert_eq!(b.get(index).unwrap(), (&2, &NotClone(1)));
+    /// ```
+    pub fn extract<Q>(&mut self, key: &Q) -> Result<Option<NodeHandle<K, V, M>>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        self.check_not_frozen_()?;
+        let index = match self.find(key) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let meta = self.take_meta(index)?;
+        let (_, (key, value), _) = self.remove__(index)?;
+        Ok(Some(NodeHandle { key, value, meta }))
+    }
+
+    #[allow(clippy::type_complexity)]
+    /// Inserts a [`NodeHandle`] by key order, with a position hint (see
+    /// [`LinkedList::ordered_insert_pos()`]). The key is normalized like any inserted key.
+    /// Returns the index of the item and, if the key already existed, the handle that was not
+    /// inserted. The list is not modified in that case.
+    pub fn insert_node(
+        &mut self,
+        node: NodeHandle<K, V, M>,
+        position: usize,
+    ) -> Result<(usize, Option<NodeHandle<K, V, M>>), MapError> {
+        let NodeHandle { key, value, meta } = node;
+        let key = self.normalize_key(key);
+        self.check_hint_(&key, position)?;
+        let (index, rejected) = self.insert_pos_(key, value, position)?;
+        if let Some((key, value)) = rejected {
+            return Ok((index, Some(NodeHandle { key, value, meta })));
+        }
+        let _ = self.set_meta(index, meta)?;
+        Ok((index, None))
+    }
+}
@@ -0,0 +1,108 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An optional hook that canonicalizes keys at the container boundary.
+
+use crate::{LinkedList, MapError};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+type NormalizeCallback<K> = Arc<dyn Fn(K) -> K + Send + Sync>;
+
+pub(crate) struct KeyNormalizer<K> {
+    callback_: NormalizeCallback<K>,
+}
+
+impl<K> Clone for KeyNormalizer<K> {
+    fn clone(&self) -> Self {
+        Self {
+            callback_: Arc::clone(&self.callback_),
+        }
+    }
+}
+
+impl<K> Debug for KeyNormalizer<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KeyNormalizer")
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Registers a callback that canonicalizes every key before it is inserted, e.g. trimming
+    /// strings or snapping floats to a grid. The callback is applied by ordered_insert(),
+    /// ordered_insert_pos(), push_back_ordered(), push_front_ordered() and everything built on
+    /// top of them. Owned lookup keys are normalized by the `*_normalized` lookups:
+    /// [`LinkedList::lower_bound_normalized()`], [`LinkedList::find_normalized()`],
+    /// [`LinkedList::contains_key_normalized()`] and [`LinkedList::remove_by_key_normalized()`].
+    /// The lookups taking a borrowed key, like find(), contains_key() and remove_by_key(), can't
+    /// call the callback and use the key as is.
+    ///
+    /// The callback should be idempotent, and the keys already in the list are not touched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<String, i8>::default();
+    /// ll.set_key_normalizer(|k: String| k.trim().to_lowercase());
+    /// let a = ll.ordered_insert(" Apple".to_string(), 1).unwrap();
+    /// assert_eq!(ll.ordered_insert("APPLE ".to_string(), 2).unwrap(), a); // a NOP
+    /// assert_eq!(ll.get(a).unwrap(), (&"apple".to_string(), &1));
+    /// assert_eq!(ll.lower_bound_normalized("ApPle".to_string()).unwrap(), Some(a));
+    /// assert_eq!(ll.find_normalized(" apple".to_string()), Some(a));
+    /// assert!(!ll.contains_key(" apple"));
+    /// ```
+    pub fn set_key_normalizer<F>(&mut self, callback: F)
+    where
+        F: Fn(K) -> K + Send + Sync + 'static,
+    {
+        self.key_normalizer_ = Some(KeyNormalizer {
+            callback_: Arc::new(callback),
+        });
+    }
+
+    /// Removes the callback registered with [`LinkedList::set_key_normalizer()`]
+    pub fn clear_key_normalizer(&mut self) {
+        self.key_normalizer_ = None;
+    }
+
+    #[inline(always)]
+    /// Returns the key as it would be inserted, i.e. passed through the key normalizer if there
+    /// is one.
+    pub fn normalize_key(&self, key: K) -> K {
+        match &self.key_normalizer_ {
+            Some(normalizer) => (normalizer.callback_)(key),
+            None => key,
+        }
+    }
+
+    /// Same as [`LinkedList::lower_bound()`], but the key is normalized first.
+    pub fn lower_bound_normalized(&self, key: K) -> Result<Option<usize>, MapError> {
+        self.lower_bound(&self.normalize_key(key))
+    }
+
+    /// Same as [`LinkedList::find()`], but the key is normalized first.
+    pub fn find_normalized(&self, key: K) -> Option<usize> {
+        self.find(&self.normalize_key(key))
+    }
+
+    /// Same as [`LinkedList::contains_key()`], but the key is normalized first.
+    pub fn contains_key_normalized(&self, key: K) -> bool {
+        self.contains_key(&self.normalize_key(key))
+    }
+
+    /// Same as [`LinkedList::remove_by_key()`], but the key is normalized first.
+    pub fn remove_by_key_normalized(&mut self, key: K) -> Result<Option<(K, V)>, MapError> {
+        let key = self.normalize_key(key);
+        self.remove_by_key(&key)
+    }
+}
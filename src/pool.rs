@@ -0,0 +1,123 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A pool of cleared lists, for reusing their allocations.
+
+use crate::{
+    CompareCounter, EqualPolicy, FreezeCount, LinkedList, ListId, SearchStrategy, SlotReuse,
+};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Hands out empty [`LinkedList`]s and takes them back, keeping the capacity of their internal
+/// vectors so that short lived lists don't have to allocate.
+///
+/// A returned list is cleared and all its settings (scan warning, search strategy, jump
+/// table and max capacity) are reset, so a list taken from the pool is indistinguishable from
+/// a new list.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::ListPool;
+/// let mut pool = ListPool::<i32, i32>::new(4);
+/// for frame in 0..3 {
+///     let mut ll = pool.take();
+///     assert!(ll.is_empty());
+///     for i in 0..100 {
+///         let _ = ll.ordered_insert_pos(i, frame, ll.tail());
+///     }
+///     pool.give(ll);
+/// }
+/// assert_eq!(pool.len(), 1);
+/// assert!(pool.take().capacity_info().nodes >= 100);
+/// ```
+#[derive(Debug)]
+pub struct ListPool<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    lists_: Vec<LinkedList<K, V>>,
+    max_pooled_: usize,
+}
+
+impl<K, V> ListPool<K, V>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+{
+    /// Creates an empty pool that will keep at most `max_pooled` lists.
+    pub fn new(max_pooled: usize) -> Self {
+        Self {
+            lists_: Vec::new(),
+            max_pooled_: max_pooled,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the number of lists currently in the pool
+    pub fn len(&self) -> usize {
+        self.lists_.len()
+    }
+
+    #[inline(always)]
+    /// Returns true if there are no lists in the pool
+    pub fn is_empty(&self) -> bool {
+        self.lists_.is_empty()
+    }
+
+    #[inline(always)]
+    /// Returns the max number of lists kept by the pool
+    pub fn max_pooled(&self) -> usize {
+        self.max_pooled_
+    }
+
+    /// Returns an empty list, reusing a pooled list if there is one.
+    pub fn take(&mut self) -> LinkedList<K, V> {
+        self.lists_.pop().unwrap_or_default()
+    }
+
+    /// Returns an empty shared list, for use with [`PIterator`](crate::PIterator).
+    pub fn take_shared(&mut self) -> Rc<RefCell<LinkedList<K, V>>> {
+        Rc::new(RefCell::new(self.take()))
+    }
+
+    /// Clears the list and keeps it for reuse. The list is dropped if the pool is full.
+    pub fn give(&mut self, mut list: LinkedList<K, V>) {
+        if self.lists_.len() < self.max_pooled_ {
+            list.clear();
+            list.scan_warning_ = None;
+            list.compare_stats_ = CompareCounter::new();
+            list.list_id_ = ListId::new();
+            list.key_normalizer_ = None;
+            list.search_strategy_ = SearchStrategy::Linear;
+            list.equal_policy_ = EqualPolicy::StopAtFirst;
+            list.jump_table_ = None;
+            list.max_capacity_ = None;
+            list.slot_reuse_ = SlotReuse::Lifo;
+            list.defer_slot_reuse_ = false;
+            list.strict_hints_ = false;
+            list.freeze_count_ = FreezeCount::new();
+            list.bookmarks_.clear();
+            self.lists_.push(list);
+        }
+    }
+
+    /// Takes back a shared list. This only succeeds if there are no other references to the
+    /// list, e.g. from a [`PIterator`](crate::PIterator), otherwise the list is handed back as
+    /// the error value, untouched.
+    pub fn give_shared(
+        &mut self,
+        list: Rc<RefCell<LinkedList<K, V>>>,
+    ) -> Result<(), Rc<RefCell<LinkedList<K, V>>>> {
+        let list = Rc::try_unwrap(list)?;
+        self.give(list.into_inner());
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Consistency checking, and a last-resort repair of a damaged list.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+/// The report of [`LinkedList::recover()`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recovery<K, V> {
+    /// The number of items in the repaired list
+    pub kept: usize,
+    /// The number of kept items that could not be reached from the head before the repair
+    pub relinked: usize,
+    /// The items that were dropped because another item had the same key, in slot order
+    pub dropped: Vec<(K, V)>,
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Checks the internal structure of the list: the links in both directions, the head and
+    /// the tail, the key order and the free slots. Returns an InternalError describing the
+    /// first problem found.
+    /// This visits every slot, it is meant for debugging and for deciding if
+    /// [`LinkedList::recover()`] is needed.
+    pub fn check_consistency(&self) -> Result<(), MapError> {
+        let mut prev = OUT_OF_BOUNDS;
+        let mut curr_index = self.head_;
+        let mut reachable = 0_usize;
+        while curr_index != OUT_OF_BOUNDS {
+            let node = match self.nodes_.get(curr_index) {
+                Some(Some(node)) => node,
+                _ => {
+                    return Err(map_error!(
+                        InternalError,
+                        "the link to {} from {} is not an active item",
+                        curr_index,
+                        prev
+                    ))
+                }
+            };
+            if node.prev_ != prev {
+                return Err(map_error!(
+                    InternalError,
+                    "the item at {} links back to {}, not {}",
+                    curr_index,
+                    node.prev_,
+                    prev
+                ));
+            }
+            if let Some(prev_key) = self.get_k_opt(prev) {
+                if prev_key >= &node.key_ {
+                    return Err(map_error!(
+                        InternalError,
+                        "the key at {} is not greater than the key at {}",
+                        curr_index,
+                        prev
+                    ));
+                }
+            }
+            reachable += 1;
+            if reachable > self.nodes_.len() {
+                return Err(map_error!(InternalError, "the links form a cycle"));
+            }
+            prev = curr_index;
+            curr_index = node.next_;
+        }
+        if prev != self.tail_ {
+            return Err(map_error!(
+                InternalError,
+                "the list ends at {}, but the tail is {}",
+                prev,
+                self.tail_
+            ));
+        }
+        let live = self.nodes_.iter().filter(|n| n.is_some()).count();
+        if live != reachable {
+            return Err(map_error!(
+                InternalError,
+                "{} active items but only {} are reachable from the head",
+                live,
+                reachable
+            ));
+        }
+        let mut free = BTreeSet::new();
+        for index in self.id_pool_.iter().chain(self.tombstones_.keys()) {
+            if !matches!(self.nodes_.get(*index), Some(None)) || !free.insert(*index) {
+                return Err(map_error!(
+                    InternalError,
+                    "the free slot {} is active or listed twice",
+                    index
+                ));
+            }
+        }
+        if free.len() + live != self.nodes_.len() {
+            return Err(map_error!(
+                InternalError,
+                "{} slots are neither active nor free",
+                self.nodes_.len() - live - free.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the links of a damaged list (see [`LinkedList::check_consistency()`]) from the
+    /// active slots. Every active item is kept at its index and relinked in key order, also the
+    /// items that could no longer be reached. If several items have the same key only the one
+    /// with the lowest index is kept, the others are removed and returned in the report.
+    ///
+    /// The free slots are rebuilt and the tombstones are dropped. Bookmarks at active items are
+    /// kept. The jump table is rebuilt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..5 {
+    ///     let _ = ll.ordered_insert(i, i);
+    /// }
+    /// assert!(ll.check_consistency().is_ok());
+    /// let recovery = ll.recover();
+    /// assert_eq!((recovery.kept, recovery.relinked), (5, 0));
+    /// assert!(recovery.dropped.is_empty());
+    /// ```
+    pub fn recover(&mut self) -> Recovery<K, V> {
+        let mut reachable = BTreeSet::new();
+        let mut curr_index = self.head_;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            if !reachable.insert(curr_index) {
+                // a cycle
+                break;
+            }
+            curr_index = node.next_;
+        }
+
+        let mut order = self
+            .nodes_
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| n.as_ref().map(|_| i))
+            .collect::<Vec<_>>();
+        // a stable sort, equal keys stay in slot order
+        order.sort_by(|a, b| self.get_k_opt(*a).cmp(&self.get_k_opt(*b)));
+
+        let mut dropped = Vec::new();
+        let mut kept = Vec::with_capacity(order.len());
+        for index in order {
+            let duplicate = match kept.last() {
+                Some(last) => self.get_k_opt(*last) == self.get_k_opt(index),
+                None => false,
+            };
+            if duplicate {
+                // unwrap is safe, the slot is active
+                let node = self.nodes_[index].take().unwrap();
+                dropped.push((index, (node.key_, node.value_)));
+            } else {
+                kept.push(index);
+            }
+        }
+        dropped.sort_by_key(|(index, _)| *index);
+
+        for (n, index) in kept.iter().enumerate() {
+            let prev = if n == 0 { OUT_OF_BOUNDS } else { kept[n - 1] };
+            let next = kept.get(n + 1).copied().unwrap_or(OUT_OF_BOUNDS);
+            if let Some(Some(node)) = self.nodes_.get_mut(*index) {
+                node.prev_ = prev;
+                node.next_ = next;
+            }
+        }
+        self.head_ = kept.first().copied().unwrap_or(OUT_OF_BOUNDS);
+        self.tail_ = kept.last().copied().unwrap_or(OUT_OF_BOUNDS);
+
+        self.tombstones_.clear();
+        self.id_pool_.clear();
+        for index in 0..self.nodes_.len() {
+            if self.nodes_[index].is_none() {
+                self.free_slot_(index);
+            }
+        }
+        let remap = (0..self.nodes_.len())
+            .map(|i| {
+                if self.nodes_[i].is_some() {
+                    i
+                } else {
+                    OUT_OF_BOUNDS
+                }
+            })
+            .collect::<Vec<_>>();
+        self.bookmarks_remapped_(&remap);
+        if self.jump_table_.is_some() {
+            self.rebuild_jump_table_();
+        }
+
+        Recovery {
+            kept: kept.len(),
+            relinked: kept.iter().filter(|i| !reachable.contains(*i)).count(),
+            dropped: dropped.into_iter().map(|(_, pair)| pair).collect(),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Index remapping tables returned by the structural operations.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+/// The old index to new index table of an operation that moves items to other slots, e.g.
+/// [`LinkedList::compact()`]. Old indices that no longer refer to an item, e.g. free slots, are
+/// mapped to OUT_OF_BOUNDS.
+///
+/// Use it to update the indices held outside of the list.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{LinkedList, OUT_OF_BOUNDS};
+/// let mut ll = LinkedList::<i8, i8>::default();
+/// let mut held = Vec::new();
+/// for i in (0..4).rev() {
+///     held.push(ll.ordered_insert(i, i).unwrap());
+/// }
+/// let _ = ll.pop_back();
+/// ll.compact().unwrap().apply_to(&mut held);
+/// assert_eq!(held, vec![OUT_OF_BOUNDS, 2, 1, 0]);
+/// assert_eq!(ll.get(held[1]).unwrap(), (&2, &2));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexRemap {
+    // old index -> new index
+    map_: Vec<usize>,
+}
+
+impl IndexRemap {
+    /// Creates a remapping from a table where `map[old_index] == new_index`
+    pub fn new(map: Vec<usize>) -> Self {
+        Self { map_: map }
+    }
+
+    #[inline(always)]
+    /// Returns the new index of `old_index`, OUT_OF_BOUNDS if it no longer refers to an item
+    pub fn get(&self, old_index: usize) -> usize {
+        self.map_.get(old_index).copied().unwrap_or(OUT_OF_BOUNDS)
+    }
+
+    /// Replaces every index of `indices` by its new index
+    pub fn apply_to(&self, indices: &mut [usize]) {
+        for index in indices.iter_mut() {
+            *index = self.get(*index);
+        }
+    }
+
+    /// Replaces every index of `indices` by its new index, and removes the indices that no
+    /// longer refer to an item
+    pub fn apply_to_vec(&self, indices: &mut Vec<usize>) {
+        self.apply_to(indices);
+        indices.retain(|index| *index != OUT_OF_BOUNDS);
+    }
+
+    #[inline(always)]
+    /// Returns true if no index changed, i.e. every old index is mapped to itself or to
+    /// OUT_OF_BOUNDS
+    pub fn is_identity(&self) -> bool {
+        self.map_
+            .iter()
+            .enumerate()
+            .all(|(old, new)| old == *new || *new == OUT_OF_BOUNDS)
+    }
+
+    #[inline(always)]
+    /// Returns the table, `map[old_index] == new_index`
+    pub fn as_slice(&self) -> &[usize] {
+        &self.map_
+    }
+
+    #[inline(always)]
+    /// Returns the table, `map[old_index] == new_index`
+    pub fn into_inner(self) -> Vec<usize> {
+        self.map_
+    }
+}
+
+impl From<Vec<usize>> for IndexRemap {
+    fn from(map: Vec<usize>) -> Self {
+        Self::new(map)
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Same as [`LinkedList::optimize()`], the remapping is returned as an [`IndexRemap`] that
+    /// can update the indices held by the caller.
+    pub fn compact(&mut self) -> Result<IndexRemap, MapError> {
+        self.optimize().map(IndexRemap::new)
+    }
+}
@@ -0,0 +1,124 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reporting of long sequential scans, i.e. stale or bad position hints.
+
+use crate::LinkedList;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// The operation that performed a long scan
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScanOperation {
+    /// ordered_insert() or ordered_insert_pos()
+    Insert,
+    /// lower_bound()
+    LowerBound,
+}
+
+/// The report given to the callback registered with [`LinkedList::set_scan_warning()`]
+#[derive(Debug)]
+pub struct LongScan<'a> {
+    /// The operation that performed the scan
+    pub operation: ScanOperation,
+    /// The key that was searched for
+    pub key: &'a dyn Debug,
+    /// The index the search started from
+    pub hint: usize,
+    /// The number of nodes visited
+    pub hops: usize,
+    /// The id of the list, see [`LinkedList::list_id()`]
+    pub list_id: u64,
+}
+
+type ScanCallback = Arc<dyn Fn(&LongScan<'_>) + Send + Sync>;
+
+pub(crate) struct ScanWarning {
+    threshold_: usize,
+    callback_: ScanCallback,
+}
+
+impl Clone for ScanWarning {
+    fn clone(&self) -> Self {
+        Self {
+            threshold_: self.threshold_,
+            callback_: Arc::clone(&self.callback_),
+        }
+    }
+}
+
+impl Debug for ScanWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ScanWarning(threshold:{})", self.threshold_)
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+{
+    /// Registers a callback that is called whenever an insert or lower_bound search visits more
+    /// than `threshold` nodes. The report contains the key and the position hint used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// # use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    /// let long_scans = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&long_scans);
+    /// let mut ll = LinkedList::<i32, ()>::default();
+    /// ll.set_scan_warning(10, move |_report| {
+    ///     let _ = counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// for i in 0..20 {
+    ///     let _ = ll.ordered_insert_pos(i, (), ll.tail()); // good hint
+    /// }
+    /// assert_eq!(long_scans.load(Ordering::Relaxed), 0);
+    /// let _ = ll.ordered_insert_pos(20, (), ll.head()); // bad hint
+    /// assert_eq!(long_scans.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn set_scan_warning<F>(&mut self, threshold: usize, callback: F)
+    where
+        F: Fn(&LongScan<'_>) + Send + Sync + 'static,
+    {
+        self.scan_warning_ = Some(ScanWarning {
+            threshold_: threshold,
+            callback_: Arc::new(callback),
+        });
+    }
+
+    /// Removes the callback registered with [`LinkedList::set_scan_warning()`]
+    pub fn clear_scan_warning(&mut self) {
+        self.scan_warning_ = None;
+    }
+
+    #[inline(always)]
+    /// Calls the scan warning callback if the scan was too long, and counts the comparisons
+    pub(crate) fn report_scan_(
+        &self,
+        operation: ScanOperation,
+        key: &dyn Debug,
+        hint: usize,
+        hops: usize,
+    ) {
+        self.count_comparisons_(operation, hops);
+        crate::instrument::scanned(operation, hops);
+        if let Some(warning) = &self.scan_warning_ {
+            if hops > warning.threshold_ {
+                (warning.callback_)(&LongScan {
+                    operation,
+                    key,
+                    hint,
+                    hops,
+                    list_id: self.list_id(),
+                });
+            }
+        }
+    }
+}
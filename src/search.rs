@@ -0,0 +1,245 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Search strategies used when searching from a position hint.
+
+use crate::{LinkedList, OUT_OF_BOUNDS};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+/// How ordered_insert_pos() and lower_bound_pos() search from a valid position hint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchStrategy {
+    /// Compare the key of every node from the hint to the target (the default)
+    #[default]
+    Linear,
+    /// Follow 1, 2, 4, 8... links between comparisons, then binary search the last stride.
+    ///
+    /// Only the number of key comparisons is reduced, to O(log d) for a target d nodes away,
+    /// which helps when comparing keys is expensive. A linked list can't skip nodes, so the
+    /// number of followed links is still O(d): the gallop follows every link up to the target,
+    /// and the binary search re-walks parts of the last stride, at most twice its length.
+    Galloping,
+}
+
+/// Which item ordered_insert_pos() and the lower bound searches return when several adjacent
+/// items compare Equal to the searched key. That can only happen with keys whose order is not
+/// transitive, with a total order the policies give the same result.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EqualPolicy {
+    /// Return the first Equal item the search reaches (the default). Which one that is depends
+    /// on the position hint and on the search direction.
+    #[default]
+    StopAtFirst,
+    /// Return the first item of the run of Equal items
+    Leftmost,
+    /// Return the last item of the run of Equal items
+    Rightmost,
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    #[inline(always)]
+    /// Returns the search strategy used from position hints
+    pub fn search_strategy(&self) -> SearchStrategy {
+        self.search_strategy_
+    }
+
+    /// Selects the search strategy used by ordered_insert_pos() and lower_bound_pos() when the
+    /// position hint is valid. Searches without a valid hint are always linear.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, SearchStrategy};
+    /// let mut ll = LinkedList::<i32, ()>::default();
+    /// ll.set_search_strategy(SearchStrategy::Galloping);
+    /// for i in 0..100 {
+    ///     let _ = ll.ordered_insert_pos(i * 2, (), ll.head()); // stale hint
+    /// }
+    /// let lb = ll.lower_bound_pos(&51, ll.head()).unwrap().unwrap();
+    /// assert_eq!(ll.get_k(lb).unwrap(), &52);
+    /// ```
+    pub fn set_search_strategy(&mut self, strategy: SearchStrategy) {
+        self.search_strategy_ = strategy;
+    }
+
+    #[inline(always)]
+    /// Returns the policy for keys that compare Equal to several items
+    pub fn equal_policy(&self) -> EqualPolicy {
+        self.equal_policy_
+    }
+
+    /// Selects which item ordered_insert_pos(), lower_bound() and lower_bound_pos() return when
+    /// several adjacent items compare Equal to the key, see [`EqualPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{EqualPolicy, LinkedList};
+    /// # use std::cmp::Ordering;
+    /// // keys that are at most 1 apart are Equal, which is not transitive
+    /// #[derive(Debug, Eq)]
+    /// struct Near(i32);
+    /// impl Ord for Near {
+    ///     fn cmp(&self, other: &Self) -> Ordering {
+    ///         if (self.0 - other.0).abs() <= 1 { Ordering::Equal } else { self.0.cmp(&other.0) }
+    ///     }
+    /// }
+    /// # impl PartialOrd for Near {
+    /// #     fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+    /// # }
+    /// # impl PartialEq for Near {
+    /// #     fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+    /// # }
+    /// let mut ll = LinkedList::<Near, ()>::default();
+    /// let zero = ll.ordered_insert(Near(0), ()).unwrap();
+    /// let two = ll.ordered_insert(Near(2), ()).unwrap();
+    /// assert_eq!(ll.ordered_insert_pos(Near(1), (), zero).unwrap(), zero);
+    /// ll.set_equal_policy(EqualPolicy::Rightmost);
+    /// assert_eq!(ll.ordered_insert_pos(Near(1), (), zero).unwrap(), two);
+    /// assert_eq!(ll.len(), 2);
+    /// ```
+    pub fn set_equal_policy(&mut self, policy: EqualPolicy) {
+        self.equal_policy_ = policy;
+    }
+
+    /// Moves from the item at index, that compares Equal to `key`, to the item selected by the
+    /// equal policy
+    pub(crate) fn apply_equal_policy_<Q>(&self, key: &Q, index: usize) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut curr_index = index;
+        loop {
+            let next = match (self.equal_policy_, self.nodes_.get(curr_index)) {
+                (EqualPolicy::Leftmost, Some(Some(node))) => node.prev_,
+                (EqualPolicy::Rightmost, Some(Some(node))) => node.next_,
+                _ => return curr_index,
+            };
+            match self.nodes_.get(next) {
+                Some(Some(node)) if key.cmp(node.key_.borrow()) == Ordering::Equal => {
+                    curr_index = next
+                }
+                _ => return curr_index,
+            }
+        }
+    }
+
+    /// Applies the equal policy to the result of a lower bound search
+    pub(crate) fn equal_policy_bound_<Q>(&self, key: &Q, bound: Option<usize>) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match bound {
+            Some(index)
+                if self.equal_policy_ != EqualPolicy::StopAtFirst
+                    && matches!(self.nodes_.get(index),
+                        Some(Some(node)) if key.cmp(node.key_.borrow()) == Ordering::Equal) =>
+            {
+                Some(self.apply_equal_policy_(key, index))
+            }
+            bound => bound,
+        }
+    }
+
+    /// Galloping lower bound search from the valid index `position`.
+    /// Returns the first index whose key does not go before `key`, and the number of visited nodes.
+    pub(crate) fn gallop_lower_bound_<Q>(&self, key: &Q, position: usize) -> (Option<usize>, usize)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let start_key = match self.nodes_.get(position) {
+            Some(Some(node)) => node.key_.borrow(),
+            _ => return (None, 0),
+        };
+        // search up the list if the start node does not go before `key`
+        let up = key.cmp(start_key) != Ordering::Greater;
+        // true if the search has passed the lower bound at index
+        let crossed = |index: usize| {
+            let greater = self
+                .nodes_
+                .get(index)
+                .and_then(|n| n.as_ref())
+                .is_some_and(|n| key.cmp(n.key_.borrow()) == Ordering::Greater);
+            greater == up
+        };
+
+        // the next index in the search direction, OUT_OF_BOUNDS at the end of the list
+        let step = |index: usize| match self.nodes_.get(index) {
+            Some(Some(node)) if up => node.prev_,
+            Some(Some(node)) => node.next_,
+            _ => OUT_OF_BOUNDS,
+        };
+
+        let mut hops = 1_usize;
+        let mut stride = 1_usize;
+        let mut curr_index = position;
+        // the start of the last stride, it has not crossed
+        let mut low;
+        // the number of links followed in the last stride
+        let mut len;
+        loop {
+            low = curr_index;
+            len = 0_usize;
+            let mut at_end = false;
+            for _ in 0..stride {
+                let next = step(curr_index);
+                if let Some(Some(_)) = self.nodes_.get(next) {
+                    hops += 1;
+                    len += 1;
+                    curr_index = next;
+                } else {
+                    at_end = true;
+                    break;
+                }
+            }
+            if at_end || len == 0 || crossed(curr_index) {
+                break;
+            }
+            stride *= 2;
+        }
+        // binary search the stride, low is `low_steps` links after its start and has not
+        // crossed, high is `high_steps` links after its start and has crossed (or is the end)
+        let (mut high, mut high_steps) = if crossed(curr_index) {
+            (curr_index, len)
+        } else {
+            (OUT_OF_BOUNDS, len + 1)
+        };
+        let mut low_steps = 0_usize;
+        while low_steps + 1 < high_steps {
+            let mid_steps = low_steps + (high_steps - low_steps) / 2;
+            let mut mid = low;
+            for _ in low_steps..mid_steps {
+                mid = step(mid);
+            }
+            if crossed(mid) {
+                high = mid;
+                high_steps = mid_steps;
+            } else {
+                low = mid;
+                low_steps = mid_steps;
+            }
+        }
+        let rv = if up {
+            // the last node that has not crossed
+            Some(low)
+        } else {
+            // the first node that has crossed
+            self.get_opt(high).map(|_| high)
+        };
+        (rv, hops)
+    }
+}
@@ -0,0 +1,56 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A key-only list, the C++ std::set counterpart of [`LinkedList`].
+
+use crate::{LinkedList, MapError};
+use std::fmt::Debug;
+
+/// A [`LinkedList`] without values, emulating a C++ std::set.
+///
+/// The value type is `()`. Zero-sized fields take no space, so with the default `M = ()` a node
+/// of a LinkedSet is exactly the two links and the key, and moving `()` values around compiles
+/// to nothing. No separate set implementation is needed: every LinkedList operation is
+/// available, the methods below just spare the `()`.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::LinkedSet;
+/// let mut set = LinkedSet::<i8>::default();
+/// let _ = set.insert(3).unwrap();
+/// let _ = set.insert(1).unwrap();
+/// let _ = set.insert(3).unwrap(); // a NOP
+/// assert_eq!(set.keys().collect::<Vec<_>>(), vec![&1, &3]);
+/// assert!(set.contains_key(&1));
+/// assert_eq!(set.take_key(&1).unwrap(), Some(1));
+/// ```
+pub type LinkedSet<K, M = ()> = LinkedList<K, (), M>;
+
+impl<K, M> LinkedList<K, (), M>
+where
+    K: Debug + Ord + PartialOrd,
+    M: Default,
+{
+    #[inline(always)]
+    /// Inserts a key, see [`LinkedList::ordered_insert()`]. Returns the index of the key.
+    pub fn insert(&mut self, key: K) -> Result<usize, MapError> {
+        self.ordered_insert(key, ())
+    }
+
+    #[inline(always)]
+    /// Inserts a key with a position hint, see [`LinkedList::ordered_insert_pos()`]
+    pub fn insert_pos(&mut self, key: K, position: usize) -> Result<usize, MapError> {
+        self.ordered_insert_pos(key, (), position)
+    }
+
+    #[inline(always)]
+    /// Removes a key, returns it if it was found. See [`LinkedList::remove_by_key()`].
+    pub fn take_key(&mut self, key: &K) -> Result<Option<K>, MapError> {
+        Ok(self.remove_by_key(key)?.map(|(k, ())| k))
+    }
+}
@@ -0,0 +1,213 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Verification of a [`LinkedList`] against a [`BTreeMap`] shadow copy.
+
+use crate::{LinkedList, MapError};
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::ops::Bound;
+
+/// A [`LinkedList`] that mirrors every mutation into a [`BTreeMap`] and compares the two after
+/// each step. It panics with a description of the first difference as soon as they diverge,
+/// e.g. when a key that is not entirely transitive, or a replace_key(), breaks the order.
+///
+/// Every step compares the whole list, so this is only intended as a checking mode.
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{LinkedList, Shadowed};
+/// let mut s = Shadowed::new(LinkedList::<i8, i8>::default());
+/// let _ = s.ordered_insert(2, 2);
+/// let _ = s.ordered_insert_pos(1, 1, s.list().tail());
+/// let _ = s.ordered_insert(1, 10); // NOP in both maps
+/// assert_eq!(s.list().get_k(s.lower_bound(&0).unwrap().unwrap()).unwrap(), &1);
+/// assert_eq!(s.shadow().len(), 2);
+/// ```
+///
+/// ```should_panic
+/// # use cpp_map::{LinkedList, Shadowed};
+/// let mut s = Shadowed::new(LinkedList::<i8, i8>::default());
+/// let one = s.ordered_insert(1, 1).unwrap();
+/// let _ = s.ordered_insert(2, 2);
+/// let _ = s.replace_key(one, 3); // 3 is now in front of 2
+/// ```
+#[derive(Debug)]
+pub struct Shadowed<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: LinkedList<K, V>,
+    shadow_: BTreeMap<K, V>,
+}
+
+impl<K, V> Shadowed<K, V>
+where
+    K: Debug + Ord + PartialOrd + Clone,
+    V: Debug + PartialEq + Clone,
+{
+    /// Wraps a list, the shadow map is built from the current content of the list.
+    /// Panics if the list is not already in order.
+    pub fn new(list: LinkedList<K, V>) -> Self {
+        let shadow_ = list.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        let rv = Self {
+            list_: list,
+            shadow_,
+        };
+        rv.verify_("new()");
+        rv
+    }
+
+    #[inline(always)]
+    /// Read only access to the list
+    pub fn list(&self) -> &LinkedList<K, V> {
+        &self.list_
+    }
+
+    #[inline(always)]
+    /// Read only access to the shadow map
+    pub fn shadow(&self) -> &BTreeMap<K, V> {
+        &self.shadow_
+    }
+
+    #[inline(always)]
+    /// Drops the shadow map and returns the list
+    pub fn into_inner(self) -> LinkedList<K, V> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`], verified
+    pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        self.insert_(key, value, None)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`], verified. Inserting an already existing
+    /// key must be a NOP in both maps.
+    pub fn ordered_insert_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        self.insert_(key, value, Some(position))
+    }
+
+    /// Inserts with an optional position hint, verified
+    fn insert_(&mut self, key: K, value: V, position: Option<usize>) -> Result<usize, MapError> {
+        let (shadow_key, shadow_value) = (key.clone(), value.clone());
+        let index = match position {
+            Some(position) => self.list_.ordered_insert_pos(key, value, position)?,
+            None => self.list_.ordered_insert(key, value)?,
+        };
+        // a failed insert, e.g. a rejected hint, must not reach the shadow map
+        let _ = self.shadow_.entry(shadow_key).or_insert(shadow_value);
+        self.verify_("ordered_insert_pos()");
+        Ok(index)
+    }
+
+    /// Replace the key at index, verified. This will panic if the new key is out of order.
+    pub fn replace_key(&mut self, index: usize, key: K) -> Result<(), MapError> {
+        let old_key = self.list_.replace_key_(index, key.clone())?;
+        if let Some(value) = self.shadow_.remove(&old_key) {
+            let _ = self.shadow_.insert(key, value);
+        }
+        self.verify_("replace_key()");
+        Ok(())
+    }
+
+    /// Remove the item at index and return it, verified.
+    pub fn remove(&mut self, index: usize) -> Result<(K, V), MapError> {
+        let (_, (key, value), _) = self.list_.remove__(index)?;
+        let _ = self.shadow_.remove(&key);
+        self.verify_("remove()");
+        Ok((key, value))
+    }
+
+    /// Same as [`LinkedList::pop_front()`], verified
+    pub fn pop_front(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let head = self.list_.head_;
+        Ok(Some(self.remove(head)?))
+    }
+
+    /// Same as [`LinkedList::pop_back()`], verified
+    pub fn pop_back(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let tail = self.list_.tail_;
+        Ok(Some(self.remove(tail)?))
+    }
+
+    /// Same as [`LinkedList::lower_bound()`], the answer is verified against the shadow map.
+    pub fn lower_bound<Q>(&self, key: &Q) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let rv = self.list_.lower_bound(key)?;
+        self.verify_lower_bound_("lower_bound()", key, rv);
+        Ok(rv)
+    }
+
+    /// Same as [`LinkedList::lower_bound_pos()`], the answer is verified against the shadow map.
+    pub fn lower_bound_pos<Q>(&self, key: &Q, position: usize) -> Result<Option<usize>, MapError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let rv = self.list_.lower_bound_pos(key, position)?;
+        self.verify_lower_bound_("lower_bound_pos()", key, rv);
+        Ok(rv)
+    }
+
+    /// Panics if the lower bound of the list differs from the lower bound of the shadow map
+    fn verify_lower_bound_<Q>(&self, operation: &str, key: &Q, index: Option<usize>)
+    where
+        K: Borrow<Q>,
+        Q: Ord + Debug + ?Sized,
+    {
+        let found = index.and_then(|i| self.list_.get_k_opt(i));
+        let expected = self
+            .shadow_
+            .range::<Q, _>((Bound::Included(key), Bound::Unbounded))
+            .next()
+            .map(|(k, _)| k);
+        if found != expected {
+            panic!(
+                "shadow map diverged in {} for key {:?}: list found {:?}, BTreeMap found {:?}",
+                operation, key, found, expected
+            );
+        }
+    }
+
+    /// Panics with the first difference if the list and the shadow map differ
+    fn verify_(&self, operation: &str) {
+        let mut list_iter = self.list_.iter();
+        let mut shadow_iter = self.shadow_.iter();
+        let mut position = 0_usize;
+        loop {
+            let (l, s) = (list_iter.next(), shadow_iter.next());
+            if l.is_none() && s.is_none() {
+                return;
+            }
+            if l != s {
+                panic!(
+                    "shadow map diverged after {}: first difference at position {}: list has {:?}, BTreeMap has {:?}",
+                    operation, position, l, s
+                );
+            }
+            position += 1;
+        }
+    }
+}
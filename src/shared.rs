@@ -0,0 +1,183 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The shared ownership wrappers a [`crate::PIterator`] can use to reach its list.
+
+use crate::{instrument, LinkedList, MapError};
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+
+/// A handle to a list shared between [`crate::PIterator`]s.
+///
+/// Implemented for `Rc<RefCell<LinkedList>>` (the default), `&RefCell<LinkedList>` for purely
+/// borrowed use and `Arc<Mutex<LinkedList>>` for use from several threads.
+/// Every PIterator operation accesses the list once, for the duration of that operation only.
+///
+/// The `Arc<Mutex<..>>` accessors never block, they return a `WouldBlock` error while the lock
+/// is held, by another thread or by the caller itself, and an `InternalError` if the mutex is
+/// poisoned.
+pub trait SharedList<K, V, M>: Clone
+where
+    K: Debug,
+    V: Debug,
+{
+    /// Read access to the list
+    type Read<'a>: Deref<Target = LinkedList<K, V, M>>
+    where
+        Self: 'a;
+    /// Write access to the list
+    type Write<'a>: DerefMut<Target = LinkedList<K, V, M>>
+    where
+        Self: 'a;
+
+    /// Returns read access to the list, or an error if the list is not accessible
+    fn try_read(&self) -> Result<Self::Read<'_>, MapError>;
+
+    /// Returns write access to the list, or an error if the list is not accessible
+    fn try_write(&self) -> Result<Self::Write<'_>, MapError>;
+
+    /// Returns true if both handles refer to the same list. The list is not accessed.
+    fn same_list(&self, other: &Self) -> bool;
+
+    /// Returns true if `list` is the list behind this handle. Only the addresses are compared,
+    /// so this works while `list` is borrowed from the handle.
+    fn is_list(&self, list: &LinkedList<K, V, M>) -> bool;
+}
+
+impl<K, V, M> SharedList<K, V, M> for Rc<RefCell<LinkedList<K, V, M>>>
+where
+    K: Debug,
+    V: Debug,
+{
+    type Read<'a>
+        = Ref<'a, LinkedList<K, V, M>>
+    where
+        Self: 'a;
+    type Write<'a>
+        = RefMut<'a, LinkedList<K, V, M>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn try_read(&self) -> Result<Self::Read<'_>, MapError> {
+        self.try_borrow().map_err(|e| {
+            instrument::borrow_failed();
+            e.into()
+        })
+    }
+
+    #[inline(always)]
+    fn try_write(&self) -> Result<Self::Write<'_>, MapError> {
+        self.try_borrow_mut().map_err(|e| {
+            instrument::borrow_failed();
+            e.into()
+        })
+    }
+
+    #[inline(always)]
+    fn same_list(&self, other: &Self) -> bool {
+        Rc::ptr_eq(self, other)
+    }
+
+    #[inline(always)]
+    fn is_list(&self, list: &LinkedList<K, V, M>) -> bool {
+        std::ptr::eq(self.as_ptr(), list)
+    }
+}
+
+impl<K, V, M> SharedList<K, V, M> for &RefCell<LinkedList<K, V, M>>
+where
+    K: Debug,
+    V: Debug,
+{
+    type Read<'a>
+        = Ref<'a, LinkedList<K, V, M>>
+    where
+        Self: 'a;
+    type Write<'a>
+        = RefMut<'a, LinkedList<K, V, M>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn try_read(&self) -> Result<Self::Read<'_>, MapError> {
+        self.try_borrow().map_err(|e| {
+            instrument::borrow_failed();
+            e.into()
+        })
+    }
+
+    #[inline(always)]
+    fn try_write(&self) -> Result<Self::Write<'_>, MapError> {
+        self.try_borrow_mut().map_err(|e| {
+            instrument::borrow_failed();
+            e.into()
+        })
+    }
+
+    #[inline(always)]
+    fn same_list(&self, other: &Self) -> bool {
+        std::ptr::eq(*self, *other)
+    }
+
+    #[inline(always)]
+    fn is_list(&self, list: &LinkedList<K, V, M>) -> bool {
+        std::ptr::eq(self.as_ptr(), list)
+    }
+}
+
+impl<K, V, M> SharedList<K, V, M> for Arc<Mutex<LinkedList<K, V, M>>>
+where
+    K: Debug,
+    V: Debug,
+{
+    type Read<'a>
+        = MutexGuard<'a, LinkedList<K, V, M>>
+    where
+        Self: 'a;
+    type Write<'a>
+        = MutexGuard<'a, LinkedList<K, V, M>>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn try_read(&self) -> Result<Self::Read<'_>, MapError> {
+        self.try_lock().map_err(|e| {
+            instrument::borrow_failed();
+            match e {
+                TryLockError::WouldBlock => map_error!(WouldBlock, "the list mutex is locked"),
+                TryLockError::Poisoned(_) => {
+                    map_error!(InternalError, "the list mutex is poisoned")
+                }
+            }
+        })
+    }
+
+    #[inline(always)]
+    fn try_write(&self) -> Result<Self::Write<'_>, MapError> {
+        self.try_read()
+    }
+
+    #[inline(always)]
+    fn same_list(&self, other: &Self) -> bool {
+        Arc::ptr_eq(self, other)
+    }
+
+    #[inline(always)]
+    fn is_list(&self, list: &LinkedList<K, V, M>) -> bool {
+        // the mutex can't be locked here, it is probably locked by the caller. The list inside
+        // the mutex is the only list that can lie within the memory of the mutex.
+        let mutex = Arc::as_ptr(self) as usize;
+        let list = list as *const LinkedList<K, V, M> as usize;
+        list >= mutex
+            && list + std::mem::size_of::<LinkedList<K, V, M>>()
+                <= mutex + std::mem::size_of::<Mutex<LinkedList<K, V, M>>>()
+    }
+}
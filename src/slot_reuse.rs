@@ -0,0 +1,82 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Policies for the order in which free slots are reused.
+
+use crate::LinkedList;
+use std::fmt::Debug;
+
+/// The order in which the slots of removed items are reused by inserts.
+///
+/// The free index pool is always kept in reuse order, the last index of
+/// [`LinkedList::free_slots()`] is the next one to be reused. The policy only decides where a
+/// released slot is put in the pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SlotReuse {
+    /// The most recently freed slot is reused first (the default). Releasing a slot is O(1).
+    #[default]
+    Lifo,
+    /// The slot that has been free the longest is reused first. Releasing a slot is O(f),
+    /// f being the number of free slots, as the slot is inserted at the front of the pool.
+    /// Removing r items in a row, e.g. by retain() or erase_range(), is therefore O(r·(f + r)).
+    Fifo,
+    /// The free slot with the lowest index is reused first, this keeps the live items packed at
+    /// the start of the node vector. Releasing a slot is O(f), and removing r items in a row is
+    /// O(r·(f + r)), like [`SlotReuse::Fifo`].
+    LowestFirst,
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    #[inline(always)]
+    /// Returns the free slot reuse policy
+    pub fn slot_reuse(&self) -> SlotReuse {
+        self.slot_reuse_
+    }
+
+    /// Selects the order in which free slots are reused. The current free slots are reordered
+    /// to match the new policy, as far as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, SlotReuse};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// ll.set_slot_reuse(SlotReuse::LowestFirst);
+    /// for i in 0..4 {
+    ///     let _ = ll.ordered_insert(i, i);
+    /// }
+    /// let _ = ll.pop_front(); // slot 0
+    /// let _ = ll.pop_back(); // slot 3
+    /// assert_eq!(ll.next_free_index(), 0);
+    /// ll.set_slot_reuse(SlotReuse::Lifo);
+    /// let _ = ll.pop_front(); // slot 1
+    /// assert_eq!(ll.next_free_index(), 1);
+    /// ```
+    pub fn set_slot_reuse(&mut self, policy: SlotReuse) {
+        self.slot_reuse_ = policy;
+        if policy == SlotReuse::LowestFirst {
+            self.id_pool_.sort_unstable_by(|a, b| b.cmp(a));
+        }
+    }
+
+    /// Puts a released slot in the free index pool, at the position given by the reuse policy
+    pub(crate) fn free_slot_(&mut self, index: usize) {
+        match self.slot_reuse_ {
+            SlotReuse::Lifo => self.id_pool_.push(index),
+            SlotReuse::Fifo => self.id_pool_.insert(0, index),
+            SlotReuse::LowestFirst => {
+                // descending order, the lowest index is popped first
+                let pos = self.id_pool_.partition_point(|i| *i > index);
+                self.id_pool_.insert(pos, index);
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strict hints: rejecting position hints that are not next to the inserted key.
+
+use crate::{LinkedList, MapError};
+use std::fmt::Debug;
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Enables or disables strict hints, disabled by default.
+    ///
+    /// In strict mode every insert with a position hint, e.g.
+    /// [`LinkedList::ordered_insert_pos()`], [`LinkedList::ordered_insert_or_assign_pos()`] or
+    /// [`LinkedList::ordered_insert_merge_pos()`], returns a `HintMismatch` error, and inserts
+    /// nothing, unless the key belongs right next to the hinted item: the key must not
+    /// go before the key of the previous item, nor after the key of the next item. A hint that
+    /// is not an active item is also a mismatch, except in an empty list where the hint is
+    /// always ignored. Meant for development, to find hints that make the inserts scan far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, MapErrorKind};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// ll.set_strict_hints(true);
+    /// let one = ll.ordered_insert_pos(1, 1, 0).unwrap();
+    /// let five = ll.ordered_insert_pos(5, 5, one).unwrap();
+    /// let nine = ll.ordered_insert_pos(9, 9, five).unwrap();
+    /// let _ = ll.ordered_insert_pos(3, 3, five).unwrap();
+    /// let err = ll.ordered_insert_pos(2, 2, nine).unwrap_err();
+    /// assert_eq!(err.kind(), MapErrorKind::HintMismatch);
+    /// assert_eq!(ll.len(), 4);
+    /// ```
+    pub fn set_strict_hints(&mut self, enabled: bool) {
+        self.strict_hints_ = enabled;
+    }
+
+    #[inline(always)]
+    /// Returns true if strict hints are enabled
+    pub fn has_strict_hints(&self) -> bool {
+        self.strict_hints_
+    }
+
+    /// Returns a HintMismatch error if strict hints are enabled and `key` does not belong next
+    /// to the item at `position`. Every public insert with a position hint calls this.
+    pub(crate) fn check_hint_(&self, key: &K, position: usize) -> Result<(), MapError> {
+        if !self.strict_hints_ || self.is_empty() {
+            return Ok(());
+        }
+        let node = match self.nodes_.get(position) {
+            Some(Some(node)) => node,
+            _ => {
+                return Err(map_error!(
+                    HintMismatch,
+                    "the hint {} for the key {:?} is not an active item",
+                    position,
+                    key
+                ))
+            }
+        };
+        let after_prev = self.get_k_opt(node.prev_).map_or(true, |prev| key >= prev);
+        let before_next = self.get_k_opt(node.next_).map_or(true, |next| key <= next);
+        if !(after_prev && before_next) {
+            return Err(map_error!(
+                HintMismatch,
+                "the key {:?} does not belong next to the hint {} with the key {:?}",
+                key,
+                position,
+                node.key_
+            ));
+        }
+        Ok(())
+    }
+}
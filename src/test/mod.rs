@@ -1,8 +1,48 @@
+// the original tests clone Copy and borrowed keys
+#![allow(
+    noop_method_call,
+    clippy::clone_on_copy,
+    clippy::non_canonical_partial_ord_impl
+)]
+// MapErrorKind.into() is a no-op conversion when MapError is MapErrorKind
+#![cfg_attr(not(feature = "rich_error"), allow(clippy::useless_conversion))]
+
+use super::BinaryCodec;
+use super::Bookmark;
+use super::Change;
+use super::CompareStats;
+use super::Cursor;
+use super::CursorPair;
+use super::DeferredInserts;
+use super::Entry;
+use super::EqualPolicy;
+use super::ExternalValues;
+use super::Fingerprinted;
+use super::FixedCapacity;
+use super::FreezeGuard;
+use super::History;
+use super::IndexRemap;
+use super::Indexed;
+use super::InsertResult;
 use super::LinkedList;
+use super::LinkedSet;
+use super::ListArena;
+use super::ListPool;
+use super::LowerBound;
 use super::MapError;
+use super::MapErrorKind;
+use super::NodeHandle;
 use super::PIterator;
+use super::SearchStrategy;
+use super::Shadowed;
+use super::SlotReuse;
+use super::{Aggregated, Count, Monoid};
+use super::{BudgetInsert, BudgetSearch};
+use super::{TotalF32, TotalF64};
+use super::{TraceOp, Tracer};
 use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::ops::Bound;
 use std::rc::Rc;
 
 /// A test _S_orted _O_bject that only sorts by key
@@ -507,25 +547,25 @@ fn linked_list_lower_bound_01() -> Result<(), MapError> {
     // Returns the first element in the container whose key is not considered to go
     // before position (i.e., either it is equivalent or goes after).
     // Returns None if no data is found
-    let v = ll.lower_bound(0)?;
+    let v = ll.lower_bound(&0)?;
     assert_eq!(v, Some(0));
-    let v = ll.lower_bound(1)?;
+    let v = ll.lower_bound(&1)?;
     assert_eq!(v, Some(1));
-    let v = ll.lower_bound(2)?;
+    let v = ll.lower_bound(&2)?;
     assert_eq!(v, Some(2));
-    let v = ll.lower_bound(5)?;
+    let v = ll.lower_bound(&5)?;
     assert_eq!(v, Some(3));
-    let v = ll.lower_bound(15)?;
+    let v = ll.lower_bound(&15)?;
     assert_eq!(v, None);
-    let v = ll.lower_bound(0)?;
+    let v = ll.lower_bound(&0)?;
     assert_eq!(v, Some(0));
-    let v = ll.lower_bound(1)?;
+    let v = ll.lower_bound(&1)?;
     assert_eq!(v, Some(1));
-    let v = ll.lower_bound(2)?;
+    let v = ll.lower_bound(&2)?;
     assert_eq!(v, Some(2));
-    let v = ll.lower_bound(5)?;
+    let v = ll.lower_bound(&5)?;
     assert_eq!(v, Some(3));
-    let v = ll.lower_bound(15)?;
+    let v = ll.lower_bound(&15)?;
     assert_eq!(v, None);
     Ok(())
 }
@@ -534,7 +574,7 @@ fn linked_list_lower_bound_01() -> Result<(), MapError> {
 /// test lower_bound() when list is empty
 fn linked_list_lower_bound_02() -> Result<(), MapError> {
     let ll = LinkedList::<i8, i8>::default();
-    let v = ll.lower_bound(0)?;
+    let v = ll.lower_bound(&0)?;
     assert_eq!(v, None);
     Ok(())
 }
@@ -570,9 +610,9 @@ fn linked_list_pointer_test01() -> Result<(), MapError> {
 /// test PIterator::lower_bound
 fn linked_list_pointer_test02() -> Result<(), MapError> {
     let ll = Rc::from(RefCell::from(LinkedList::<i8, i8>::default()));
-    let v = PIterator::lower_bound(Rc::clone(&ll), 1)?;
+    let v = PIterator::lower_bound(Rc::clone(&ll), &1)?;
     assert!(!v.is_ok()?);
-    let v = PIterator::lower_bound(Rc::clone(&ll), 1)?;
+    let v = PIterator::lower_bound(Rc::clone(&ll), &1)?;
     assert!(!v.is_ok()?);
     Ok(())
 }
@@ -585,15 +625,3409 @@ fn linked_list_pointer_test03() -> Result<(), MapError> {
     let _ = ll.borrow_mut().ordered_insert(2, 1)?; // 1
     let _ = ll.borrow_mut().ordered_insert(3, 2)?; // 2
     let _ = ll.borrow_mut().ordered_insert(4, 3)?; // 3
-    let lb = PIterator::lower_bound(Rc::clone(&ll), 3)?;
+    let lb = PIterator::lower_bound(Rc::clone(&ll), &3)?;
     assert!(lb.is_ok()?);
     assert_eq!(lb.get_k()?, 3);
 
-    let lb = PIterator::lower_bound(Rc::clone(&ll), 1)?;
+    let lb = PIterator::lower_bound(Rc::clone(&ll), &1)?;
     assert!(lb.is_ok()?);
     assert_eq!(lb.get_k()?, 1);
 
-    let v = PIterator::lower_bound(Rc::clone(&ll), 5)?;
+    let v = PIterator::lower_bound(Rc::clone(&ll), &5)?;
     assert!(!v.is_ok()?);
     Ok(())
 }
+
+#[test]
+/// test TotalF64 and TotalF32 as keys
+fn linked_list_total_float_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<TotalF64, i8>::default();
+    let _ = ll.ordered_insert(TotalF64(1.5), 0)?;
+    let _ = ll.ordered_insert(TotalF64(f64::NAN), 1)?;
+    let _ = ll.ordered_insert(TotalF64(-0.0), 2)?;
+    let _ = ll.ordered_insert(TotalF64(0.0), 3)?;
+    let _ = ll.ordered_insert(TotalF64(f64::NEG_INFINITY), 4)?;
+    let _ = ll.ordered_insert(TotalF64(1.5), 5)?; // <- this is a NOP
+    assert_eq!(
+        ll.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        vec![4_i8, 2, 3, 0, 1]
+    );
+    assert_eq!(ll.lower_bound(&TotalF64(1.0))?, Some(0));
+
+    let mut ll = LinkedList::<TotalF32, i8>::default();
+    let _ = ll.ordered_insert_pos(TotalF32(2.0), 0, ll.tail())?;
+    let _ = ll.ordered_insert_pos(TotalF32(-2.0), 1, ll.tail())?;
+    assert_eq!(
+        ll.iter().map(|(x, _)| f32::from(*x)).collect::<Vec<_>>(),
+        vec![-2.0_f32, 2.0]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "derive")]
+#[test]
+/// test #[derive(SortByKey)]
+fn linked_list_derive_01() -> Result<(), MapError> {
+    #[derive(Debug, super::SortByKey)]
+    struct Dk {
+        #[sort_key]
+        key: i32,
+        payload: i32,
+    }
+    #[derive(Debug, super::SortByKey)]
+    struct Tk(i32, #[sort_key] i8);
+
+    let mut ll = LinkedList::<Dk, i8>::default();
+    let _ = ll.ordered_insert(Dk { key: 2, payload: 0 }, 0)?;
+    let _ = ll.ordered_insert(Dk { key: 1, payload: 1 }, 1)?;
+    let _ = ll.ordered_insert(Dk { key: 2, payload: 2 }, 2)?; // <- this is a NOP
+    assert_eq!(
+        ll.iter()
+            .map(|(x, _)| (x.key, x.payload))
+            .collect::<Vec<_>>(),
+        vec![(1, 1), (2, 0)]
+    );
+    let tk = Tk(5, 1);
+    assert!(tk < Tk(0, 2));
+    assert!(tk == Tk(0, 1));
+    assert_eq!(tk.0, 5);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::transaction() commit and rollback
+fn linked_list_transaction_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    let _ = ll.ordered_insert(1, 0)?; // 0
+    let _ = ll.ordered_insert(2, 1)?; // 1
+    let _ = ll.ordered_insert(3, 2)?; // 2
+    let _ = ll.remove_(1)?;
+    let before = format!("{:?}", ll);
+
+    let rv: Result<(), MapError> = ll.transaction(|editor| {
+        let _ = editor.ordered_insert(4, 3)?;
+        let _ = editor.ordered_insert(5, 4)?;
+        let _ = editor.ordered_insert(5, 5)?; // <- this is a NOP
+        let _ = editor.remove(0)?;
+        let _ = editor.remove(editor.list().tail())?;
+        editor.replace_key(2, 0)?;
+        Err(MapErrorKind::InternalError.into())
+    });
+    assert!(rv.is_err());
+    assert_eq!(format!("{:?}", ll), before);
+
+    let index = ll.transaction(|editor| {
+        let _ = editor.remove(0)?;
+        editor.ordered_insert(0, 3)
+    })?;
+    assert_eq!(index, 0);
+    assert_eq!(
+        ll.iter().map(|(x, _)| *x).collect::<Vec<_>>(),
+        vec![0_i8, 3]
+    );
+    Ok(())
+}
+
+#[test]
+/// test that a rollback and undo/redo restore the metadata of removed items
+fn linked_list_transaction_02() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8, String>::default();
+    let one = ll.ordered_insert(1, 1)?;
+    let two = ll.ordered_insert(2, 2)?;
+    let _ = ll.set_meta(one, "one".to_string())?;
+    let _ = ll.set_meta(two, "two".to_string())?;
+    let before = format!("{:?}", ll);
+
+    let rv: Result<(), MapError> = ll.transaction(|editor| {
+        let _ = editor.remove(one)?;
+        let _ = editor.remove(two)?;
+        Err(MapErrorKind::InternalError.into())
+    });
+    assert!(rv.is_err());
+    assert_eq!(format!("{:?}", ll), before);
+    assert_eq!(ll.get_meta(one).map(|m| m.as_str()), Some("one"));
+
+    let mut h = History::new(ll, 2);
+    h.remove(two)?;
+    let _ = h.edit(|editor| editor.ordered_insert(3, 3))?;
+    assert!(h.undo()?);
+    assert!(h.undo()?);
+    assert_eq!(format!("{:?}", h.list()), before);
+    assert!(h.redo()?);
+    assert!(h.undo()?);
+    assert_eq!(h.list().get_meta(two).map(|m| m.as_str()), Some("two"));
+    Ok(())
+}
+
+#[test]
+/// test that removes of keys and values without Clone are rolled back and undone together
+/// with the bookmarks and the jump table
+fn linked_list_transaction_03() -> Result<(), MapError> {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct NoClone(i32);
+
+    let mut ll = LinkedList::<NoClone, NoClone>::default();
+    for i in 0..20 {
+        let _ = ll.ordered_insert(NoClone(i), NoClone(-i))?;
+    }
+    ll.set_jump_table(true);
+    let four = ll.lower_bound(&NoClone(4))?.unwrap();
+    let mark = ll.add_bookmark(four)?;
+    let before = format!("{:?}", ll);
+
+    let rv: Result<(), MapError> = ll.transaction(|editor| {
+        let head = editor.list().head();
+        assert_eq!(editor.remove(head)?, (&NoClone(0), &NoClone(0)));
+        for i in 1..10 {
+            let index = editor.list().lower_bound(&NoClone(i))?.unwrap();
+            let _ = editor.remove(index)?;
+        }
+        Err(MapErrorKind::InternalError.into())
+    });
+    assert!(rv.is_err());
+    assert_eq!(format!("{:?}", ll), before);
+    assert_eq!(ll.bookmark(mark), Some(four));
+
+    let mut h = History::new(ll, 2);
+    h.remove(four)?;
+    assert_ne!(h.list().bookmark(mark), Some(four));
+    assert!(h.undo()?);
+    assert_eq!(h.list().bookmark(mark), Some(four));
+    assert!(h.redo()?);
+    assert!(h.undo()?);
+    assert_eq!(h.list().bookmark(mark), Some(four));
+    assert_eq!(h.list().get_k(four)?, &NoClone(4));
+    Ok(())
+}
+
+#[test]
+/// test History::undo() and History::redo()
+fn linked_list_history_01() -> Result<(), MapError> {
+    let mut h = History::new(LinkedList::<i8, i8>::default(), 3);
+    let _ = h.ordered_insert(1, 0)?;
+    let _ = h.ordered_insert(2, 1)?;
+    let _ = h.ordered_insert(2, 2)?; // <- this is a NOP, not recorded
+    let s2 = format!("{:?}", h.list());
+    h.remove(0)?;
+    let s3 = format!("{:?}", h.list());
+    let _ = h.edit(|editor| {
+        editor.replace_key(1, 5)?;
+        editor.ordered_insert(3, 3)
+    })?;
+    let s4 = format!("{:?}", h.list());
+    assert_eq!(h.undo_len(), 3);
+
+    assert!(h.undo()?);
+    assert_eq!(format!("{:?}", h.list()), s3);
+    assert!(h.undo()?);
+    assert_eq!(format!("{:?}", h.list()), s2);
+    assert!(h.undo()?);
+    assert!(!h.undo()?); // depth was 3
+    assert_eq!(h.list().len(), 1);
+
+    assert!(h.redo()?);
+    assert_eq!(format!("{:?}", h.list()), s2);
+    assert!(h.redo()?);
+    assert!(h.redo()?);
+    assert_eq!(format!("{:?}", h.list()), s4);
+    assert!(!h.redo()?);
+
+    assert!(h.undo()?);
+    let _ = h.ordered_insert(0, 0)?;
+    assert_eq!(h.redo_len(), 0);
+    assert_eq!(
+        h.list().iter().map(|(x, _)| *x).collect::<Vec<_>>(),
+        vec![0_i8, 2]
+    );
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::to_text() and LinkedList::from_text()
+fn linked_list_text_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<TotalF64, String>::default();
+    let _ = ll.ordered_insert(TotalF64(1.5), "a b".to_string())?;
+    let _ = ll.ordered_insert(TotalF64(-2.0), "".to_string())?;
+    let text = ll.to_text();
+    assert_eq!(text, "-2\t\n1.5\ta b\n");
+    let ll2 = LinkedList::<TotalF64, String>::from_text(&text)?;
+    assert_eq!(
+        ll.iter().collect::<Vec<_>>(),
+        ll2.iter().collect::<Vec<_>>()
+    );
+
+    let ll = LinkedList::<i8, i8>::from_text("3\t0\n\n1\t1\n3\t2\n")?;
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        vec![(1_i8, 1_i8), (3, 0)]
+    );
+    let err = LinkedList::<i8, i8>::from_text("1\t1\n2 2\n").unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::ParseError);
+    #[cfg(feature = "rich_error")]
+    assert!(matches!(err, MapError::ParseError(2, _)));
+    let err = LinkedList::<i8, i8>::from_text("1\tx\n").unwrap_err();
+    #[cfg(feature = "rich_error")]
+    assert!(matches!(err, MapError::ParseError(1, _)));
+    assert_eq!(err.kind(), MapErrorKind::ParseError);
+    Ok(())
+}
+
+#[test]
+/// test that LinkedList::to_bytes() and LinkedList::from_bytes() preserves the slot layout
+fn linked_list_binary_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<TotalF32, (u8, String)>::default();
+    let _ = ll.ordered_insert(TotalF32(1.0), (1, "a".to_string()))?; // 0
+    let _ = ll.ordered_insert(TotalF32(2.0), (2, "b".to_string()))?; // 1
+    let _ = ll.ordered_insert(TotalF32(3.0), (3, "c".to_string()))?; // 2
+    let _ = ll.ordered_insert(TotalF32(0.5), (4, "d".to_string()))?; // 3
+    let _ = ll.remove_(2)?;
+    let _ = ll.remove_(0)?;
+    let bytes = ll.to_bytes();
+    let mut ll2 = LinkedList::<TotalF32, (u8, String)>::from_bytes(&bytes)?;
+    assert_eq!(format!("{:?}", ll), format!("{:?}", ll2));
+    assert_eq!(ll2.ordered_insert(TotalF32(5.0), (5, "e".to_string()))?, 0);
+    assert_eq!(ll2.ordered_insert(TotalF32(6.0), (6, "f".to_string()))?, 2);
+
+    let empty = LinkedList::<i8, ()>::from_bytes(&LinkedList::<i8, ()>::default().to_bytes())?;
+    assert!(empty.is_empty());
+
+    let err = LinkedList::<TotalF32, (u8, String)>::from_bytes(&bytes[..bytes.len() - 1]);
+    assert_eq!(err.unwrap_err().kind(), MapErrorKind::DecodeError);
+    let err = LinkedList::<TotalF32, (u8, String)>::from_bytes(&bytes[1..]);
+    assert_eq!(err.unwrap_err().kind(), MapErrorKind::DecodeError);
+    Ok(())
+}
+
+#[test]
+/// test that LinkedList::from_bytes() keeps the metadata and rejects broken links
+fn linked_list_binary_02() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8, String>::default();
+    let one = ll.ordered_insert(1, 1)?;
+    let _ = ll.ordered_insert(2, 2)?;
+    let _ = ll.set_meta(one, "one".to_string())?;
+    let ll2 = LinkedList::<i8, i8, String>::from_bytes(&ll.to_bytes())?;
+    assert_eq!(ll2.get_meta(one).map(|m| m.as_str()), Some("one"));
+    assert_eq!(ll2.get_meta(ll2.tail()).map(|m| m.as_str()), Some(""));
+
+    // two items, the second item links to `second_next`
+    let encode = |second_next: usize| {
+        let mut bytes = b"CPPM".to_vec();
+        bytes.push(1);
+        for i in [0_usize, 1, 2].iter() {
+            i.encode(&mut bytes); // head, tail and the number of slots
+        }
+        for (prev, next, key) in [(crate::OUT_OF_BOUNDS, 1_usize, 1_i8), (0, second_next, 2)].iter()
+        {
+            bytes.push(1);
+            prev.encode(&mut bytes);
+            next.encode(&mut bytes);
+            key.encode(&mut bytes);
+            key.encode(&mut bytes);
+            ().encode(&mut bytes);
+        }
+        0_usize.encode(&mut bytes); // free indices
+        false.encode(&mut bytes);
+        0_usize.encode(&mut bytes); // tombstones
+        bytes
+    };
+    let ll3 = LinkedList::<i8, i8>::from_bytes(&encode(crate::OUT_OF_BOUNDS))?;
+    assert_eq!(ll3.iter().count(), 2);
+    // 0 -> 1 -> 0
+    let err = LinkedList::<i8, i8>::from_bytes(&encode(0)).unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::DecodeError);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::free_slots(), live_slot_indices() and slot_count()
+fn linked_list_slots_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    assert_eq!(ll.slot_count(), 0);
+    assert_eq!(ll.live_slot_indices().count(), 0);
+    for i in 0..5 {
+        let _ = ll.ordered_insert(i, i)?;
+    }
+    let _ = ll.remove_(3)?;
+    let _ = ll.remove_(1)?;
+    assert_eq!(ll.free_slots(), &[3, 1]);
+    assert_eq!(ll.live_slot_indices().collect::<Vec<_>>(), vec![0, 2, 4]);
+    assert_eq!(ll.slot_count(), 5);
+    assert_eq!(ll.next_free_index(), 1);
+    let _ = ll.ordered_insert(9, 9)?;
+    assert_eq!(ll.free_slots(), &[3]);
+    assert_eq!(ll.slot_count(), 5);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::capacity_info()
+fn linked_list_capacity_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::with_capacity(3);
+    let c = ll.capacity_info();
+    assert_eq!((c.nodes, c.free_list, c.live, c.spare), (3, 3, 0, 3));
+    let _ = ll.ordered_insert(1, 0)?;
+    let _ = ll.ordered_insert(2, 0)?;
+    let _ = ll.ordered_insert(3, 0)?;
+    assert_eq!(ll.capacity_info().spare, 0);
+    let _ = ll.pop_back()?;
+    let c = ll.capacity_info();
+    assert_eq!((c.live, c.spare), (2, 1));
+    Ok(())
+}
+
+#[test]
+/// test that LinkedList::new() can be used in a static
+fn linked_list_const_new_01() -> Result<(), MapError> {
+    use std::sync::Mutex;
+    static LIST: Mutex<LinkedList<i8, i8>> = Mutex::new(LinkedList::new());
+    let mut ll = LIST.lock().unwrap();
+    assert!(ll.is_empty());
+    assert_eq!(ll.capacity_info().nodes, 0);
+    let _ = ll.ordered_insert(2, 0)?;
+    let _ = ll.ordered_insert(1, 1)?;
+    assert_eq!(
+        ll.iter().map(|(x, _)| *x).collect::<Vec<_>>(),
+        vec![1_i8, 2]
+    );
+    Ok(())
+}
+
+#[test]
+/// test PIterator::into_iter_cloned() and PIterator::iter_from_here()
+fn linked_list_pointer_test04() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i8, i8>::default()));
+    let p = PIterator::new(Rc::clone(&ll))?;
+    assert_eq!(p.iter_from_here().count(), 0);
+    for i in 0..4 {
+        let _ = ll.borrow_mut().ordered_insert(i, -i)?;
+    }
+    let mut p = PIterator::new(Rc::clone(&ll))?;
+    p.next()?;
+    assert_eq!(
+        p.iter_from_here().map(|(k, _)| k).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(p.get_k()?, 1);
+    assert_eq!(p.into_iter_cloned().map(|(_, v)| v).sum::<i8>(), -6);
+    Ok(())
+}
+
+#[test]
+/// test Debug for PIterator
+fn linked_list_pointer_test05() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i8, i8>::default()));
+    let _ = ll.borrow_mut().ordered_insert(1, 0)?; // 0
+    let mut p = PIterator::new(Rc::clone(&ll))?;
+    assert_eq!(format!("{:?}", p), "PIterator(0, head, tail, key:1)");
+    let _ = ll.borrow_mut().ordered_insert(2, 0)?; // 1
+    let _ = ll.borrow_mut().ordered_insert(3, 0)?; // 2
+    p.next()?;
+    assert_eq!(format!("{:?}", p), "PIterator(1, key:2)");
+    p.next()?;
+    assert_eq!(format!("{:?}", p), "PIterator(2, tail, key:3)");
+    {
+        let _borrow = ll.borrow_mut();
+        assert_eq!(
+            format!("{:?}", p),
+            "PIterator(2, <list already mutably borrowed>)"
+        );
+    }
+    p.next()?;
+    assert_eq!(format!("{:?}", p), "PIterator(OUT_OF_BOUNDS)");
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::for_each_mut() and LinkedList::try_for_each()
+fn linked_list_for_each_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    ll.for_each_mut(|_, _| panic!("list is empty"));
+    for i in (0..5).rev() {
+        let _ = ll.ordered_insert(i, 0)?;
+    }
+    let mut n = 0;
+    ll.for_each_mut(|k, v| {
+        *v = *k * 10 + n;
+        n += 1;
+    });
+    assert_eq!(
+        ll.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        vec![0_i8, 11, 22, 33, 44]
+    );
+    let rv = ll.try_for_each(|k, v| {
+        if *k == 3 {
+            return Err(MapErrorKind::InternalError.into());
+        }
+        *v = 0;
+        Ok(())
+    });
+    assert!(rv.is_err());
+    assert_eq!(
+        ll.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        vec![0_i8, 0, 0, 33, 44]
+    );
+    ll.try_for_each(|_, v| {
+        *v = 1;
+        Ok(())
+    })?;
+    assert_eq!(ll.iter().map(|(_, v)| *v).sum::<i8>(), 5);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::get_pair_mut()
+fn linked_list_get_pair_mut_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<So, i8>::default();
+    let _ = ll.ordered_insert(So::new(1, 1), 0)?; // 0
+    let _ = ll.ordered_insert(So::new(2, 2), 0)?; // 1
+    {
+        let (k, v) = ll.get_pair_mut(1)?;
+        *v = (k.key + k.value) as i8;
+    }
+    assert_eq!(ll.get_v(1)?, &4);
+    assert!(ll.get_pair_mut(2).is_err());
+    assert!(ll.get_pair_mut(crate::OUT_OF_BOUNDS).is_err());
+    let _ = ll.remove_(0)?;
+    assert!(ll.get_pair_mut(0).is_err());
+    *ll.get_v_mut(1)? += 1;
+    assert_eq!(ll.get_v(1)?, &5);
+    assert!(ll.get_v_mut(0).is_err());
+    assert!(ll.get_v_mut(crate::OUT_OF_BOUNDS).is_err());
+    ll.update_value(1, 7)?;
+    assert_eq!(ll.get_v(1)?, &7);
+    assert!(ll.update_value(0, 7).is_err());
+    assert_eq!(ll.replace_value(1, 8)?, 7);
+    assert_eq!(ll.get_v(1)?, &8);
+    assert!(ll.replace_value(0, 8).is_err());
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::apply()
+fn linked_list_apply_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, Vec<i8>>::default();
+    let a = ll.ordered_insert(1, vec![])?;
+    let b = ll.ordered_insert(2, vec![])?;
+    for _ in 0..3 {
+        ll.apply(a, |k, v| v.push(*k))?;
+    }
+    let len = ll.apply(b, |k, v| {
+        v.push(*k);
+        v.len()
+    })?;
+    assert_eq!(len, 1);
+    assert_eq!(ll.get_v(a)?, &vec![1, 1, 1]);
+    let _ = ll.pop_back()?;
+    assert!(ll.apply(b, |_, v| v.len()).is_err());
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::optimize()
+fn linked_list_optimize_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    assert!(ll.optimize()?.is_empty());
+    for i in [5_i8, 1, 4, 2, 3, 0].iter() {
+        let _ = ll.ordered_insert(*i, *i)?;
+    }
+    let _ = ll.remove_(2)?; // key 4
+    let _ = ll.remove_(5)?; // key 0
+    let old_index_of_3 = 4;
+    let remap = ll.optimize()?;
+    assert_eq!(ll.get_k(remap[old_index_of_3])?, &3);
+    assert_eq!(ll.slot_count(), 4);
+    assert!(ll.free_slots().is_empty());
+    assert_eq!(ll.live_slot_indices().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    assert_eq!(
+        (0..4).map(|i| *ll.get_k(i).unwrap()).collect::<Vec<_>>(),
+        vec![1_i8, 2, 3, 5]
+    );
+    assert_eq!(ll.get_prev_k(ll.tail())?, &3);
+    let _ = ll.ordered_insert(4, 4)?;
+    let _ = ll.ordered_insert(0, 0)?;
+    assert_eq!(
+        ll.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![0_i8, 1, 2, 3, 4, 5]
+    );
+    Ok(())
+}
+
+#[test]
+/// test MapError::kind() and conversion from MapErrorKind
+fn linked_list_error_kind_01() -> Result<(), MapError> {
+    let ll = LinkedList::<i8, i8>::default();
+    let err = ll.get(0).unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::InternalError);
+    let err = LinkedList::<i8, i8>::from_text("x").unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::ParseError);
+    let err: MapError = MapErrorKind::DecodeError.into();
+    assert_eq!(err.kind(), MapErrorKind::DecodeError);
+    let ll = Rc::from(RefCell::from(LinkedList::<i8, i8>::default()));
+    let _borrow = ll.borrow_mut();
+    let err = PIterator::new(Rc::clone(&ll)).unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::BorrowError);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::set_scan_warning()
+fn linked_list_scan_warning_01() -> Result<(), MapError> {
+    use super::ScanOperation;
+    use std::sync::{Arc, Mutex};
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let r = Arc::clone(&reports);
+    let mut ll = LinkedList::<i8, i8>::default();
+    ll.set_scan_warning(3, move |report| {
+        r.lock().unwrap().push((
+            report.operation,
+            format!("{:?}", report.key),
+            report.hint,
+            report.hops,
+        ))
+    });
+    for i in 0..6 {
+        let _ = ll.ordered_insert_pos(i, i, ll.tail())?;
+    }
+    assert!(reports.lock().unwrap().is_empty());
+    let _ = ll.ordered_insert_pos(4, 0, 0)?; // NOP, but a long one
+    let _ = ll.lower_bound(&0)?;
+    let _ = ll.lower_bound(&5)?;
+    ll.clear_scan_warning();
+    let _ = ll.lower_bound(&0)?;
+    assert_eq!(
+        *reports.lock().unwrap(),
+        vec![
+            (ScanOperation::Insert, "4".to_string(), 0, 5),
+            (ScanOperation::LowerBound, "0".to_string(), 5, 6)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::push_back_ordered() and LinkedList::push_front_ordered()
+fn linked_list_push_ordered_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    let _ = ll.push_back_ordered(3, 0)?;
+    let _ = ll.push_back_ordered(4, 1)?;
+    let _ = ll.push_front_ordered(2, 2)?;
+    let _ = ll.push_front_ordered(1, 3)?;
+    let err = ll.push_back_ordered(4, 4).unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::OrderViolation);
+    let err = ll.push_front_ordered(1, 4).unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::OrderViolation);
+    assert!(ll.push_front_ordered(5, 4).is_err());
+    assert!(ll.push_back_ordered(0, 4).is_err());
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        vec![(1_i8, 3_i8), (2, 2), (3, 0), (4, 1)]
+    );
+    assert_eq!(ll.lower_bound(&3)?, Some(0));
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::get_opt(), get_k_opt() and get_v_opt()
+fn linked_list_get_opt_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    assert_eq!(ll.get_opt(0), None);
+    let _ = ll.ordered_insert(1, 10)?; // 0
+    let _ = ll.ordered_insert(2, 20)?; // 1
+    assert_eq!(ll.get_opt(1), Some((&2, &20)));
+    assert_eq!(ll.get_k_opt(0), Some(&1));
+    assert_eq!(ll.get_v_opt(0), Some(&10));
+    let _ = ll.remove_(0)?;
+    assert_eq!(ll.get_opt(0), None);
+    assert_eq!(ll.get_k_opt(0), None);
+    assert_eq!(ll.get_v_opt(2), None);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::lower_bound_pos() and PIterator::seek_lower_bound()
+fn linked_list_pointer_test06() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i8, i8>::default()));
+    let mut p = PIterator::new(Rc::clone(&ll))?;
+    p.seek_lower_bound(&3)?;
+    assert!(!p.is_ok()?);
+    for i in 0..10 {
+        let _ = ll.borrow_mut().ordered_insert(i * 2, i)?;
+    }
+    for key in -1..21 {
+        let expected = ll.borrow().lower_bound(&key)?;
+        for position in 0..11 {
+            assert_eq!(ll.borrow().lower_bound_pos(&key, position)?, expected);
+        }
+    }
+    p.move_to_head()?;
+    p.seek_lower_bound(&5)?;
+    assert_eq!(p.get_k()?, 6);
+    p.seek_lower_bound(&6)?;
+    assert_eq!(p.get_k()?, 6);
+    p.seek_lower_bound(&-5)?;
+    assert!(p.is_at_head()?);
+    p.seek_lower_bound(&17)?;
+    assert_eq!(p.get_k()?, 18);
+    p.seek_lower_bound(&19)?;
+    assert!(!p.is_ok()?);
+    p.seek_lower_bound(&11)?;
+    assert_eq!(p.get_k()?, 12);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::first_mut() and LinkedList::last_mut()
+fn linked_list_first_last_mut_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    assert!(ll.first_mut().is_none());
+    assert!(ll.last_mut().is_none());
+    let _ = ll.ordered_insert(5, 0)?;
+    if let Some((k, v)) = ll.first_mut() {
+        *v += *k;
+    }
+    if let Some((k, v)) = ll.last_mut() {
+        *v += *k;
+    }
+    assert_eq!(ll.get_v(0)?, &10);
+    let _ = ll.ordered_insert(1, 0)?;
+    let _ = ll.ordered_insert(9, 0)?;
+    *ll.first_mut().unwrap().1 = 1;
+    *ll.last_mut().unwrap().1 = 9;
+    assert_eq!(
+        ll.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        vec![1_i8, 10, 9]
+    );
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::get_or_default()
+fn linked_list_get_or_default_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, Vec<i8>>::default();
+    for i in [3_i8, 1, 3, 2, 1, 3].iter() {
+        ll.get_or_default(*i, ll.head())?.push(*i * 10);
+    }
+    assert_eq!(ll.len(), 3);
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, v.len())).collect::<Vec<_>>(),
+        vec![(1_i8, 2_usize), (2, 1), (3, 3)]
+    );
+    // an existing value is not replaced
+    assert_eq!(ll.get_or_default(2, usize::MAX)?, &mut vec![20_i8]);
+    assert_eq!(ll.len(), 3);
+    Ok(())
+}
+
+#[test]
+/// test Tracer frames and JSON export
+fn linked_list_tracer_01() -> Result<(), MapError> {
+    let mut t = Tracer::new(LinkedList::<String, i8>::default());
+    let a = t.ordered_insert("a".to_string(), 1)?;
+    // a NOP insert is not recorded
+    let _ = t.ordered_insert("a".to_string(), 2)?;
+    let _ = t.ordered_insert("c".to_string(), 3)?;
+    t.replace_key(a, "b\"\n".to_string())?;
+    assert!(t.pop_back()?.is_some());
+    assert_eq!(t.frames().len(), 4);
+    assert_eq!(
+        t.frames()[2].op,
+        TraceOp::ReplaceKey {
+            index: a,
+            old_key: "a".to_string(),
+            key: "b\"\n".to_string()
+        }
+    );
+    assert_eq!(t.frames()[1].order.len(), 2);
+    assert_eq!(
+        t.to_json(),
+        concat!(
+            r#"{"frames":["#,
+            r#"{"op":"insert","index":0,"key":"a","order":[[0,"a"]]},"#,
+            r#"{"op":"insert","index":1,"key":"c","order":[[0,"a"],[1,"c"]]},"#,
+            r#"{"op":"replace_key","index":0,"old_key":"a","key":"b\"\n","order":[[0,"b\"\n"],[1,"c"]]},"#,
+            r#"{"op":"remove","index":1,"key":"c","order":[[0,"b\"\n"]]}"#,
+            r#"]}"#
+        )
+    );
+    t.clear_frames();
+    assert_eq!(t.to_json(), r#"{"frames":[]}"#);
+    assert_eq!(t.into_inner().len(), 1);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::view()
+fn linked_list_view_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i8, i8>::default();
+    for i in [8_i8, 2, 6, 4, 0].iter() {
+        let _ = ll.ordered_insert(*i, -*i)?;
+    }
+    let keys = |v: &super::View<'_, i8, i8>| v.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    assert_eq!(keys(&ll.view(..)), vec![0_i8, 2, 4, 6, 8]);
+    assert_eq!(keys(&ll.view(3..=6)), vec![4_i8, 6]);
+    assert_eq!(keys(&ll.view(..4)), vec![0_i8, 2]);
+    assert_eq!(
+        keys(&ll.view((Bound::Excluded(4), Bound::Unbounded))),
+        vec![6_i8, 8]
+    );
+    let empty = ll.view(5..6);
+    assert!(empty.is_empty());
+    assert_eq!(empty.len(), 0);
+    assert!(empty.lower_bound(&0).is_none());
+
+    let view = ll.view(2..8);
+    assert_eq!(view.len(), 3);
+    assert_eq!(view.get_k(view.head()), Some(&2));
+    assert_eq!(view.get(view.tail()), Some((&6, &-6)));
+    assert_eq!(view.lower_bound(&3).and_then(|i| view.get_k(i)), Some(&4));
+    assert_eq!(view.lower_bound(&-1), Some(view.head()));
+    assert!(view.lower_bound(&7).is_none());
+    assert!(view.find(&8).is_none());
+    assert!(view.find(&3).is_none());
+    let eight = ll.lower_bound(&8)?.unwrap();
+    assert!(!view.contains_index(eight));
+    assert!(view.get(eight).is_none());
+    assert!(!view.contains_index(ll.head()));
+    assert!(!view.contains_index(crate::OUT_OF_BOUNDS));
+    assert!(!empty.contains_index(ll.head()));
+    let four = ll.find(&4).unwrap();
+    assert!(view.contains_index(four));
+    assert_eq!(view.get(four), Some((&4, &-4)));
+    assert_eq!(view.list().len(), 5);
+    Ok(())
+}
+
+#[test]
+/// test Shadowed mirroring and verification
+fn linked_list_shadowed_01() -> Result<(), MapError> {
+    let mut s = Shadowed::new(LinkedList::<i32, i32>::default());
+    for i in [5, 1, 9, 3, 7, 3, 5].iter() {
+        let _ = s.ordered_insert_pos(*i, *i * 10, s.list().tail())?;
+    }
+    assert_eq!(s.shadow().len(), 5);
+    assert_eq!(
+        s.lower_bound(&4)?.map(|i| s.list().get_k_opt(i)),
+        Some(Some(&5))
+    );
+    assert_eq!(
+        s.lower_bound_pos(&8, s.list().head())?
+            .map(|i| s.list().get_k_opt(i)),
+        Some(Some(&9))
+    );
+    assert!(s.lower_bound(&10)?.is_none());
+    let three = s.list().lower_bound(&3)?.unwrap();
+    s.replace_key(three, 4)?;
+    assert_eq!(s.remove(three)?, (4, 30));
+    assert_eq!(s.pop_front()?, Some((1, 10)));
+    assert_eq!(s.pop_back()?, Some((9, 90)));
+    assert_eq!(
+        s.shadow().iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        vec![(5, 50), (7, 70)]
+    );
+    assert_eq!(s.into_inner().len(), 2);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "first difference at position 0")]
+/// test that Shadowed panics when the list gets out of order
+fn linked_list_shadowed_02() {
+    let mut s = Shadowed::new(LinkedList::<i32, ()>::default());
+    let _ = s.ordered_insert(2, ()).unwrap();
+    let one = s.ordered_insert(1, ()).unwrap();
+    s.replace_key(one, 3).unwrap();
+}
+
+#[test]
+/// test LinkedList::interpolation_hint() and the interpolated insert/lower_bound
+fn linked_list_interpolation_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<TotalF64, usize>::default();
+    assert_eq!(ll.interpolation_hint(&TotalF64(1.0)), usize::MAX);
+    // insert in a scrambled order
+    for i in 0..500_usize {
+        let x = ((i * 7919) % 500) as f64 * 0.5;
+        let _ = ll.ordered_insert_interpolated(TotalF64(x), i)?;
+    }
+    assert_eq!(ll.len(), 500);
+    let keys: Vec<f64> = ll.iter().map(|(k, _)| k.get()).collect();
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(ll.interpolation_hint(&TotalF64(-1.0)), ll.head());
+    assert_eq!(ll.interpolation_hint(&TotalF64(1000.0)), ll.tail());
+    let hint = ll.interpolation_hint(&TotalF64(100.2));
+    assert!((ll.get_k(hint)?.get() - 100.2).abs() < 20.0);
+    let lb = ll.lower_bound_interpolated(&TotalF64(100.2))?.unwrap();
+    assert_eq!(ll.get_k(lb)?.get(), 100.5);
+    assert!(ll.lower_bound_interpolated(&TotalF64(250.0))?.is_none());
+    Ok(())
+}
+
+#[test]
+/// test SearchStrategy::Galloping against the linear search
+fn linked_list_galloping_01() -> Result<(), MapError> {
+    let mut linear = LinkedList::<i32, i32>::default();
+    let mut galloping = LinkedList::<i32, i32>::default();
+    galloping.set_search_strategy(SearchStrategy::Galloping);
+    assert_eq!(galloping.search_strategy(), SearchStrategy::Galloping);
+    let mut hint = 0_usize;
+    for i in 0..300_i32 {
+        let key = (i * 37) % 101 * 3;
+        let a = linear.ordered_insert_pos(key, i, hint)?;
+        let b = galloping.ordered_insert_pos(key, i, hint)?;
+        assert_eq!(a, b);
+        hint = (hint * 7 + 3) % (linear.slot_count() + 1);
+    }
+    assert_eq!(
+        linear.iter().collect::<Vec<_>>(),
+        galloping.iter().collect::<Vec<_>>()
+    );
+    for position in galloping.live_slot_indices().collect::<Vec<_>>() {
+        for key in -1..305 {
+            assert_eq!(
+                linear.lower_bound_pos(&key, position)?,
+                galloping.lower_bound_pos(&key, position)?
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+/// test the jump table against a list without one
+fn linked_list_jump_table_01() -> Result<(), MapError> {
+    let mut plain = LinkedList::<i32, i32>::default();
+    let mut jump = LinkedList::<i32, i32>::default();
+    assert!(!jump.has_jump_table());
+    jump.set_jump_table(true);
+    assert!(jump.has_jump_table());
+    assert!(jump.lower_bound(&1)?.is_none());
+    for i in 0..2000_i32 {
+        let key = (i * 7919) % 1009;
+        assert_eq!(plain.ordered_insert(key, i)?, jump.ordered_insert(key, i)?);
+        if i % 3 == 0 {
+            // remove some items, including the ones in the jump table
+            let index = plain.lower_bound(&(key / 2))?.unwrap();
+            assert_eq!(jump.lower_bound(&(key / 2))?, Some(index));
+            assert_eq!(plain.remove_(index)?, jump.remove_(index)?);
+        }
+        for probe in [-1, key - 1, key, key + 1, 1010].iter() {
+            assert_eq!(plain.lower_bound(probe)?, jump.lower_bound(probe)?);
+        }
+    }
+    assert_eq!(
+        plain.iter().collect::<Vec<_>>(),
+        jump.iter().collect::<Vec<_>>()
+    );
+    while !plain.is_empty() {
+        assert_eq!(plain.pop_back()?, jump.pop_back()?);
+        assert_eq!(plain.lower_bound(&500)?, jump.lower_bound(&500)?);
+    }
+    let _ = jump.optimize()?;
+    assert!(jump.has_jump_table());
+    jump.set_jump_table(false);
+    assert!(!jump.has_jump_table());
+    Ok(())
+}
+
+#[test]
+/// test lower_bound() with borrowed keys
+fn linked_list_lower_bound_borrow_01() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<String, i8>::default()));
+    for (i, name) in ["delta", "alpha", "charlie", "bravo"].iter().enumerate() {
+        let _ = ll.borrow_mut().ordered_insert(name.to_string(), i as i8)?;
+    }
+    let lb = ll.borrow().lower_bound("b")?.unwrap();
+    assert_eq!(ll.borrow().get_k(lb)?, "bravo");
+    let lb = ll.borrow().lower_bound_pos("c", lb)?.unwrap();
+    assert_eq!(ll.borrow().get_k(lb)?, "charlie");
+    assert!(ll.borrow().lower_bound("e")?.is_none());
+    let mut p = PIterator::lower_bound(Rc::clone(&ll), "alpha")?;
+    assert_eq!(p.get_v()?, 1);
+    p.seek_lower_bound("d")?;
+    assert_eq!(p.get_k()?, "delta");
+    assert_eq!(ll.borrow().view("b".to_string()..).find("delta"), Some(0));
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::extend_with_hints()
+fn linked_list_extend_with_hints_01() -> Result<(), MapError> {
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+    let long_scans = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&long_scans);
+    let mut ll = LinkedList::<i32, i32>::default();
+    ll.set_scan_warning(2, move |_| {
+        let _ = counter.fetch_add(1, AtomicOrdering::Relaxed);
+    });
+    // no hints at all, the previous insert is used as hint
+    let rv = ll.extend_with_hints((0..100).map(|i| (i, -i, usize::MAX)));
+    assert_eq!(rv.len(), 100);
+    assert_eq!(long_scans.load(AtomicOrdering::Relaxed), 0);
+    let head = ll.head();
+    let rv = ll.extend_with_hints(vec![(-1, 1, head), (50, 0, 12345), (-2, 2, 17)]);
+    assert_eq!(rv[1].as_ref().ok(), Some(&50)); // NOP, returns the existing index
+    assert_eq!(ll.len(), 102);
+    assert_eq!(ll.get_k(ll.head())?, &-2);
+    assert!(ll.iter().map(|(k, _)| *k).eq(-2..100));
+    Ok(())
+}
+
+#[test]
+/// test ListPool
+fn linked_list_pool_01() -> Result<(), MapError> {
+    let mut pool = ListPool::<i8, i8>::new(1);
+    assert!(pool.is_empty());
+    assert_eq!(pool.max_pooled(), 1);
+    let mut ll = pool.take();
+    ll.set_search_strategy(SearchStrategy::Galloping);
+    ll.set_jump_table(true);
+    for i in 0..10 {
+        let _ = ll.ordered_insert(i, i)?;
+    }
+    let nodes = ll.capacity_info().nodes;
+    pool.give(ll);
+    pool.give(LinkedList::default()); // pool is full, dropped
+    assert_eq!(pool.len(), 1);
+    let ll = pool.take();
+    assert!(ll.is_empty());
+    assert_eq!(ll.capacity_info().nodes, nodes);
+    assert_eq!(ll.search_strategy(), SearchStrategy::Linear);
+    assert!(!ll.has_jump_table());
+    assert!(pool.is_empty());
+
+    // a shared list is only taken back if no pointer refers to it
+    let shared = pool.take_shared();
+    let _ = shared.borrow_mut().ordered_insert(1, 1)?;
+    let p = PIterator::new(Rc::clone(&shared))?;
+    let shared = pool.give_shared(shared).unwrap_err();
+    assert_eq!(shared.borrow().len(), 1);
+    drop(p);
+    assert!(pool.give_shared(shared).is_ok());
+    assert_eq!(pool.len(), 1);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::with_max_capacity()
+fn linked_list_max_capacity_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::with_max_capacity(4);
+    assert_eq!(ll.max_capacity(), Some(4));
+    assert_eq!(LinkedList::<i32, i32>::default().max_capacity(), None);
+    let capacity = ll.capacity_info().nodes;
+    for i in 0..4 {
+        let _ = ll.ordered_insert(i * 2, i)?;
+    }
+    // a NOP insert doesn't need room
+    assert!(ll.ordered_insert(2, 0).is_ok());
+    for rv in [
+        ll.ordered_insert(5, 5),
+        ll.push_back_ordered(10, 10),
+        ll.push_front_ordered(-1, -1),
+    ]
+    .iter()
+    {
+        assert_eq!(
+            rv.as_ref().unwrap_err().kind(),
+            MapErrorKind::CapacityExceeded
+        );
+    }
+    #[cfg(feature = "rich_error")]
+    assert!(matches!(
+        ll.ordered_insert(5, 5),
+        Err(MapError::CapacityExceeded(_))
+    ));
+    assert_eq!(ll.len(), 4);
+    let _ = ll.pop_back()?;
+    let _ = ll.ordered_insert(5, 5)?;
+    assert_eq!(ll.capacity_info().nodes, capacity);
+    assert_eq!(
+        ll.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![0, 2, 4, 5]
+    );
+    Ok(())
+}
+
+#[test]
+/// test per node metadata through the list accessors, PIterator and optimize()
+fn linked_list_meta_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32, String>::default();
+    let one = ll.ordered_insert(1, 1)?;
+    let three = ll.ordered_insert(3, 3)?;
+    assert_eq!(ll.set_meta(three, "three".to_string())?, "");
+    let two = ll.ordered_insert_pos(2, 2, one)?;
+    assert_eq!(ll.get_meta(one).map(|m| m.as_str()), Some(""));
+    assert_eq!(ll.get_meta(two).map(|m| m.as_str()), Some(""));
+    assert_eq!(ll.get_meta(three).map(|m| m.as_str()), Some("three"));
+    ll.get_meta_mut(three).unwrap().push('!');
+    assert_eq!(ll.get_meta(three).map(|m| m.as_str()), Some("three!"));
+    assert!(ll.set_meta(crate::OUT_OF_BOUNDS, String::new()).is_err());
+    assert!(ll.take_meta(100).is_err());
+
+    // metadata is dropped with the node, a reused slot starts out with the default metadata
+    let _ = ll.set_meta(one, "one".to_string())?;
+    let _ = ll.pop_front()?;
+    assert_eq!(ll.get_meta(one), None);
+    assert_eq!(ll.ordered_insert(0, 0)?, one);
+    assert_eq!(ll.get_meta(one).map(|m| m.as_str()), Some(""));
+
+    let remap = ll.optimize()?;
+    assert_eq!(
+        ll.get_meta(remap[three]).map(|m| m.as_str()),
+        Some("three!")
+    );
+
+    let ll = Rc::from(RefCell::from(ll));
+    let mut p = PIterator::lower_bound(Rc::clone(&ll), &3)?;
+    assert_eq!(p.get_meta()?, "three!");
+    assert_eq!(p.set_meta("3".to_string())?, "three!");
+    p.prev()?;
+    assert_eq!(p.get_meta()?, "");
+    let _ = p.set_meta("2".to_string())?;
+    assert_eq!(
+        ll.borrow()
+            .live_slot_indices()
+            .filter_map(|i| ll.borrow().get_meta(i).cloned())
+            .collect::<Vec<_>>(),
+        vec!["".to_string(), "2".to_string(), "3".to_string()]
+    );
+    Ok(())
+}
+
+#[test]
+/// test aggregate_range() against a brute force fold, with a non commutative monoid
+fn linked_list_aggregated_01() -> Result<(), MapError> {
+    struct Concat;
+    impl Monoid<i32, i32> for Concat {
+        type Output = Vec<i32>;
+        fn identity(&self) -> Vec<i32> {
+            Vec::new()
+        }
+        fn lift(&self, key: &i32, value: &i32) -> Vec<i32> {
+            vec![*key + *value]
+        }
+        fn combine(&self, a: &Vec<i32>, b: &Vec<i32>) -> Vec<i32> {
+            a.iter().chain(b.iter()).copied().collect()
+        }
+    }
+    let mut seed = 12345_u32;
+    let mut rnd = move |n: i32| {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        ((seed >> 8) % n as u32) as i32
+    };
+    let mut plain = LinkedList::<i32, i32>::default();
+    for i in (0..300).step_by(3) {
+        let _ = plain.ordered_insert_pos(i, 0, plain.tail())?;
+    }
+    let mut a = Aggregated::new(plain, Concat);
+    let mut count = Aggregated::new(LinkedList::<i32, i32>::default(), Count);
+    for step in 0..2000 {
+        let key = rnd(400);
+        if rnd(3) == 0 && !a.list().is_empty() {
+            if let Some(index) = a.list().lower_bound(&key)? {
+                let _ = a.remove(index)?;
+            }
+        } else {
+            let _ = a.ordered_insert(key, step)?;
+            let _ = count.ordered_insert(key, step)?;
+        }
+        if rnd(5) == 0 {
+            if let Some(index) = a.list().lower_bound(&key)? {
+                a.apply(index, |_, v| *v += 1)?;
+            }
+        }
+        let (lo, hi) = (rnd(400), rnd(400));
+        let expected = a
+            .list()
+            .iter()
+            .filter(|(k, _)| **k >= lo && **k < hi)
+            .map(|(k, v)| *k + *v)
+            .collect::<Vec<_>>();
+        assert_eq!(a.aggregate_range(lo..hi), expected);
+        let expected = a
+            .list()
+            .iter()
+            .filter(|(k, _)| **k > lo && **k <= hi)
+            .count();
+        assert_eq!(
+            a.aggregate_range((Bound::Excluded(lo), Bound::Included(hi)))
+                .len(),
+            expected
+        );
+    }
+    assert_eq!(a.aggregate_all().len(), a.list().len());
+    assert_eq!(count.aggregate_all(), count.list().len());
+    assert_eq!(
+        count.aggregate_range(..200),
+        count.list().iter().filter(|(k, _)| **k < 200).count()
+    );
+    while a.pop_front()?.is_some() {}
+    assert!(a.aggregate_all().is_empty());
+    let _ = a.ordered_insert(7, 1)?;
+    assert_eq!(a.aggregate_range(..), vec![8]);
+    Ok(())
+}
+
+#[test]
+/// test deferred slot reuse: PIterators at removed items can still move, slots are not reused
+/// until purged
+fn linked_list_deferred_slot_reuse_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    assert!(!ll.has_deferred_slot_reuse());
+    ll.set_deferred_slot_reuse(true)?;
+    for i in 0..6 {
+        let _ = ll.ordered_insert_pos(i, i * 10, ll.tail())?;
+    }
+    let ll = Rc::from(RefCell::from(ll));
+    let mut p2 = PIterator::lower_bound(Rc::clone(&ll), &2)?;
+    let mut p3 = PIterator::lower_bound(Rc::clone(&ll), &3)?;
+    let mut p4 = PIterator::lower_bound(Rc::clone(&ll), &4)?;
+    // remove 2, 3 and 4 with other pointers
+    for key in [2, 3, 4].iter() {
+        let _ = PIterator::lower_bound(Rc::clone(&ll), key)?.remove_current()?;
+    }
+    // the slots are not reused
+    let _ = ll.borrow_mut().ordered_insert(10, 100)?;
+    let _ = ll.borrow_mut().ordered_insert(-1, -10)?;
+    {
+        let list = ll.borrow();
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.tombstone_count(), 3);
+        assert!(list.is_tombstone(p3.current()));
+        assert!(list.free_slots().is_empty());
+        assert_eq!(
+            list.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![-1, 0, 1, 5, 10]
+        );
+        assert_eq!(
+            list.lower_bound(&2)?.map(|i| *list.get_k(i).unwrap()),
+            Some(5)
+        );
+    }
+    assert!(!p3.is_ok()?);
+    p2.prev()?;
+    assert_eq!(p2.get_k()?, 1);
+    p3.next()?;
+    assert_eq!(p3.get_k()?, 5);
+    p4.prev()?;
+    assert_eq!(p4.get_k()?, 1);
+
+    // undo of a remove takes the tombstone back
+    let mut list = ll.borrow_mut();
+    let rv: Result<(), MapError> = list.transaction(|editor| {
+        let _ = editor.remove(editor.list().head())?;
+        Err(MapErrorKind::InternalError.into())
+    });
+    assert!(rv.is_err());
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.tombstone_count(), 3);
+    assert_eq!(list.peek_front_k(), Some(&-1));
+
+    let bytes = list.to_bytes();
+    assert_eq!(list.purge_tombstones()?, 3);
+    assert_eq!(list.free_slots().len(), 3);
+    assert_eq!(list.len(), 5);
+    let mut decoded = LinkedList::<i32, i32>::from_bytes(&bytes)?;
+    assert!(decoded.has_deferred_slot_reuse());
+    assert_eq!((decoded.len(), decoded.tombstone_count()), (5, 3));
+    assert_eq!(decoded.purge_tombstones()?, 3);
+    assert_eq!(decoded.free_slots(), list.free_slots());
+    let _ = list.pop_back()?;
+    list.set_deferred_slot_reuse(false)?;
+    let _ = list.pop_back()?;
+    assert_eq!(list.tombstone_count(), 0);
+    assert_eq!(list.free_slots().len(), 5);
+    Ok(())
+}
+
+#[test]
+/// test the free slot reuse policies, and that undo restores the free index pool
+fn linked_list_slot_reuse_01() -> Result<(), MapError> {
+    for policy in [SlotReuse::Lifo, SlotReuse::Fifo, SlotReuse::LowestFirst].iter() {
+        let mut ll = LinkedList::<i32, i32>::default();
+        assert_eq!(ll.slot_reuse(), SlotReuse::Lifo);
+        ll.set_slot_reuse(*policy);
+        assert_eq!(ll.slot_reuse(), *policy);
+        for i in 0..6 {
+            let _ = ll.ordered_insert_pos(i, i, ll.tail())?;
+        }
+        // free the slots 3, 1 and 4, in that order
+        for key in [3, 1, 4].iter() {
+            let index = ll.lower_bound(key)?.unwrap();
+            ll.transaction(|editor| editor.remove(index).map(|_| ()))?;
+        }
+        let expected = match policy {
+            SlotReuse::Lifo => vec![4, 1, 3],
+            SlotReuse::Fifo => vec![3, 1, 4],
+            SlotReuse::LowestFirst => vec![1, 3, 4],
+        };
+        let pool = ll.free_slots().to_vec();
+        let rv: Result<(), MapError> = ll.transaction(|editor| {
+            let _ = editor.ordered_insert(10, 10)?;
+            let _ = editor.remove(editor.list().head())?;
+            Err(MapErrorKind::InternalError.into())
+        });
+        assert!(rv.is_err());
+        assert_eq!(ll.free_slots(), &pool[..]);
+        let reused = (0..3)
+            .map(|i| ll.ordered_insert(10 + i, 0))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(reused, expected);
+        assert!(ll.free_slots().is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+/// test lower_bound_many() against lower_bound()
+fn linked_list_lower_bound_many_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    assert_eq!(ll.lower_bound_many(&[1, 2])?, vec![None, None]);
+    for i in (0..100).rev() {
+        let _ = ll.ordered_insert((i * 7) % 101, i)?;
+    }
+    let probes = (-3..110).map(|i| i / 2).collect::<Vec<_>>();
+    let expected = probes
+        .iter()
+        .map(|k| ll.lower_bound(k))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(ll.lower_bound_many(probes.iter())?, expected);
+    assert!(ll.lower_bound_many(Vec::<&i32>::new())?.is_empty());
+    assert_eq!(
+        ll.lower_bound_many(&[5, 4]).unwrap_err().kind(),
+        MapErrorKind::OrderViolation
+    );
+    Ok(())
+}
+
+#[test]
+/// test that the key normalizer is applied by every insert, and by the *_normalized() lookups
+fn linked_list_key_normalizer_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<TotalF64, i32>::default();
+    // snap to a 0.5 grid
+    ll.set_key_normalizer(|k: TotalF64| TotalF64((k.get() * 2.0).round() / 2.0));
+    assert_eq!(ll.normalize_key(TotalF64(1.2)), TotalF64(1.0));
+    let one = ll.ordered_insert(TotalF64(1.1), 0)?;
+    assert_eq!(ll.ordered_insert_pos(TotalF64(0.9), 1, ll.tail())?, one);
+    let _ = ll.push_back_ordered(TotalF64(2.1), 2)?;
+    assert!(ll.push_back_ordered(TotalF64(1.9), 3).is_err());
+    let _ = ll.push_front_ordered(TotalF64(-0.2), 4)?;
+    *ll.get_or_default(TotalF64(1.4), one)? += 10;
+    let rv = ll.extend_with_hints(vec![(TotalF64(1.6), 5, one), (TotalF64(3.24), 6, one)]);
+    assert!(rv.iter().all(|r| r.is_ok()));
+    assert_eq!(
+        ll.iter().map(|(k, v)| (k.get(), *v)).collect::<Vec<_>>(),
+        vec![(0.0, 4), (1.0, 0), (1.5, 10), (2.0, 2), (3.0, 6)]
+    );
+    assert_eq!(ll.lower_bound_normalized(TotalF64(1.2))?, Some(one));
+    assert_eq!(ll.find_normalized(TotalF64(0.8)), Some(one));
+    assert_eq!(ll.find(&TotalF64(0.8)), None);
+    assert!(ll.contains_key_normalized(TotalF64(2.9)));
+    assert!(!ll.contains_key(&TotalF64(2.9)));
+    assert_eq!(ll.remove_by_key(&TotalF64(-0.1))?, None);
+    assert_eq!(
+        ll.remove_by_key_normalized(TotalF64(-0.1))?
+            .map(|(k, v)| (k.get(), v)),
+        Some((0.0, 4))
+    );
+    ll.clear_key_normalizer();
+    assert_ne!(ll.lower_bound_normalized(TotalF64(1.2))?, Some(one));
+    let _ = ll.ordered_insert(TotalF64(0.8), 7)?;
+    assert_eq!(ll.len(), 5);
+    Ok(())
+}
+
+#[test]
+/// test PIterator::insert_before_current() and insert_after_current()
+fn linked_list_pointer_insert_01() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i32, i32>::default()));
+    let mut p = PIterator::new(Rc::clone(&ll))?;
+    let ten = p.insert_after_current(10, 10)?;
+    p.move_to_head()?;
+    assert_eq!(p.current(), ten);
+    let _ = p.insert_after_current(20, 20)?;
+    let _ = p.insert_before_current(5, 5)?;
+    p.next()?;
+    let _ = p.insert_after_current(30, 30)?;
+    let _ = p.insert_before_current(15, 15)?;
+    assert_eq!(p.get_k()?, 20);
+    assert_eq!(
+        ll.borrow().iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![5, 10, 15, 20, 30]
+    );
+    assert_eq!(ll.borrow().peek_back_k(), Some(&30));
+    assert_eq!(ll.borrow().peek_front_k(), Some(&5));
+    let mut end = PIterator::new_2(Rc::clone(&ll), crate::OUT_OF_BOUNDS);
+    assert!(end.insert_before_current(40, 40).is_err());
+    assert!(end.insert_after_current(40, 40).is_err());
+    assert_eq!(ll.borrow().len(), 5);
+    Ok(())
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+/// test that an out of order PIterator::insert_after_current() is caught by the debug assertion
+fn linked_list_pointer_insert_02() {
+    let ll = Rc::from(RefCell::from(LinkedList::<i32, i32>::default()));
+    let _ = ll.borrow_mut().ordered_insert(1, 1);
+    let _ = ll.borrow_mut().ordered_insert(3, 3);
+    let mut p = PIterator::new(Rc::clone(&ll)).unwrap();
+    let _ = p.insert_after_current(4, 4);
+}
+
+#[test]
+/// test diff() against a brute force comparison
+fn linked_list_diff_01() -> Result<(), MapError> {
+    let mut seed = 777_u32;
+    let mut rnd = move |n: i32| {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        ((seed >> 8) % n as u32) as i32
+    };
+    let mut ll = LinkedList::<i32, i32>::default();
+    for _ in 0..50 {
+        let _ = ll.ordered_insert(rnd(100), rnd(3))?;
+    }
+    for _ in 0..20 {
+        let checkpoint = ll.clone();
+        for _ in 0..10 {
+            let key = rnd(100);
+            match rnd(3) {
+                0 => {
+                    let _ = ll.ordered_insert(key, rnd(3))?;
+                }
+                1 => {
+                    if let Some(index) = ll.lower_bound(&key)? {
+                        ll.transaction(|editor| editor.remove(index).map(|_| ()))?;
+                    }
+                }
+                _ => {
+                    if let Some(index) = ll.lower_bound(&key)? {
+                        ll.apply(index, |_, v| *v = (*v + 1) % 3)?;
+                    }
+                }
+            }
+        }
+        let mut expected = Vec::new();
+        for key in 0..100 {
+            let old = checkpoint.iter().find(|(k, _)| **k == key);
+            let new = ll.iter().find(|(k, _)| **k == key);
+            match (old, new) {
+                (Some((k, v)), None) => expected.push(Change::Removed(k, v)),
+                (None, Some((k, v))) => expected.push(Change::Inserted(k, v)),
+                (Some((_, old)), Some((key, new))) if old != new => {
+                    expected.push(Change::Changed { key, old, new })
+                }
+                _ => (),
+            }
+        }
+        assert_eq!(checkpoint.diff(&ll), expected);
+        assert_eq!(ll.diff(&ll).len(), 0);
+    }
+    Ok(())
+}
+
+#[test]
+/// test nearest() against a brute force search
+fn linked_list_nearest_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<TotalF64, usize>::default();
+    let distance = |a: &TotalF64, b: &TotalF64| (a.get() - b.get()).abs();
+    assert!(ll.nearest(&TotalF64(1.0), distance)?.is_none());
+    for i in 0..30_usize {
+        let _ = ll.ordered_insert(TotalF64(((i * 17) % 31) as f64 * 0.7), i)?;
+    }
+    for probe in (-10..250).map(|i| TotalF64(i as f64 * 0.1)) {
+        let nearest = ll.nearest(&probe, distance)?.unwrap();
+        let best = ll
+            .iter()
+            .map(|(k, _)| distance(&probe, k))
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(distance(&probe, ll.get_k(nearest)?), best);
+    }
+    Ok(())
+}
+
+#[test]
+/// test ordered_insert_merge() and ordered_insert_merge_pos() with both search strategies
+fn linked_list_insert_merge_01() -> Result<(), MapError> {
+    for strategy in [SearchStrategy::Linear, SearchStrategy::Galloping].iter() {
+        let mut ll = LinkedList::<i32, i32>::default();
+        ll.set_search_strategy(*strategy);
+        let add = |existing: &mut i32, new: i32| *existing += new;
+        for i in 0..40 {
+            let _ = ll.ordered_insert_merge(i % 10, 1, add)?;
+            let _ = ll.ordered_insert_merge_pos(i % 7, 100, ll.tail(), add)?;
+            let _ = ll.ordered_insert_merge_pos(i % 5, 10000, ll.head(), add)?;
+        }
+        let expected = (0..10)
+            .map(|k| {
+                let count = |m: i32| (0..40).filter(|i| i % m == k).count() as i32;
+                (k, count(10) + 100 * count(7) + 10000 * count(5))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            expected
+        );
+    }
+    Ok(())
+}
+
+#[test]
+/// test that a FreezeGuard blocks structural mutations through every path, but not reads
+fn linked_list_freeze_01() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i32, i32>::default()));
+    for i in 0..5 {
+        let _ = ll.borrow_mut().ordered_insert(i, i)?;
+    }
+    let mut p = PIterator::new(Rc::clone(&ll))?;
+    let guard_1 = FreezeGuard::new(&ll)?;
+    let guard_2 = p.freeze()?;
+    // a clone of a frozen list is not frozen
+    assert!(!ll.borrow().clone().is_frozen());
+    {
+        let mut list = ll.borrow_mut();
+        let head = list.head();
+        for rv in [
+            list.ordered_insert(10, 10),
+            list.ordered_insert_pos(10, 10, head),
+            list.push_back_ordered(10, 10),
+            list.push_front_ordered(-1, -1),
+            list.pop_front().map(|_| 0),
+            list.transaction(|editor| editor.remove(head).map(|_| 0)),
+            list.transaction(|editor| editor.replace_key(head, -1).map(|_| 0)),
+        ]
+        .iter()
+        {
+            assert_eq!(rv.as_ref().unwrap_err().kind(), MapErrorKind::Frozen);
+        }
+        // NOPs and value changes are allowed
+        assert_eq!(list.ordered_insert(2, 0)?, list.lower_bound(&2)?.unwrap());
+        list.apply(head, |_, v| *v = 100)?;
+    }
+    assert_eq!(p.replace_key(0).unwrap_err().kind(), MapErrorKind::Frozen);
+    assert_eq!(
+        p.insert_before_current(-1, -1).unwrap_err().kind(),
+        MapErrorKind::Frozen
+    );
+    p.next()?;
+    assert!(p.remove_current().is_err());
+    assert_eq!(p.get_k()?, 1);
+    drop(guard_1);
+    assert!(ll.borrow().is_frozen());
+    drop(guard_2);
+    assert!(!ll.borrow().is_frozen());
+    let _ = p.remove_current()?;
+    assert_eq!(
+        ll.borrow()
+            .iter()
+            .map(|(k, v)| (*k, *v))
+            .collect::<Vec<_>>(),
+        vec![(0, 100), (2, 2), (3, 3), (4, 4)]
+    );
+    Ok(())
+}
+
+#[test]
+/// test that a FreezeGuard blocks try_clear(), optimize() and purge_tombstones()
+fn linked_list_freeze_02() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i32, i32>::default()));
+    ll.borrow_mut().set_deferred_slot_reuse(true)?;
+    for i in (0..5).rev() {
+        let _ = ll.borrow_mut().ordered_insert(i, i)?;
+    }
+    let _ = ll.borrow_mut().pop_front()?;
+    let guard = FreezeGuard::new(&ll)?;
+    {
+        let mut list = ll.borrow_mut();
+        assert_eq!(list.try_clear().unwrap_err().kind(), MapErrorKind::Frozen);
+        assert_eq!(list.optimize().unwrap_err().kind(), MapErrorKind::Frozen);
+        assert_eq!(list.compact().unwrap_err().kind(), MapErrorKind::Frozen);
+        assert_eq!(
+            list.purge_tombstones().unwrap_err().kind(),
+            MapErrorKind::Frozen
+        );
+        // a frozen list keeps its tombstones, and the setting
+        assert_eq!(
+            list.set_deferred_slot_reuse(false).unwrap_err().kind(),
+            MapErrorKind::Frozen
+        );
+        assert!(list.has_deferred_slot_reuse());
+        assert_eq!((list.len(), list.tombstone_count()), (4, 1));
+        assert_eq!(list.head(), 3);
+    }
+    drop(guard);
+    let mut list = ll.borrow_mut();
+    assert_eq!(list.purge_tombstones()?, 1);
+    assert_eq!(list.optimize()?, vec![3, 2, 1, 0, crate::OUT_OF_BOUNDS]);
+    list.try_clear()?;
+    assert!(list.is_empty());
+    Ok(())
+}
+
+#[test]
+/// test that bookmarks follow their items through inserts, removes, optimize() and clear()
+fn linked_list_bookmark_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    assert!(ll.add_bookmark_at(0).is_err());
+    for i in 0..10 {
+        let _ = ll.ordered_insert(i, i)?;
+    }
+    let key_of =
+        |ll: &LinkedList<i32, i32>, b: Bookmark| ll.bookmark(b).map(|i| *ll.get_k(i).unwrap());
+    let third = ll.add_bookmark_at(2)?;
+    let before_five = ll.add_bookmark(ll.lower_bound(&4)?.unwrap())?;
+    let last = ll.add_bookmark(ll.tail())?;
+    assert_eq!(key_of(&ll, before_five), Some(4));
+    let _ = ll.ordered_insert(-5, 0)?;
+    assert_eq!(ll.bookmark_ordinal(third), Some(3));
+    // removing the bookmarked item moves the bookmark to the successor
+    ll.transaction(|editor| {
+        editor
+            .remove(editor.list().lower_bound(&4)?.unwrap())
+            .map(|_| ())
+    })?;
+    assert_eq!(key_of(&ll, before_five), Some(5));
+    // ... or to the predecessor at the tail
+    let _ = ll.pop_back()?;
+    assert_eq!(key_of(&ll, last), Some(8));
+    let remap = ll.optimize()?;
+    assert_eq!(
+        ll.bookmark(third),
+        Some(remap[remap.iter().position(|i| *i == 3).unwrap()])
+    );
+    assert_eq!(key_of(&ll, third), Some(2));
+    ll.move_bookmark(third, ll.head())?;
+    assert_eq!(key_of(&ll, third), Some(-5));
+    assert_eq!(ll.remove_bookmark(last), Some(ll.tail()));
+    assert_eq!(ll.bookmark(last), None);
+    assert!(ll.move_bookmark(last, ll.head()).is_err());
+    // a removed bookmark id is reused
+    let again = ll.add_bookmark(ll.tail())?;
+    assert_eq!(again, last);
+    ll.clear();
+    assert_eq!(ll.bookmark(third), None);
+    assert_eq!(ll.bookmark_ordinal(third), None);
+    Ok(())
+}
+
+#[test]
+/// test ordered_insert_hint() with PIterator hints from the same and from another list
+fn linked_list_insert_hint_01() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i32, i32>::default()));
+    let other = Rc::from(RefCell::from(LinkedList::<i32, i32>::default()));
+    let mut p = PIterator::new(Rc::clone(&ll))?;
+    // an out of bounds hint is allowed, same as for ordered_insert_pos()
+    let _ = ll.borrow_mut().ordered_insert_hint(5, 5, &p)?;
+    p.move_to_head()?;
+    for i in (0..5).chain(6..10) {
+        let index = ll.borrow_mut().ordered_insert_hint(i, i, &p)?;
+        p = PIterator::new_2(Rc::clone(&ll), index);
+    }
+    assert_eq!(
+        ll.borrow().iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        (0..10).collect::<Vec<_>>()
+    );
+    let q = PIterator::new(Rc::clone(&other))?;
+    assert_eq!(
+        ll.borrow_mut()
+            .ordered_insert_hint(10, 10, &q)
+            .unwrap_err()
+            .kind(),
+        MapErrorKind::HintMismatch
+    );
+    assert!(other.borrow_mut().ordered_insert_hint(10, 10, &p).is_err());
+    assert_eq!(ll.borrow().len(), 10);
+    assert!(other.borrow().is_empty());
+
+    // PIterators over the other kinds of shared lists
+    let cell = RefCell::new(LinkedList::<i32, i32>::default());
+    let p = PIterator::new(&cell)?;
+    let _ = cell.borrow_mut().ordered_insert_hint(1, 1, &p)?;
+    assert!(ll.borrow_mut().ordered_insert_hint(11, 11, &p).is_err());
+    let shared = std::sync::Arc::new(std::sync::Mutex::new(LinkedList::<i32, i32>::default()));
+    let p = PIterator::new(std::sync::Arc::clone(&shared))?;
+    let _ = shared.lock().unwrap().ordered_insert_hint(1, 1, &p)?;
+    assert_eq!(
+        cell.borrow_mut()
+            .ordered_insert_hint(2, 2, &p)
+            .unwrap_err()
+            .kind(),
+        MapErrorKind::HintMismatch
+    );
+    assert_eq!((cell.borrow().len(), shared.lock().unwrap().len()), (1, 1));
+    Ok(())
+}
+
+#[test]
+/// test fragmentation() on a churned list, and after optimize()
+fn linked_list_fragmentation_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let f = ll.fragmentation();
+    assert_eq!((f.live, f.free, f.longest_free_run), (0, 0, 0));
+    assert_eq!((f.live_ratio, f.average_link_distance), (1.0, 0.0));
+    for i in 0..10 {
+        let _ = ll.ordered_insert(i, i)?;
+    }
+    assert_eq!(ll.fragmentation().average_link_distance, 1.0);
+    // free the slots 2, 3, 4 and 7
+    for key in [2, 3, 4, 7].iter() {
+        let index = ll.lower_bound(key)?.unwrap();
+        ll.transaction(|editor| editor.remove(index).map(|_| ()))?;
+    }
+    // 0 1 5 6 8 9 -> distances 1 4 1 2 1
+    let f = ll.fragmentation();
+    assert_eq!((f.live, f.free, f.longest_free_run), (6, 4, 3));
+    assert_eq!(f.live_ratio, 0.6);
+    assert_eq!(f.average_link_distance, 9.0 / 5.0);
+    // the next insert reuses slot 7 (LIFO)
+    let _ = ll.ordered_insert(-1, 0)?;
+    // 7 0 1 5 6 8 9 -> distances 7 1 4 1 2 1
+    assert_eq!(ll.fragmentation().average_link_distance, 16.0 / 6.0);
+    let _ = ll.optimize()?;
+    let f = ll.fragmentation();
+    assert_eq!((f.live, f.free, f.longest_free_run), (7, 0, 0));
+    assert_eq!((f.live_ratio, f.average_link_distance), (1.0, 1.0));
+    Ok(())
+}
+
+#[test]
+/// test that from_iter_report_duplicates() hands back every rejected pair
+fn linked_list_report_duplicates_01() -> Result<(), MapError> {
+    let pairs = (0..100).map(|i| ((i * 37) % 41, i)).collect::<Vec<_>>();
+    let (ll, rejected) = LinkedList::<i32, i32>::from_iter_report_duplicates(pairs.clone())?;
+    assert_eq!(ll.len() + rejected.len(), pairs.len());
+    let mut seen = std::collections::BTreeMap::new();
+    let mut expected_rejected = Vec::new();
+    for (k, v) in pairs {
+        match seen.entry(k) {
+            std::collections::btree_map::Entry::Occupied(_) => expected_rejected.push((k, v)),
+            std::collections::btree_map::Entry::Vacant(e) => {
+                let _ = e.insert(v);
+            }
+        }
+    }
+    assert_eq!(rejected, expected_rejected);
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        seen.into_iter().collect::<Vec<_>>()
+    );
+    let (ll, rejected) = LinkedList::<i32, i32>::from_iter_report_duplicates(Vec::new())?;
+    assert!(ll.is_empty() && rejected.is_empty());
+    Ok(())
+}
+
+#[test]
+/// test PIterator over the borrowed and the thread safe list handles
+fn linked_list_shared_list_01() -> Result<(), MapError> {
+    let ll = std::cell::RefCell::new(LinkedList::<i32, i32>::default());
+    for i in 0..10 {
+        let _ = ll.borrow_mut().ordered_insert(i, i)?;
+    }
+    let mut p = PIterator::lower_bound(&ll, &5)?;
+    let _ = p.remove_current()?;
+    assert_eq!(p.get_k()?, 4);
+    assert_eq!(
+        p.iter_from_here().map(|(k, _)| k).collect::<Vec<_>>(),
+        vec![4, 6, 7, 8, 9]
+    );
+    {
+        let _guard = p.freeze()?;
+        assert_eq!(
+            ll.borrow_mut().ordered_insert(5, 5).unwrap_err().kind(),
+            MapErrorKind::Frozen
+        );
+    }
+    assert_eq!(ll.borrow().len(), 9);
+
+    let ll = std::sync::Arc::new(std::sync::Mutex::new(LinkedList::<i32, i32>::default()));
+    let handles = (0..4)
+        .map(|t| {
+            let p = PIterator::new(std::sync::Arc::clone(&ll)).unwrap();
+            std::thread::spawn(move || {
+                for i in 0..25 {
+                    let _ = p.list.lock().unwrap().ordered_insert(i * 4 + t, t).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for h in handles {
+        h.join().unwrap();
+    }
+    let p = PIterator::new(std::sync::Arc::clone(&ll))?;
+    assert_eq!(
+        p.into_iter_cloned().map(|(k, _)| k).collect::<Vec<_>>(),
+        (0..100).collect::<Vec<_>>()
+    );
+    // a held lock and a poisoned mutex are errors, not a deadlock or a panic
+    let p = PIterator::new(std::sync::Arc::clone(&ll))?;
+    {
+        let _lock = ll.lock().unwrap();
+        assert_eq!(p.get_k().unwrap_err().kind(), MapErrorKind::WouldBlock);
+    }
+    assert_eq!(p.get_k()?, 0);
+    let poisoner = std::sync::Arc::clone(&ll);
+    assert!(std::thread::spawn(move || {
+        let _lock = poisoner.lock().unwrap();
+        panic!("poisoning the list mutex");
+    })
+    .join()
+    .is_err());
+    assert_eq!(p.get_k().unwrap_err().kind(), MapErrorKind::InternalError);
+    Ok(())
+}
+
+#[test]
+/// test that the hint of lower_bound_hint() is the right insert position
+fn linked_list_lower_bound_hint_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    assert_eq!(
+        ll.lower_bound_hint(&3, ll.head())?,
+        LowerBound {
+            index: None,
+            hint: crate::OUT_OF_BOUNDS
+        }
+    );
+    for key in [5, 1, 9, 3, 7, 0, 8, 2, 6, 4] {
+        let lb = ll.lower_bound_hint(&(key * 3), ll.head())?;
+        assert!(lb.index.is_none() || lb.index == Some(lb.hint));
+        let index = ll.ordered_insert_pos(key * 3, key, lb.hint)?;
+        let lb = ll.lower_bound_hint(&(key * 3), ll.tail())?;
+        assert_eq!((lb.index, lb.hint), (Some(index), index));
+    }
+    assert_eq!(
+        ll.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+        (0..10).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+/// test trimming a list from both ends with a CursorPair
+fn linked_list_cursor_pair_01() -> Result<(), MapError> {
+    for len in 0..6 {
+        let ll = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+        for i in 0..len {
+            let _ = ll.borrow_mut().ordered_insert(i, i)?;
+        }
+        let mut pair = CursorPair::new(Rc::clone(&ll))?;
+        let mut removed = Vec::new();
+        let mut from_front = true;
+        while !pair.crossed()? {
+            if from_front {
+                removed.push(pair.remove_front()?.0);
+            } else {
+                removed.push(pair.remove_back()?.0);
+            }
+            from_front = !from_front;
+        }
+        assert!(!pair.meet()?);
+        assert!(ll.borrow().is_empty());
+        assert_eq!(removed.len(), len as usize);
+        let (front, back) = pair.into_inner();
+        assert!(!front.is_ok()? && !back.is_ok()?);
+    }
+    let ll = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+    for i in 0..5 {
+        let _ = ll.borrow_mut().ordered_insert(i, i)?;
+    }
+    let mut pair = CursorPair::new(Rc::clone(&ll))?;
+    pair.front.next()?;
+    pair.front.next()?;
+    pair.back.prev()?;
+    assert!(!pair.meet()?);
+    pair.back.prev()?;
+    assert!(pair.meet()? && !pair.crossed()?);
+    pair.back.prev()?;
+    assert!(!pair.meet()? && pair.crossed()?);
+    Ok(())
+}
+
+#[test]
+/// test that iter_by_value() is a stable sort on the values
+fn linked_list_iter_by_value_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in 0..50 {
+        let _ = ll.ordered_insert((i * 17) % 50, i % 7)?;
+    }
+    let mut expected = ll.iter().collect::<Vec<_>>();
+    expected.sort_by_key(|(_, v)| std::cmp::Reverse(**v));
+    let by_value = ll.iter_by_value(|a, b| b.cmp(a));
+    assert_eq!(by_value.len(), 50);
+    assert_eq!(by_value.collect::<Vec<_>>(), expected);
+    expected.sort_by_key(|(_, v)| **v);
+    assert_eq!(
+        ll.iter_by_value(i32::cmp).next_back(),
+        expected.last().copied()
+    );
+    assert_eq!(
+        LinkedList::<i32, i32>::default()
+            .iter_by_value(i32::cmp)
+            .count(),
+        0
+    );
+    Ok(())
+}
+
+#[test]
+/// test find() and find_pos() against a BTreeMap
+fn linked_list_find_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let mut reference = std::collections::BTreeMap::new();
+    for i in 0..40 {
+        let key = (i * 7) % 60;
+        let index = ll.ordered_insert(key, i)?;
+        let _ = reference.insert(key, index);
+    }
+    for key in -1..61 {
+        assert_eq!(ll.find(&key), reference.get(&key).copied());
+        assert_eq!(ll.contains_key(&key), reference.contains_key(&key));
+        assert_eq!(ll.find_pos(&key, ll.tail()), reference.get(&key).copied());
+        assert_eq!(
+            ll.find_pos(&key, crate::OUT_OF_BOUNDS),
+            reference.get(&key).copied()
+        );
+    }
+    assert!(LinkedList::<i32, i32>::default().find(&1).is_none());
+    Ok(())
+}
+
+#[test]
+/// test swap() of two lists, and that a frozen list can't be swapped
+fn linked_list_swap_01() -> Result<(), MapError> {
+    let a = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+    let mut b = LinkedList::<i32, i32>::default();
+    for i in 0..5 {
+        let _ = a.borrow_mut().ordered_insert(i, i)?;
+        let _ = b.ordered_insert(i + 10, i)?;
+    }
+    let bookmark = a.borrow_mut().add_bookmark_at(2)?;
+    {
+        let _guard = FreezeGuard::new(&a)?;
+        assert_eq!(
+            a.borrow_mut().swap(&mut b).unwrap_err().kind(),
+            MapErrorKind::Frozen
+        );
+        assert_eq!(
+            b.swap(&mut a.borrow_mut()).unwrap_err().kind(),
+            MapErrorKind::Frozen
+        );
+    }
+    a.borrow_mut().swap(&mut b)?;
+    assert!(!a.borrow().is_frozen() && !b.is_frozen());
+    assert_eq!(
+        a.borrow().iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![10, 11, 12, 13, 14]
+    );
+    assert_eq!(
+        b.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 4]
+    );
+    assert_eq!(b.get_k(b.bookmark(bookmark).unwrap())?, &2);
+    // the freeze state doesn't follow the content
+    let _guard = FreezeGuard::new(&a)?;
+    assert!(a.borrow().is_frozen() && !b.is_frozen());
+    Ok(())
+}
+
+#[test]
+/// test moving items with their metadata between lists with extract() and insert_node()
+fn linked_list_node_handle_01() -> Result<(), MapError> {
+    let mut a = LinkedList::<i32, String, i32>::default();
+    let mut b = LinkedList::<i32, String, i32>::default();
+    for i in 0..10 {
+        let index = a.ordered_insert(i, i.to_string())?;
+        let _ = a.set_meta(index, -i)?;
+        if i % 2 == 0 {
+            let _ = b.ordered_insert(i, "b".to_string())?;
+        }
+    }
+    assert!(a.extract(&20)?.is_none());
+    for i in 0..10 {
+        let node = a.extract(&i)?.unwrap();
+        assert_eq!(node.meta, -i);
+        let (index, rejected) = b.insert_node(node, b.tail())?;
+        if i % 2 == 0 {
+            assert_eq!(
+                rejected,
+                Some(NodeHandle {
+                    key: i,
+                    value: i.to_string(),
+                    meta: -i
+                })
+            );
+            assert_eq!(b.get_meta(index), Some(&0));
+        } else {
+            assert!(rejected.is_none());
+            assert_eq!(b.get(index)?, (&i, &i.to_string()));
+            assert_eq!(b.get_meta(index), Some(&-i));
+        }
+    }
+    assert!(a.is_empty());
+    assert_eq!(b.len(), 10);
+
+    let ll = Rc::new(RefCell::new(b));
+    let _guard = FreezeGuard::new(&ll)?;
+    assert_eq!(
+        ll.borrow_mut().extract(&1).unwrap_err().kind(),
+        MapErrorKind::Frozen
+    );
+    assert_eq!(
+        ll.borrow().get_meta(ll.borrow().find(&1).unwrap()),
+        Some(&-1)
+    );
+    Ok(())
+}
+
+#[test]
+/// test upper_bound() against a BTreeMap range
+fn linked_list_upper_bound_01() -> Result<(), MapError> {
+    let ll = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+    let mut reference = std::collections::BTreeMap::new();
+    for i in 0..30 {
+        let key = (i * 11) % 50;
+        let _ = ll.borrow_mut().ordered_insert(key, i)?;
+        let _ = reference.insert(key, i);
+    }
+    for key in -1..52 {
+        let expected = reference
+            .range((Bound::Excluded(key), Bound::Unbounded))
+            .next()
+            .map(|(k, _)| *k);
+        let list = ll.borrow();
+        let ub = list.upper_bound(&key)?;
+        assert_eq!(ub.map(|i| *list.get_k(i).unwrap()), expected);
+        assert_eq!(list.upper_bound_pos(&key, list.head())?, ub);
+        assert_eq!(list.equal_range(&key)?, (list.lower_bound(&key)?, ub));
+        drop(list);
+        let p = PIterator::upper_bound(Rc::clone(&ll), &key)?;
+        assert_eq!(p.get_k().ok(), expected);
+    }
+    Ok(())
+}
+
+#[test]
+/// test merge_from() against BTreeMap semantics, including metadata and capacity limits
+fn linked_list_merge_from_01() -> Result<(), MapError> {
+    let mut a = LinkedList::<i32, i32, i32>::default();
+    let mut b = LinkedList::<i32, i32, i32>::default();
+    let mut expected_a = std::collections::BTreeMap::new();
+    let mut expected_b = std::collections::BTreeMap::new();
+    for i in 0..40 {
+        let _ = a.ordered_insert((i * 7) % 50, 1)?;
+        let _ = expected_a.insert((i * 7) % 50, 1);
+        let index = b.ordered_insert((i * 13) % 60, 2)?;
+        let _ = b.set_meta(index, i)?;
+    }
+    for (k, _) in b.iter() {
+        if expected_a.contains_key(k) {
+            let _ = expected_b.insert(*k, 2);
+        } else {
+            let _ = expected_a.insert(*k, 2);
+        }
+    }
+    let moved = a.merge_from(&mut b)?;
+    assert_eq!(moved, 40 - expected_b.len());
+    assert_eq!(
+        a.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        expected_a.into_iter().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        b.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        expected_b.into_iter().collect::<Vec<_>>()
+    );
+    for i in 0..40 {
+        let key = (i * 13) % 60;
+        if a.get_v(a.find(&key).unwrap())? == &2 {
+            assert_eq!(a.get_meta(a.find(&key).unwrap()), Some(&i));
+        }
+    }
+
+    let mut a = LinkedList::<i32, i32>::with_max_capacity(3);
+    let mut b = LinkedList::<i32, i32>::default();
+    for i in 0..5 {
+        let _ = b.ordered_insert(i, i)?;
+    }
+    let _ = a.ordered_insert(1, 1)?;
+    assert_eq!(
+        a.merge_from(&mut b).unwrap_err().kind(),
+        MapErrorKind::CapacityExceeded
+    );
+    assert_eq!(a.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(b.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![1, 3, 4]);
+    Ok(())
+}
+
+#[test]
+/// test that ListIterator::peek() agrees with next(), and iter_from() restarts at an index
+fn linked_list_iterator_peek_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in 0..20 {
+        let _ = ll.ordered_insert((i * 3) % 20, i)?;
+    }
+    let mut iter = ll.iter();
+    let mut indices = Vec::new();
+    while let Some(peeked) = iter.peek() {
+        indices.push(iter.peek_index().unwrap());
+        assert_eq!(iter.next(), Some(peeked));
+    }
+    assert!(iter.peek_index().is_none() && iter.next().is_none());
+    for (n, index) in indices.iter().enumerate() {
+        assert_eq!(ll.iter_from(*index).count(), 20 - n);
+        assert_eq!(ll.iter_from(*index).next(), ll.get_opt(*index));
+    }
+    assert_eq!(ll.iter_from(crate::OUT_OF_BOUNDS).count(), 0);
+    let _ = ll.pop_front()?;
+    assert_eq!(ll.iter_from(indices[0]).peek(), None);
+    Ok(())
+}
+
+#[test]
+/// test PIterator::erase_range() over every pair of positions
+fn linked_list_erase_range_01() -> Result<(), MapError> {
+    for first in 0..6 {
+        for last in 0..7 {
+            let ll = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+            for i in 0..6 {
+                let _ = ll.borrow_mut().ordered_insert(i, i)?;
+            }
+            let p_first = PIterator::lower_bound(Rc::clone(&ll), &first)?;
+            // last == 6 is out of bounds, i.e. the end of the list
+            let p_last = PIterator::lower_bound(Rc::clone(&ll), &last)?;
+            if first > last {
+                assert_eq!(
+                    PIterator::erase_range(&p_first, &p_last)
+                        .unwrap_err()
+                        .kind(),
+                    MapErrorKind::OrderViolation
+                );
+                assert_eq!(ll.borrow().len(), 6);
+                continue;
+            }
+            assert_eq!(
+                PIterator::erase_range(&p_first, &p_last)?,
+                (last - first) as usize
+            );
+            assert_eq!(
+                ll.borrow().iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+                (0..6)
+                    .filter(|k| *k < first || *k >= last)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+    let a = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+    let b = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+    let _ = a.borrow_mut().ordered_insert(1, 1)?;
+    let _ = b.borrow_mut().ordered_insert(1, 1)?;
+    let p_a = PIterator::new(Rc::clone(&a))?;
+    let p_b = PIterator::new(Rc::clone(&b))?;
+    assert_eq!(
+        PIterator::erase_range(&p_a, &p_b).unwrap_err().kind(),
+        MapErrorKind::HintMismatch
+    );
+    assert_eq!(a.borrow().len() + b.borrow().len(), 2);
+    Ok(())
+}
+
+#[test]
+/// test remove_by_key() against a BTreeMap, and that the slots are recycled
+fn linked_list_remove_by_key_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let mut reference = std::collections::BTreeMap::new();
+    for i in 0..30 {
+        let _ = ll.ordered_insert((i * 7) % 30, i)?;
+        let _ = reference.insert((i * 7) % 30, i);
+    }
+    for key in (-2..32).rev().step_by(3) {
+        assert_eq!(
+            ll.remove_by_key(&key)?,
+            reference.remove(&key).map(|v| (key, v))
+        );
+    }
+    assert_eq!(ll.free_slots().len(), 30 - ll.len());
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        reference.into_iter().collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+/// test that a LinkedSet node has no space for the value, and the set helpers
+fn linked_list_set_01() -> Result<(), MapError> {
+    // the two links and the key, nothing for the value or the default metadata
+    let usize_size = std::mem::size_of::<usize>();
+    assert_eq!(
+        std::mem::size_of::<crate::Node<u64, (), ()>>(),
+        2 * usize_size + std::mem::size_of::<u64>()
+    );
+    assert_eq!(
+        std::mem::size_of::<crate::Node<[u64; 3], (), ()>>(),
+        2 * usize_size + std::mem::size_of::<[u64; 3]>()
+    );
+    assert!(
+        std::mem::size_of::<crate::Node<u64, (), ()>>()
+            < std::mem::size_of::<crate::Node<u64, u64, ()>>()
+    );
+    let mut set = LinkedSet::<i32>::default();
+    for i in (0..20).rev() {
+        let _ = set.insert(i % 10)?;
+    }
+    let tail = set.tail();
+    let _ = set.insert_pos(10, tail)?;
+    assert_eq!(
+        set.keys().copied().collect::<Vec<_>>(),
+        (0..11).collect::<Vec<_>>()
+    );
+    assert_eq!(set.take_key(&4)?, Some(4));
+    assert_eq!(set.take_key(&4)?, None);
+    assert_eq!(set.keys().last(), Some(&10));
+    Ok(())
+}
+
+#[test]
+/// test that the comparison counts follow the search hops, and are opt-in
+fn linked_list_compare_stats_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    assert!(ll.compare_stats().is_none());
+    ll.enable_compare_stats();
+    for i in 0..50 {
+        let _ = ll.ordered_insert_pos(i, i, ll.tail())?;
+    }
+    let stats = ll.compare_stats().unwrap();
+    // the first insert into an empty list doesn't search
+    assert_eq!(stats.insert.operations, 49);
+    assert_eq!(stats.insert.comparisons, 49);
+    assert_eq!(stats.insert.average(), 1.0);
+    assert_eq!(stats.lower_bound, Default::default());
+    let _ = ll.lower_bound(&0)?;
+    let stats = ll.compare_stats().unwrap();
+    assert_eq!(stats.lower_bound.operations, 1);
+    assert_eq!(stats.last, 50);
+    assert_eq!(stats.lower_bound.max_comparisons, 50);
+    // a clone keeps counting on its own
+    let clone = ll.clone();
+    let _ = clone.lower_bound(&49)?;
+    assert_eq!(clone.compare_stats().unwrap().lower_bound.operations, 2);
+    assert_eq!(ll.compare_stats().unwrap().lower_bound.operations, 1);
+    ll.disable_compare_stats();
+    let _ = ll.lower_bound(&0)?;
+    assert_eq!(ll.compare_stats(), None::<CompareStats>);
+    Ok(())
+}
+
+#[test]
+/// test retain() against BTreeMap::retain(), with and without deferred slot reuse
+fn linked_list_retain_01() -> Result<(), MapError> {
+    for defer_slot_reuse in [false, true] {
+        let mut ll = LinkedList::<i32, i32>::default();
+        ll.set_deferred_slot_reuse(defer_slot_reuse)?;
+        let mut reference = std::collections::BTreeMap::new();
+        for i in 0..40 {
+            let _ = ll.ordered_insert((i * 7) % 40, i)?;
+            let _ = reference.insert((i * 7) % 40, i);
+        }
+        let bookmark = ll.add_bookmark(ll.find(&3).unwrap())?;
+        assert_eq!(ll.retain(|k, v| k % 3 != 0 && v % 2 == 0)?, 27);
+        reference.retain(|k, v| k % 3 != 0 && *v % 2 == 0);
+        assert_eq!(
+            ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            reference.clone().into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(ll.len(), reference.len());
+        assert!(*ll.get_k(ll.bookmark(bookmark).unwrap())? > 3);
+        let _ = ll.purge_tombstones()?;
+        assert_eq!(ll.free_slots().len(), 27);
+        assert_eq!(ll.retain(|_, _| false)?, 13);
+        assert!(ll.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+/// test the neighbours of an InsertedEntry, and entries of existing keys
+fn linked_list_inserted_entry_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    {
+        let mut entry = ll.ordered_insert_entry(5, 5)?;
+        assert!(entry.inserted());
+        assert_eq!((entry.prev(), entry.next()), (None, None));
+        assert!(entry.prev_mut().is_none() && entry.next_mut().is_none());
+    }
+    let _ = ll.ordered_insert(1, 1)?;
+    let nine = ll.ordered_insert(9, 9)?;
+    let mut entry = ll.ordered_insert_entry_pos(7, 7, nine)?;
+    assert_eq!(entry.next(), Some(nine));
+    *entry.next_mut().unwrap().1 += 100;
+    assert_eq!(entry.prev_mut().unwrap(), (&5, &mut 5));
+    let seven = entry.index();
+    let list = entry.into_list();
+    let mut entry = list.ordered_insert_entry(7, 0)?;
+    assert!(!entry.inserted());
+    assert_eq!(entry.index(), seven);
+    assert_eq!(entry.get_mut(), (&7, &mut 7));
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        vec![(1, 1), (5, 5), (7, 7), (9, 109)]
+    );
+    Ok(())
+}
+
+#[test]
+/// test check_consistency() and recover() on deliberately damaged lists
+fn linked_list_recover_01() -> Result<(), MapError> {
+    let build = || -> Result<LinkedList<i32, i32>, MapError> {
+        let mut ll = LinkedList::<i32, i32>::default();
+        for i in 0..10 {
+            let _ = ll.ordered_insert((i * 3) % 10, i)?;
+        }
+        let _ = ll.remove_by_key(&5)?;
+        Ok(ll)
+    };
+    let ll = build()?;
+    assert!(ll.check_consistency().is_ok());
+    let expected = ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+
+    // a broken forward link makes half of the list unreachable
+    let mut ll = build()?;
+    let bookmark = ll.add_bookmark(ll.find(&8).unwrap())?;
+    let two = ll.find(&2).unwrap();
+    ll.nodes_[two].as_mut().unwrap().next_ = crate::OUT_OF_BOUNDS;
+    assert!(ll.check_consistency().is_err());
+    let recovery = ll.recover();
+    assert_eq!((recovery.kept, recovery.relinked), (9, 6));
+    assert!(recovery.dropped.is_empty());
+    assert!(ll.check_consistency().is_ok());
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        expected
+    );
+    assert_eq!(ll.get_k(ll.bookmark(bookmark).unwrap())?, &8);
+
+    // a duplicated key, a cycle and a lost free slot
+    let mut ll = build()?;
+    let four = ll.find(&4).unwrap();
+    ll.nodes_[four].as_mut().unwrap().key_ = 3;
+    let tail = ll.tail();
+    ll.nodes_[tail].as_mut().unwrap().next_ = ll.head();
+    let _ = ll.id_pool_.pop();
+    assert!(ll.check_consistency().is_err());
+    let recovery = ll.recover();
+    assert_eq!(recovery.dropped.len(), 1);
+    assert_eq!(recovery.kept, 8);
+    assert!(ll.check_consistency().is_ok());
+    assert_eq!(ll.len(), 8);
+    assert_eq!(ll.free_slots().len(), 2);
+    Ok(())
+}
+
+#[test]
+/// test that extract_if() yields exactly what retain() would drop
+fn linked_list_extract_if_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in 0..30 {
+        let _ = ll.ordered_insert((i * 7) % 30, i)?;
+    }
+    let mut kept = ll.clone();
+    let _ = kept.retain(|k, _| k % 4 != 1)?;
+    let extracted = ll
+        .extract_if(|k, v| {
+            *v += 100;
+            k % 4 == 1
+        })?
+        .collect::<Vec<_>>();
+    assert_eq!(extracted.len(), 8);
+    assert!(extracted.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(extracted.iter().all(|(k, v)| k % 4 == 1 && *v >= 100));
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v - 100)).collect::<Vec<_>>(),
+        kept.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+    );
+    // dropping the iterator early keeps the rest
+    let first = ll.extract_if(|_, _| true)?.next();
+    assert_eq!(first.map(|(k, _)| k), Some(0));
+    assert_eq!(ll.len(), 21);
+    Ok(())
+}
+
+#[test]
+/// test that a budgeted search either agrees with lower_bound_pos() or stops within the budget
+fn linked_list_budget_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in 0..20 {
+        let _ = ll.ordered_insert(i * 2, i)?;
+    }
+    let indices = ll.live_slot_indices().collect::<Vec<_>>();
+    for position in indices.iter().copied().chain([crate::OUT_OF_BOUNDS]) {
+        for key in -1..41 {
+            let expected = ll.lower_bound_pos(&key, position)?;
+            let mut budget = 0;
+            loop {
+                ll.enable_compare_stats();
+                match ll.lower_bound_budget(&key, position, budget)? {
+                    BudgetSearch::Found(rv) => {
+                        assert_eq!(rv, expected);
+                        break;
+                    }
+                    BudgetSearch::NotFoundWithinBudget { stopped_at } => {
+                        assert_eq!(ll.compare_stats().unwrap().last, budget as u64);
+                        assert_eq!(ll.lower_bound_pos(&key, stopped_at)?, expected);
+                        budget += 1;
+                    }
+                }
+            }
+            assert!(budget <= 22);
+        }
+    }
+    let head = ll.head();
+    match ll.ordered_insert_pos_budget(39, 0, head, 5)? {
+        BudgetInsert::NotFoundWithinBudget {
+            stopped_at,
+            key,
+            value,
+        } => {
+            assert_eq!((ll.get_k(stopped_at)?, key, value), (&8, 39, 0));
+            let BudgetInsert::Done(index) =
+                ll.ordered_insert_pos_budget(key, value, stopped_at, 20)?
+            else {
+                panic!("the budget was too small")
+            };
+            assert_eq!(ll.get_prev_k(index)?, &38);
+        }
+        BudgetInsert::Done(_) => panic!("the budget was too large"),
+    }
+    let tail = ll.tail();
+    assert_eq!(
+        ll.ordered_insert_pos_budget(39, 1, tail, 1)?,
+        BudgetInsert::Done(ll.find(&39).unwrap())
+    );
+    assert_eq!(ll.len(), 21);
+    let mut empty = LinkedList::<i32, i32>::default();
+    assert!(matches!(
+        empty.ordered_insert_pos_budget(1, 1, 0, 0)?,
+        BudgetInsert::Done(_)
+    ));
+    Ok(())
+}
+
+#[test]
+/// test that branded indices round trip, and that a clone is another list
+fn linked_list_brand_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let indices = (0..5)
+        .map(|i| ll.ordered_insert(i, i).map(|index| ll.brand(index)))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (i, index) in indices.iter().enumerate() {
+        assert_eq!(ll.get_k(ll.unbrand(*index))?, &(i as i32));
+        assert_eq!(ll.unbrand(*index), index.index());
+    }
+    let clone = ll.clone();
+    let rv = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| clone.unbrand(indices[0])));
+    assert_eq!(rv.is_err(), cfg!(debug_assertions));
+    Ok(())
+}
+
+#[test]
+/// test ListIterator::position() and ListIterator::remaining_len()
+fn linked_list_iter_progress_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in (0..10).rev() {
+        let _ = ll.ordered_insert(i, i)?;
+    }
+    let mut iter = ll.iter();
+    for i in 0..10 {
+        assert_eq!(iter.position(), i);
+        assert_eq!(iter.remaining_len(), 10 - i);
+        let _ = iter.next();
+    }
+    assert_eq!((iter.position(), iter.remaining_len()), (10, 0));
+    assert!(iter.next().is_none());
+    assert_eq!(iter.remaining_len(), 0);
+
+    let index = ll.find(&6).unwrap();
+    let mut iter = ll.iter_from(index);
+    assert_eq!((iter.position(), iter.remaining_len()), (6, 4));
+    assert_eq!(iter.next(), Some((&6, &6)));
+    assert_eq!(iter.remaining_len(), 3);
+    assert_eq!(iter.by_ref().count(), 3);
+    assert_eq!(ll.iter_from(crate::OUT_OF_BOUNDS).remaining_len(), 0);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::with_fixed_capacity() and FixedCapacity
+fn linked_list_fixed_capacity_01() -> Result<(), MapError> {
+    const CAPACITY: usize = FixedCapacity::<3>::CHECKED;
+    let mut ll = LinkedList::<i32, i32>::with_fixed_capacity::<CAPACITY>();
+    for i in 0..3 {
+        let _ = ll.ordered_insert(i, i)?;
+    }
+    assert_eq!(
+        ll.ordered_insert(3, 3).unwrap_err().kind(),
+        MapErrorKind::CapacityExceeded
+    );
+    assert!(FixedCapacity::<65536>::index_fits(u16::BITS));
+    assert!(!FixedCapacity::<65537>::index_fits(u16::BITS));
+    assert!(FixedCapacity::<{ usize::MAX - 1 }>::index_fits(u64::BITS));
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::ordered_insert_or_assign()
+fn linked_list_insert_or_assign_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in (0..10).step_by(2) {
+        assert!(ll.ordered_insert_or_assign(i, i)?.inserted);
+    }
+    let tail = ll.tail();
+    for i in 0..10 {
+        let rv = ll.ordered_insert_or_assign_pos(i, -i, tail)?;
+        assert_eq!(rv.inserted, i % 2 == 1);
+        assert_eq!(ll.get(rv.index)?, (&i, &-i));
+    }
+    assert_eq!(ll.len(), 10);
+    assert!(ll.iter().all(|(k, v)| *k == -*v));
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::ordered_insert_result() and LinkedList::ordered_insert_pos_result()
+fn linked_list_insert_result_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let first = ll.ordered_insert_result(5, 5)?;
+    assert_eq!(
+        first,
+        InsertResult {
+            index: 0,
+            inserted: true
+        }
+    );
+    let rv = ll.ordered_insert_result(5, 6)?;
+    assert_eq!((rv.index, rv.inserted), (first.index, false));
+    for i in 0..10 {
+        let rv = ll.ordered_insert_pos_result(i, i, first.index)?;
+        assert_eq!(rv.inserted, i != 5);
+        assert_eq!(ll.get(rv.index)?, (&i, &i));
+    }
+    assert_eq!(ll.len(), 10);
+    Ok(())
+}
+
+#[test]
+/// test that Indexed keeps the secondary index in sync
+fn linked_list_indexed_01() -> Result<(), MapError> {
+    // (priority, id)
+    let mut ll = LinkedList::<(i32, u32), i32>::default();
+    let _ = ll.ordered_insert((5, 0), 0)?;
+    let mut indexed = Indexed::new(ll, |k: &(i32, u32)| k.1)?;
+    for id in 1..10_u32 {
+        let _ = indexed.ordered_insert(((id as i32 * 7) % 10, id), id as i32)?;
+    }
+    for id in 0..10_u32 {
+        let index = indexed.find_by(&id).unwrap();
+        assert_eq!(indexed.list().get_k(index)?.1, id);
+    }
+    // a NOP, and a conflicting projection
+    assert_eq!(
+        indexed.ordered_insert((5, 0), 100)?,
+        indexed.find_by(&0).unwrap()
+    );
+    assert!(indexed.ordered_insert((6, 0), 100).is_err());
+    assert_eq!(indexed.list().len(), 10);
+
+    let (key, _) = indexed.pop_front()?.unwrap();
+    assert!(indexed.find_by(&key.1).is_none());
+    assert_eq!(indexed.remove_by(&6)?.map(|(k, _)| k), Some((2, 6)));
+    assert!(indexed.remove_by(&6)?.is_none());
+
+    // the item (9, 7) moves to the id 70
+    let index = indexed.find_by(&7).unwrap();
+    indexed.replace_key(index, (9, 70))?;
+    assert!(indexed.find_by(&7).is_none());
+    assert_eq!(indexed.get_by(&70), Some((&(9, 70), &7)));
+    assert!(indexed.replace_key(index, (9, 1)).is_err());
+    *indexed.get_v_mut(index)? += 1;
+    assert_eq!(indexed.get_by(&70), Some((&(9, 70), &8)));
+
+    let ll = indexed.into_inner();
+    assert_eq!(ll.len(), 8);
+    assert!(Indexed::new(ll, |_: &(i32, u32)| 0).is_err());
+    Ok(())
+}
+
+#[test]
+/// test DeferredInserts, buffered while the list is borrowed
+fn linked_list_deferred_inserts_01() -> Result<(), MapError> {
+    let ll = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+    for i in 0..5 {
+        let _ = ll.borrow_mut().ordered_insert(i * 10, i)?;
+    }
+    let p = PIterator::new(Rc::clone(&ll))?;
+    let mut deferred = p.defer_inserts();
+    {
+        let list = ll.borrow();
+        for (k, v) in list.iter() {
+            deferred.ordered_insert(k + 5, *v);
+            deferred.ordered_insert(*k, -1); // a NOP
+        }
+        deferred.ordered_insert(7, 100);
+        deferred.ordered_insert(7, 200);
+        // the list is still borrowed
+        assert!(deferred.flush().is_err());
+        assert_eq!(deferred.len(), 12);
+    }
+    {
+        let _guard = p.freeze()?;
+        assert!(deferred.flush().is_err());
+        assert_eq!(deferred.len(), 12);
+    }
+    assert_eq!(deferred.flush()?, 6);
+    assert!(deferred.is_empty());
+    let list = ll.borrow();
+    assert_eq!(list.len(), 11);
+    assert_eq!(list.get_v(list.find(&7).unwrap())?, &100);
+    assert!(list.iter().all(|(k, v)| k % 5 != 0 || *v >= 0));
+    drop(list);
+
+    let mut deferred = DeferredInserts::new(Rc::clone(&ll));
+    deferred.ordered_insert(-1, -1);
+    assert_eq!(deferred.discard(), vec![(-1, -1)]);
+    deferred.ordered_insert(-2, -2);
+    assert_eq!(deferred.flush()?, 1);
+    drop(deferred);
+    assert_eq!(ll.borrow().peek_front_k(), Some(&-2));
+    Ok(())
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "never flushed")]
+/// test that dropping a DeferredInserts with buffered items is caught in debug builds
+fn linked_list_deferred_inserts_02() {
+    let ll = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+    let mut deferred = DeferredInserts::new(Rc::clone(&ll));
+    deferred.ordered_insert(1, 1);
+    let _list = ll.borrow();
+    drop(deferred);
+}
+
+#[test]
+/// test LinkedList::entry() and LinkedList::entry_pos()
+fn linked_list_entry_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, Vec<i32>>::default();
+    for i in [5, 1, 9, 5, 3, 9, 5] {
+        ll.entry(i)?.or_insert_with(Vec::new)?.push(i);
+    }
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, v.len())).collect::<Vec<_>>(),
+        vec![(1, 1), (3, 1), (5, 3), (9, 2)]
+    );
+    let tail = ll.tail();
+    match ll.entry_pos(4, tail)? {
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), &4);
+            let index = entry.insert_index(vec![4])?;
+            assert_eq!(ll.get_k(index)?, &4);
+        }
+        Entry::Occupied(_) => panic!("4 was not in the list"),
+    }
+    match ll.entry(9)? {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.get(), &vec![9, 9]);
+            assert_eq!(entry.insert(vec![]), vec![9, 9]);
+            assert_eq!(entry.remove()?, (9, vec![]));
+        }
+        Entry::Vacant(_) => panic!("9 was in the list"),
+    }
+    let _ = ll.entry(1)?.and_modify(|v| v.clear()).or_default()?;
+    assert!(ll
+        .entry(0)?
+        .and_modify(|v| v.push(0))
+        .or_default()?
+        .is_empty());
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, v.len())).collect::<Vec<_>>(),
+        vec![(0, 0), (1, 0), (3, 1), (4, 1), (5, 3)]
+    );
+    Ok(())
+}
+
+#[test]
+/// test that LinkedList::list_id() tells lists apart, also in errors and scan warnings
+fn linked_list_list_id_01() -> Result<(), MapError> {
+    use std::sync::{Arc, Mutex};
+    let mut a = LinkedList::<i32, i32>::default();
+    let b = LinkedList::<i32, i32>::default();
+    assert_ne!(a.list_id(), b.list_id());
+    let id = a.list_id();
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let r = Arc::clone(&reported);
+    a.set_scan_warning(0, move |report| r.lock().unwrap().push(report.list_id));
+    let index = a.ordered_insert(1, 1)?;
+    let _ = a.lower_bound(&1)?;
+    assert!(!reported.lock().unwrap().is_empty());
+    assert!(reported.lock().unwrap().iter().all(|i| *i == id));
+
+    let _ = a.pop_front()?;
+    let err = a.get(index).unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::InternalError);
+    #[cfg(feature = "rich_error")]
+    assert!(err
+        .to_string()
+        .contains(&format!("{} was not active in list {}", index, id)));
+    #[cfg(feature = "rich_error")]
+    assert!(a
+        .get_k(100)
+        .unwrap_err()
+        .to_string()
+        .contains(&format!("not found in list {}", id)));
+    Ok(())
+}
+
+#[test]
+/// test that LinkedList::get_or_insert_with() only builds missing values
+fn linked_list_get_or_insert_with_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let mut built = 0;
+    for i in [3, 1, 3, 2, 1, 3] {
+        let index = ll.get_or_insert_with(i, || {
+            built += 1;
+            i * 10
+        })?;
+        assert_eq!(ll.get(index)?, (&i, &(i * 10)));
+    }
+    assert_eq!(built, 3);
+    assert_eq!(ll.len(), 3);
+    Ok(())
+}
+
+#[test]
+/// test LinkedList::append() with overlapping key ranges and free slots
+fn linked_list_append_01() -> Result<(), MapError> {
+    let mut a = LinkedList::<i32, i32>::default();
+    let mut b = LinkedList::<i32, i32>::default();
+    for i in 0..10 {
+        let _ = a.ordered_insert(i * 2, 0)?;
+        let _ = b.ordered_insert(i * 3, 1)?;
+    }
+    let _ = a.pop_back()?;
+    let _ = a.pop_back()?;
+    let slots = a.slot_count();
+    assert_eq!(a.append(&mut b)?, 7);
+    assert!(b.is_empty());
+    // the two free slots were reused
+    assert_eq!(a.slot_count(), slots + 5);
+    assert_eq!(a.len(), 15);
+    let keys = a.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(a.get_v(a.find(&6).unwrap())?, &0);
+    assert_eq!(a.get_v(a.find(&27).unwrap())?, &1);
+    assert_eq!(a.append(&mut b)?, 0);
+    Ok(())
+}
+
+#[test]
+/// test Cursor, the borrowed read only pointer
+fn linked_list_cursor_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in (0..10).rev() {
+        let _ = ll.ordered_insert(i * 10, i)?;
+    }
+    let mut cursor = ll.lower_bound_cursor(&35)?;
+    let start = cursor;
+    assert_eq!(cursor.get(), Some((&40, &4)));
+    let mut keys = Vec::new();
+    while cursor.is_ok() {
+        keys.push(*cursor.get_k().unwrap());
+        let _ = cursor.move_next();
+    }
+    assert_eq!(keys, vec![40, 50, 60, 70, 80, 90]);
+    assert_eq!(cursor.get(), None);
+    assert_eq!(start.iter_from_here().count(), 6);
+
+    let cursor = ll.upper_bound_cursor(&40)?;
+    assert_eq!(cursor.get_v(), Some(&5));
+    assert!(!ll.upper_bound_cursor(&90)?.is_ok());
+    assert!(!ll.lower_bound_cursor(&91)?.is_ok());
+
+    let mut cursor = ll.cursor();
+    assert!(cursor.is_at_head());
+    assert!(!cursor.move_prev());
+    cursor.move_to_tail();
+    assert!(cursor.is_at_tail());
+    assert_eq!(cursor.get_k(), Some(&90));
+    let cursor = Cursor::new(&ll, crate::OUT_OF_BOUNDS);
+    assert!(!cursor.is_ok());
+    Ok(())
+}
+
+#[test]
+/// test that ListArena moves items between lists by relinking
+fn linked_list_arena_01() -> Result<(), MapError> {
+    let mut arena = ListArena::<i32, i32>::with_capacity(10);
+    let even = arena.add_list();
+    let odd = arena.add_list();
+    let indices = (0..10)
+        .rev()
+        .map(|i| arena.ordered_insert(even, i, i * 10))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(arena.ordered_insert(even, 3, 0)?, indices[6]);
+    assert_eq!(arena.len(even), 10);
+    for index in &indices {
+        if arena.get(*index)?.0 % 2 == 1 {
+            assert!(arena.move_to(*index, odd)?);
+        }
+    }
+    let keys = |list| arena.iter(list).map(|(k, _)| *k).collect::<Vec<_>>();
+    assert_eq!(keys(even), vec![0, 2, 4, 6, 8]);
+    assert_eq!(keys(odd), vec![1, 3, 5, 7, 9]);
+    assert_eq!((arena.total_len(), arena.len(odd)), (10, 5));
+    // the items kept their indices and values
+    for (n, index) in indices.iter().enumerate() {
+        let key = 9 - n as i32;
+        assert_eq!(arena.get(*index)?, (&key, &(key * 10)));
+    }
+
+    // a duplicate key is not moved
+    let four = arena.ordered_insert(odd, 4, -4)?;
+    assert!(!arena.move_to(four, even)?);
+    assert_eq!(arena.list_of(four), Some(odd));
+    assert_eq!(arena.remove(four)?, (4, -4));
+    assert!(arena.get(four).is_err());
+    let head = arena.head(odd);
+    assert_eq!(arena.next(head).map(|i| *arena.get(i).unwrap().0), Some(3));
+    assert_eq!(arena.prev(head), None);
+    assert_eq!(arena.find(even, &8), Some(arena.tail(even)));
+    *arena.get_pair_mut(head)?.1 += 1;
+    assert_eq!(arena.get(head)?, (&1, &11));
+
+    // the slot of the removed item is reused
+    let slots = arena.total_len();
+    let index = arena.ordered_insert(even, 100, 0)?;
+    assert_eq!(index, four);
+    assert_eq!(arena.total_len(), slots + 1);
+    Ok(())
+}
+
+#[test]
+/// test ListArena inserts and moves with position hints against one BTreeMap per list
+fn linked_list_arena_02() -> Result<(), MapError> {
+    let mut arena = ListArena::<i32, i32>::default();
+    let lists = (0..4).map(|_| arena.add_list()).collect::<Vec<_>>();
+    let mut reference = vec![std::collections::BTreeMap::new(); lists.len()];
+    let mut indices = std::collections::HashMap::new();
+    for i in 0..200 {
+        let key = (i as i32 * 37) % 61;
+        // list 0 stays empty until the end, the other lists are filled out of order
+        let l = 3 - (i % 3);
+        let hint = arena
+            .lower_bound(lists[l], &(key - 2))
+            .unwrap_or(arena.tail(lists[l]));
+        let index = arena.ordered_insert_pos(lists[l], key, i as i32, hint)?;
+        if let std::collections::btree_map::Entry::Vacant(e) = reference[l].entry(key) {
+            let _ = e.insert(i as i32);
+            let _ = indices.insert(index, (l, key));
+        }
+    }
+    for (n, (index, (from, key))) in indices.clone().into_iter().enumerate() {
+        let to = (from + n) % lists.len();
+        let hint = if n % 2 == 0 {
+            arena.head(lists[to])
+        } else {
+            arena.tail(lists[to])
+        };
+        let moved = arena.move_to_pos(index, lists[to], hint)?;
+        assert_eq!(moved, from == to || !reference[to].contains_key(&key));
+        if moved && from != to {
+            let value = reference[from].remove(&key).unwrap();
+            let _ = reference[to].insert(key, value);
+            let _ = indices.insert(index, (to, key));
+        }
+    }
+    for (l, list) in lists.iter().enumerate() {
+        assert_eq!(
+            arena.iter(*list).map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            reference[l]
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(arena.len(*list), reference[l].len());
+        for key in [-1, 0, 30, 60, 61] {
+            assert_eq!(
+                arena.lower_bound_pos(*list, &key, arena.tail(*list)),
+                arena.lower_bound(*list, &key)
+            );
+        }
+    }
+    for (index, (l, key)) in indices {
+        assert_eq!(arena.list_of(index), Some(lists[l]));
+        assert_eq!(arena.get(index)?.0, &key);
+        assert_eq!(
+            arena.find_pos(lists[l], &key, arena.tail(lists[l])),
+            Some(index)
+        );
+    }
+    Ok(())
+}
+
+#[test]
+/// test split_off() at, before, and after the keys of the list
+fn linked_list_split_off_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32, &str>::default();
+    for i in 0..10 {
+        let _ = ll.ordered_insert(i * 2, i)?;
+    }
+    let index = ll.find(&14).unwrap();
+    let _ = ll.set_meta(index, "fourteen")?;
+
+    let mut tail = ll.split_off(&13)?;
+    assert_eq!(
+        ll.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![0, 2, 4, 6, 8, 10, 12]
+    );
+    assert_eq!(
+        tail.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![14, 16, 18]
+    );
+    assert_eq!(ll.peek_back_k(), Some(&12));
+    assert_eq!(tail.slot_count(), 3);
+    assert_eq!(tail.get_meta(tail.head()), Some(&"fourteen"));
+    // the slots of the moved items are reused
+    assert_eq!(ll.slot_count(), 10);
+    let _ = ll.ordered_insert(13, 0)?;
+    assert_eq!(ll.slot_count(), 10);
+
+    let rest = tail.split_off(&100)?;
+    assert!(rest.is_empty());
+    assert_eq!(tail.len(), 3);
+    let all = tail.split_off(&0)?;
+    assert!(tail.is_empty());
+    assert_eq!(all.len(), 3);
+    let _ = tail.ordered_insert(1, 1)?;
+    assert_eq!(tail.iter().collect::<Vec<_>>(), vec![(&1, &1)]);
+    Ok(())
+}
+
+#[test]
+/// test split_off_before() at the head, in the middle and at the tail
+fn linked_list_split_off_before_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let indices = (0..6)
+        .map(|i| ll.ordered_insert(i, i))
+        .collect::<Result<Vec<_>, _>>()?;
+    let last = ll.split_off_before(indices[5])?;
+    assert_eq!(last.iter().collect::<Vec<_>>(), vec![(&5, &5)]);
+    assert_eq!(ll.tail(), indices[4]);
+    let middle = ll.split_off_before(indices[2])?;
+    assert_eq!(
+        middle.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![2, 3, 4]
+    );
+    let all = ll.split_off_before(indices[0])?;
+    assert_eq!(all.len(), 2);
+    assert!(ll.is_empty());
+    assert!(ll.split_off_before(indices[0]).is_err());
+    assert!(ll.split_off_before(crate::OUT_OF_BOUNDS).is_err());
+    Ok(())
+}
+
+#[test]
+/// test that strict hints reject hints that are not next to the key
+fn linked_list_strict_hints_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    assert!(!ll.has_strict_hints());
+    let mut hint = ll.ordered_insert(0, 0)?;
+    // a far away hint works in the default mode
+    let _ = ll.ordered_insert_pos(100, 0, crate::OUT_OF_BOUNDS)?;
+    ll.set_strict_hints(true);
+    for i in 1..10 {
+        hint = ll.ordered_insert_pos(i * 10, i, hint)?;
+    }
+    // an existing key next to the hint is a NOP
+    assert_eq!(ll.ordered_insert_pos(90, 0, hint)?, hint);
+    assert_eq!(ll.ordered_insert_pos(95, 0, hint)?, ll.find(&95).unwrap());
+    let head = ll.head();
+    for (key, position) in [(5, hint), (95, head), (50, crate::OUT_OF_BOUNDS)] {
+        let err = ll.ordered_insert_pos(key, 0, position).unwrap_err();
+        assert_eq!(err.kind(), MapErrorKind::HintMismatch);
+    }
+    assert_eq!(ll.len(), 12);
+    // ordered_insert() has no hint to check
+    let _ = ll.ordered_insert(55, 0)?;
+    ll.set_strict_hints(false);
+    let _ = ll.ordered_insert_pos(5, 0, hint)?;
+    assert_eq!(ll.len(), 14);
+    Ok(())
+}
+
+#[test]
+/// test that every public hinted insert rejects a bad hint in strict mode
+fn linked_list_strict_hints_02() -> Result<(), MapError> {
+    let ll = Rc::from(RefCell::from(LinkedList::<i32, i32>::default()));
+    for i in 0..10 {
+        let _ = ll.borrow_mut().ordered_insert(i * 10, i)?;
+    }
+    ll.borrow_mut().set_strict_hints(true);
+    let head = ll.borrow().head();
+    let p = PIterator::new(Rc::clone(&ll))?;
+    let mismatch = |r: Result<(), MapError>| {
+        assert_eq!(r.unwrap_err().kind(), MapErrorKind::HintMismatch);
+    };
+    {
+        let mut list = ll.borrow_mut();
+        mismatch(list.ordered_insert_pos(55, 0, head).map(|_| ()));
+        mismatch(list.ordered_insert_pos_result(55, 0, head).map(|_| ()));
+        mismatch(list.ordered_insert_or_assign_pos(55, 0, head).map(|_| ()));
+        mismatch(
+            list.ordered_insert_merge_pos(55, 0, head, |v, n| *v += n)
+                .map(|_| ()),
+        );
+        mismatch(list.ordered_insert_entry_pos(55, 0, head).map(|_| ()));
+        let node = NodeHandle {
+            key: 55,
+            value: 0,
+            meta: (),
+        };
+        mismatch(list.insert_node(node, head).map(|_| ()));
+        mismatch(list.ordered_insert_hint(55, 0, &p).map(|_| ()));
+        // an existing key is no excuse for a bad hint
+        mismatch(list.ordered_insert_or_assign_pos(90, 0, head).map(|_| ()));
+        assert_eq!(list.len(), 10);
+        assert_eq!(list.get(list.find(&90).unwrap())?, (&90, &9));
+    }
+    // the unhinted inserts of the wrappers and the deferred inserts still work
+    let mut deferred = p.defer_inserts();
+    deferred.ordered_insert(75, 0);
+    deferred.ordered_insert(5, 0);
+    assert_eq!(deferred.flush()?, 2);
+    let mut list = ll.borrow_mut();
+    let err = list
+        .transaction(|editor| {
+            let _ = editor.ordered_insert(65, 0)?;
+            editor.ordered_insert_pos(45, 0, head)
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), MapErrorKind::HintMismatch);
+    let _ = list.transaction(|editor| editor.ordered_insert(65, 0))?;
+    assert_eq!(list.len(), 13);
+    assert!(list.find(&45).is_none());
+    Ok(())
+}
+
+#[test]
+/// test that ExternalValues keeps the values in the caller's store
+fn linked_list_external_values_01() -> Result<(), MapError> {
+    let mut values = Vec::<Vec<u8>>::new();
+    let mut keys = ExternalValues::<i32>::new();
+    let mut hint = 0;
+    for i in (0..5).rev() {
+        hint = keys.ordered_insert_pos(i, vec![i as u8; 3], &mut values, hint)?;
+    }
+    assert_eq!(values.len(), 5);
+    // a NOP insert doesn't touch the store
+    assert_eq!(keys.ordered_insert(2, vec![], &mut values)?, 2);
+    assert_eq!(values[2], vec![2; 3]);
+    keys.get_v_mut(2, &mut values)?.push(9);
+    assert_eq!(keys.get(2, &values)?, (&2, &vec![2, 2, 2, 9]));
+    assert_eq!(keys.find(&4), Some(0));
+
+    assert_eq!(keys.remove(0)?, 4);
+    assert!(keys.get(0, &values).is_err());
+    assert_eq!(keys.ordered_insert(10, vec![10], &mut values)?, 0);
+    assert_eq!(values.len(), 5);
+    assert_eq!(
+        keys.iter(&values)
+            .map(|(k, v)| (*k, v[0]))
+            .collect::<Vec<_>>(),
+        vec![(0, 0), (1, 1), (2, 2), (3, 3), (10, 10)]
+    );
+    // a store that is too short
+    let mut short = Vec::<Vec<u8>>::new();
+    assert!(keys.ordered_insert(11, vec![], &mut short).is_err());
+    assert!(keys.get(1, &short).is_err());
+    assert_eq!(keys.len(), 5);
+    Ok(())
+}
+
+#[test]
+/// test Extend<(K, V)>, the previous insert is the hint of the next
+fn linked_list_extend_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    ll.extend((0..100).map(|i| (i, i)));
+    assert_eq!(ll.len(), 100);
+    ll.enable_compare_stats();
+    // a sorted stream needs a single compare per item
+    ll.extend((100..200).map(|i| (i, i)));
+    assert_eq!(ll.compare_stats().unwrap().insert.max_comparisons, 1);
+    ll.extend(vec![(-1, 0), (50, -50), (250, 0)]);
+    assert_eq!(ll.len(), 202);
+    assert_eq!(ll.get_v(ll.find(&50).unwrap())?, &50);
+    assert_eq!(ll.peek_front_k(), Some(&-1));
+    assert_eq!(ll.peek_back_k(), Some(&250));
+
+    // the items that don't fit are dropped
+    let mut ll = LinkedList::<i32, i32>::with_max_capacity(3);
+    ll.extend((0..5).map(|i| (i, i)));
+    assert_eq!(
+        ll.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    Ok(())
+}
+
+#[test]
+/// test FromIterator<(K, V)> with sorted and unsorted input
+fn linked_list_from_iter_01() -> Result<(), MapError> {
+    let ll: LinkedList<i32, i32> = (0..100).map(|i| (i, i)).collect();
+    assert_eq!(ll.len(), 100);
+    // sorted input gets the slots in key order
+    for i in 0..100 {
+        assert_eq!(ll.get_k(i as usize)?, &i);
+    }
+
+    let ll: LinkedList<i32, i32> = (0..100).rev().chain(50..150).map(|i| (i % 77, i)).collect();
+    assert_eq!(ll.len(), 77);
+    assert_eq!(
+        ll.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        (0..77).collect::<Vec<_>>()
+    );
+    // the first value of a repeated key is kept
+    assert_eq!(ll.get_v(ll.find(&10).unwrap())?, &87);
+    Ok(())
+}
+
+#[test]
+/// test iter_joined() over two lists with different value types
+fn linked_list_iter_joined_01() -> Result<(), MapError> {
+    let left: LinkedList<i32, i32> = (0..10).map(|i| (i * 2, i)).collect();
+    let right: LinkedList<i32, char> = (0..10).map(|i| (i * 3, 'r')).collect();
+    let joined = left.iter_joined(&right).collect::<Vec<_>>();
+    assert_eq!(joined.len(), 10 + 10 - 4);
+    assert!(joined.windows(2).all(|w| w[0].0 < w[1].0));
+    for (k, l, r) in joined.iter().copied() {
+        assert_eq!(l.is_some(), *k % 2 == 0 && *k < 20);
+        assert_eq!(r.is_some(), *k % 3 == 0 && *k < 30);
+        assert_eq!(l.copied(), l.map(|_| *k / 2));
+    }
+    assert_eq!(joined.last(), Some(&(&27, None, Some(&'r'))));
+
+    let empty = LinkedList::<i32, ()>::default();
+    assert_eq!(left.iter_joined(&empty).count(), 10);
+    assert_eq!(empty.iter_joined(&right).count(), 10);
+    assert_eq!(empty.iter_joined(&empty).next(), None);
+    Ok(())
+}
+
+/// A key where values at most 1 apart are Equal, which is not transitive
+#[derive(Debug, Clone, Copy, Eq)]
+struct Near(i32);
+
+impl Ord for Near {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        if (self.0 - other.0).abs() <= 1 {
+            std::cmp::Ordering::Equal
+        } else {
+            self.0.cmp(&other.0)
+        }
+    }
+}
+
+impl PartialOrd for Near {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Near {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+#[test]
+/// test the equal policies with a non transitive key
+fn linked_list_equal_policy_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<Near, ()>::default();
+    let zero = ll.ordered_insert(Near(0), ())?;
+    let two = ll.ordered_insert(Near(2), ())?;
+    let four = ll.ordered_insert(Near(4), ())?;
+    assert_eq!(ll.equal_policy(), EqualPolicy::StopAtFirst);
+    // (policy, insert from zero, insert from two, lower_bound, lower_bound_pos from four)
+    for (policy, expected) in [
+        (EqualPolicy::StopAtFirst, [zero, two, zero, zero]),
+        (EqualPolicy::Leftmost, [zero, zero, zero, zero]),
+        (EqualPolicy::Rightmost, [two, two, two, two]),
+    ] {
+        ll.set_equal_policy(policy);
+        assert_eq!(ll.ordered_insert_pos(Near(1), (), zero)?, expected[0]);
+        assert_eq!(ll.ordered_insert_pos(Near(1), (), two)?, expected[1]);
+        assert_eq!(ll.lower_bound(&Near(1))?, Some(expected[2]));
+        assert_eq!(ll.lower_bound_pos(&Near(1), four)?, Some(expected[3]));
+    }
+    ll.set_search_strategy(SearchStrategy::Galloping);
+    assert_eq!(ll.lower_bound_pos(&Near(3), zero)?, Some(four));
+    ll.set_equal_policy(EqualPolicy::Leftmost);
+    assert_eq!(ll.lower_bound_pos(&Near(3), zero)?, Some(two));
+    assert_eq!(ll.len(), 3);
+    Ok(())
+}
+
+#[test]
+/// test the owning iterator from both ends
+fn linked_list_into_iter_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, String>::default();
+    for i in (0..100).rev() {
+        let _ = ll.ordered_insert(i, i.to_string())?;
+    }
+    for i in 0..50 {
+        let _ = ll.remove_by_key(&(i * 2))?;
+    }
+    let mut borrowed = Vec::new();
+    for (k, v) in &ll {
+        borrowed.push((*k, v.clone()));
+    }
+    assert_eq!(
+        borrowed,
+        ll.iter().map(|(k, v)| (*k, v.clone())).collect::<Vec<_>>()
+    );
+    let mut iter = ll.into_iter();
+    assert_eq!(iter.len(), 50);
+    assert_eq!(iter.next(), Some((1, "1".to_string())));
+    assert_eq!(iter.next_back(), Some((99, "99".to_string())));
+    let rest = iter.by_ref().take(47).collect::<Vec<_>>();
+    assert_eq!(rest.first().map(|(k, _)| *k), Some(3));
+    assert_eq!(rest.last().map(|(k, v)| (*k, v.as_str())), Some((95, "95")));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next_back(), Some((97, "97".to_string())));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(LinkedList::<i32, i32>::default().into_iter().count(), 0);
+    Ok(())
+}
+
+#[test]
+/// test that IndexRemap updates held indices after compact()
+fn linked_list_index_remap_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    let mut held = Vec::new();
+    for i in (0..10).rev() {
+        held.push(ll.ordered_insert(i, i)?);
+    }
+    for i in 0..5 {
+        let _ = ll.remove_by_key(&(i * 2))?;
+    }
+    let mut held_vec = held.clone();
+    let remap = ll.compact()?;
+    assert!(!remap.is_identity());
+    assert_eq!(remap.get(1000), crate::OUT_OF_BOUNDS);
+    remap.apply_to(&mut held);
+    remap.apply_to_vec(&mut held_vec);
+    assert_eq!(held_vec.len(), 5);
+    for (n, index) in held.iter().enumerate() {
+        let key = 9 - n as i32;
+        assert_eq!(
+            ll.get_k_opt(*index),
+            if key % 2 == 1 { Some(&key) } else { None }
+        );
+    }
+    // the list is already compact
+    assert!(ll.compact()?.is_identity());
+    assert_eq!(IndexRemap::from(vec![1, 0]).into_inner(), vec![1, 0]);
+    Ok(())
+}
+
+static SLOW_KEY_COMPARES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// A key that counts its comparisons
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SlowKey(u32, u32);
+
+impl Ord for SlowKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let _ = SLOW_KEY_COMPARES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        (self.0, self.1).cmp(&(other.0, other.1))
+    }
+}
+
+impl PartialOrd for SlowKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[test]
+/// test that Fingerprinted only compares keys with equal fingerprints
+fn linked_list_fingerprinted_01() -> Result<(), MapError> {
+    let mut ll = Fingerprinted::new(LinkedList::<SlowKey, u32>::default(), |k| k.0 as f64);
+    let compares = || SLOW_KEY_COMPARES.load(std::sync::atomic::Ordering::Relaxed);
+    let before = compares();
+    for i in 0..50 {
+        let _ = ll.ordered_insert(SlowKey(i % 10, i), i)?;
+    }
+    // only the keys with the same first field were compared
+    assert!(compares() - before <= 50 * 5, "{}", compares() - before);
+    let keys = ll
+        .list()
+        .iter()
+        .map(|(k, _)| (k.0, k.1))
+        .collect::<Vec<_>>();
+    let mut expected = keys.clone();
+    expected.sort();
+    assert_eq!(keys, expected);
+    assert_eq!(ll.list().len(), 50);
+
+    let index = ll.find(&SlowKey(3, 13)).unwrap();
+    assert_eq!(ll.fingerprint(index), Some(3.0));
+    assert_eq!(ll.ordered_insert(SlowKey(3, 13), 0)?, index);
+    assert_eq!(ll.find(&SlowKey(3, 14)), None);
+    let lb = ll
+        .lower_bound_pos(&SlowKey(3, 14), ll.list().head())
+        .unwrap();
+    assert_eq!(ll.list().get_k(lb)?, &SlowKey(3, 23));
+    ll.replace_key(index, SlowKey(3, 14))?;
+    assert_eq!(ll.find(&SlowKey(3, 14)), Some(index));
+    *ll.get_v_mut(index)? += 100;
+    assert_eq!(ll.remove(index)?, (SlowKey(3, 14), 113));
+    assert_eq!(ll.pop_front()?, Some((SlowKey(0, 0), 0)));
+    assert_eq!(ll.pop_back()?, Some((SlowKey(9, 49), 49)));
+    assert_eq!(ll.lower_bound(&SlowKey(100, 0)), None);
+    assert_eq!(ll.into_inner().len(), 47);
+    Ok(())
+}
+
+#[test]
+/// test keys() and values()
+fn linked_list_keys_values_01() -> Result<(), MapError> {
+    let ll: LinkedList<i32, String> = (0..5).rev().map(|i| (i, i.to_string())).collect();
+    assert_eq!(ll.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(ll.values().cloned().collect::<String>(), "01234");
+    assert_eq!(ll.keys().zip(ll.values()).count(), ll.len());
+    assert_eq!(LinkedList::<i32, i32>::default().values().next(), None);
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+/// test that the metrics are emitted through the metrics facade
+fn linked_list_metrics_01() -> Result<(), MapError> {
+    use metrics::{
+        Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Sum(AtomicU64);
+    impl CounterFn for Sum {
+        fn increment(&self, value: u64) {
+            let _ = self.0.fetch_add(value, Ordering::Relaxed);
+        }
+        fn absolute(&self, value: u64) {
+            self.0.store(value, Ordering::Relaxed);
+        }
+    }
+    impl HistogramFn for Sum {
+        fn record(&self, value: f64) {
+            self.increment(value as u64);
+        }
+    }
+
+    #[derive(Default)]
+    struct TestRecorder(Mutex<Vec<(String, Arc<Sum>)>>);
+    impl TestRecorder {
+        fn sum_(&self, key: &Key) -> Arc<Sum> {
+            let mut sums = self.0.lock().unwrap();
+            let name = format!("{}{:?}", key.name(), key.labels().collect::<Vec<_>>());
+            if let Some((_, sum)) = sums.iter().find(|(n, _)| *n == name) {
+                return Arc::clone(sum);
+            }
+            let sum = Arc::new(Sum::default());
+            sums.push((name, Arc::clone(&sum)));
+            sum
+        }
+        fn get(&self, name: &str) -> u64 {
+            let sums = self.0.lock().unwrap();
+            sums.iter()
+                .filter(|(n, _)| n.starts_with(name))
+                .map(|(_, s)| s.0.load(Ordering::Relaxed))
+                .sum()
+        }
+    }
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.sum_(key))
+        }
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.sum_(key))
+        }
+    }
+
+    let recorder = TestRecorder::default();
+    metrics::with_local_recorder(&recorder, || -> Result<(), MapError> {
+        let ll = Rc::new(RefCell::new(LinkedList::<i32, i32>::default()));
+        for i in 0..10 {
+            let _ = ll.borrow_mut().ordered_insert(i, i)?;
+        }
+        let _ = ll.borrow_mut().pop_front()?;
+        let head = ll.borrow().head();
+        let _ = ll.borrow_mut().ordered_insert_pos(100, 0, head)?;
+        let _guard = ll.borrow_mut();
+        assert!(PIterator::new(Rc::clone(&ll)).is_err());
+        Ok(())
+    })?;
+    assert_eq!(recorder.get("cpp_map_inserts_total"), 11);
+    assert_eq!(recorder.get("cpp_map_removals_total"), 1);
+    assert_eq!(recorder.get("cpp_map_borrow_failures_total"), 1);
+    assert!(recorder.get("cpp_map_scan_length") >= 9);
+    Ok(())
+}
+
+#[test]
+/// test iter_mut() and values_mut()
+fn linked_list_iter_mut_01() -> Result<(), MapError> {
+    let mut ll = LinkedList::<i32, i32>::default();
+    for i in (0..6).rev() {
+        let _ = ll.ordered_insert(i, 0)?;
+    }
+    // free slots are skipped
+    let _ = ll.remove_by_key(&2);
+    let mut iter = ll.iter_mut();
+    assert_eq!(iter.len(), 5);
+    while let (Some((front_k, front_v)), Some((back_k, back_v))) = (iter.next(), iter.next_back()) {
+        *front_v = *front_k * 10;
+        *back_v = *back_k * 100;
+    }
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+    assert_eq!(
+        ll.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        vec![(0, 0), (1, 10), (3, 0), (4, 400), (5, 500)]
+    );
+    for v in ll.values_mut() {
+        *v += 1;
+    }
+    for (_, v) in &mut ll {
+        *v *= 2;
+    }
+    assert_eq!(
+        ll.values().copied().collect::<Vec<_>>(),
+        vec![2, 22, 2, 802, 1002]
+    );
+    // tombstones are skipped
+    ll.set_deferred_slot_reuse(true)?;
+    let _ = ll.remove_by_key(&4);
+    assert_eq!(
+        ll.iter_mut().rev().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![5, 3, 1, 0]
+    );
+    assert_eq!(LinkedList::<i32, i32>::default().iter_mut().next(), None);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A simple line based text format: one `key<TAB>value` line per item, in sorted order.
+
+use crate::{LinkedList, MapError};
+use std::fmt::{Debug, Display, Write};
+use std::str::FromStr;
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Returns the list as text, one `key<TAB>value` line per item in sorted order.
+    /// The Display output of the keys must not contain tabs or newlines, and the values must not
+    /// contain newlines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.ordered_insert(2, 20);
+    /// let _ = ll.ordered_insert(1, 10);
+    /// let text = ll.to_text();
+    /// assert_eq!(text, "1\t10\n2\t20\n");
+    /// let ll2 = LinkedList::<i8, i8>::from_text(&text).unwrap();
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), ll2.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn to_text(&self) -> String
+    where
+        K: Display,
+        V: Display,
+    {
+        let mut text = String::new();
+        for (k, v) in self.iter() {
+            // writing to a String never fails
+            let _ = writeln!(text, "{}\t{}", k, v);
+        }
+        text
+    }
+
+    /// Parses the text produced by [`LinkedList::to_text()`].
+    /// Empty lines are ignored. The lines do not have to be sorted, but (just like
+    /// ordered_insert()) only the first occurrence of a key will be used.
+    pub fn from_text(text: &str) -> Result<Self, MapError>
+    where
+        K: FromStr,
+        V: FromStr,
+    {
+        let mut list = Self::default();
+        for (line_number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut split = line.splitn(2, '\t');
+            let (key, value) = match (split.next(), split.next()) {
+                (Some(key), Some(value)) => (key, value),
+                _ => {
+                    return Err(map_error!(
+                        ParseError,
+                        line_number + 1,
+                        "expected key<TAB>value"
+                    ))
+                }
+            };
+            let key = key.parse::<K>().map_err(|_| {
+                map_error!(ParseError, line_number + 1, "could not parse key '{}'", key)
+            })?;
+            let value = value.parse::<V>().map_err(|_| {
+                map_error!(
+                    ParseError,
+                    line_number + 1,
+                    "could not parse value '{}'",
+                    value
+                )
+            })?;
+            let _ = list.ordered_insert_pos(key, value, list.tail_)?;
+        }
+        Ok(list)
+    }
+}
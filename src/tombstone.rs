@@ -0,0 +1,124 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Deferred slot reuse: the slots of removed items are kept as tombstones until they are
+//! purged, so that they are not recycled while PIterators may still point at them.
+//! The removed items themselves are unlinked at once, there is no lazy deletion.
+
+use crate::{LinkedList, MapError, OUT_OF_BOUNDS};
+use std::fmt::Debug;
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    /// Enables or disables deferred slot reuse.
+    ///
+    /// A removed item is unlinked from the list as usual, so iteration and searches never see
+    /// it, but while slot reuse is deferred its slot is not returned to the free pool. Instead the slot is kept as a
+    /// tombstone that remembers the old neighbours of the item. A [`crate::PIterator`] positioned
+    /// at a tombstone reports `is_ok() == false`, but `next()` and `prev()` still move it to the
+    /// live item that followed (or preceded) the removed item. No insert will reuse the slot until
+    /// [`LinkedList::purge_tombstones()`] is called, the tombstones are never purged
+    /// automatically.
+    ///
+    /// Disabling deferred slot reuse purges all the tombstones. Returns a Frozen error, and
+    /// leaves the setting as it is, if there is a live [`FreezeGuard`](crate::FreezeGuard) for
+    /// this list and there are tombstones to purge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// ll.set_deferred_slot_reuse(true).unwrap();
+    /// let one = ll.ordered_insert(1, 1).unwrap();
+    /// let _ = ll.ordered_insert(2, 2).unwrap();
+    /// let _ = ll.pop_front();
+    /// assert_eq!(ll.len(), 1);
+    /// assert_eq!(ll.tombstone_count(), 1);
+    /// assert_ne!(ll.ordered_insert(0, 0).unwrap(), one);
+    /// assert_eq!(ll.purge_tombstones().unwrap(), 1);
+    /// assert_eq!(ll.free_slots(), &[one]);
+    /// ```
+    pub fn set_deferred_slot_reuse(&mut self, enabled: bool) -> Result<(), MapError> {
+        if !enabled && !self.tombstones_.is_empty() {
+            let _ = self.purge_tombstones()?;
+        }
+        self.defer_slot_reuse_ = enabled;
+        Ok(())
+    }
+
+    #[inline(always)]
+    /// Returns true if slot reuse is deferred
+    pub fn has_deferred_slot_reuse(&self) -> bool {
+        self.defer_slot_reuse_
+    }
+
+    #[inline(always)]
+    /// Returns the number of removed slots that are waiting to be purged
+    pub fn tombstone_count(&self) -> usize {
+        self.tombstones_.len()
+    }
+
+    #[inline(always)]
+    /// Returns true if the slot at index is a tombstone
+    pub fn is_tombstone(&self, index: usize) -> bool {
+        self.tombstones_.contains_key(&index)
+    }
+
+    /// Releases all the tombstones to the free pool, returns the number of released slots.
+    /// Returns a Frozen error if there is a live [`FreezeGuard`](crate::FreezeGuard) for this
+    /// list.
+    /// Warning: any PIterator positioned at a tombstone will be invalid.
+    pub fn purge_tombstones(&mut self) -> Result<usize, MapError> {
+        self.check_not_frozen_()?;
+        let count = self.tombstones_.len();
+        for index in std::mem::take(&mut self.tombstones_).into_keys().rev() {
+            self.free_slot_(index);
+        }
+        Ok(count)
+    }
+
+    /// Returns the old (prev, next) neighbours of the tombstone at index
+    pub(crate) fn tombstone_(&self, index: usize) -> Option<(usize, usize)> {
+        self.tombstones_.get(&index).copied()
+    }
+
+    /// Follows the links of the tombstone at index (and of any tombstone it leads to) in the
+    /// given direction, returns the first live index or OUT_OF_BOUNDS.
+    pub(crate) fn step_from_tombstone_(&self, mut index: usize, forward: bool) -> usize {
+        while let Some((prev, next)) = self.tombstone_(index) {
+            index = if forward { next } else { prev };
+        }
+        if let Some(Some(_)) = self.nodes_.get(index) {
+            index
+        } else {
+            OUT_OF_BOUNDS
+        }
+    }
+
+    /// Frees the slot at index, or turns it into a tombstone if slot reuse is deferred.
+    pub(crate) fn release_slot_(&mut self, index: usize, prev: usize, next: usize) {
+        if self.defer_slot_reuse_ {
+            let _ = self.tombstones_.insert(index, (prev, next));
+        } else {
+            self.free_slot_(index);
+        }
+    }
+
+    /// Moves the tombstone at index to the free pool, returns false if there was no tombstone
+    pub(crate) fn forget_tombstone_(&mut self, index: usize) -> bool {
+        if self.tombstones_.remove(&index).is_some() {
+            self.free_slot_(index);
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Floating point key wrappers with a total order.
+//!
+//! f32 and f64 are only `PartialOrd`, so they can't be used directly as keys of a
+//! [`LinkedList`](crate::LinkedList). These newtypes order their content with `total_cmp()`,
+//! i.e. -NaN < -inf < ... < -0.0 < +0.0 < ... < +inf < +NaN
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::ParseFloatError;
+use std::str::FromStr;
+
+macro_rules! total_float {
+    ($name:ident, $float:ty) => {
+        #[doc = concat!("A `", stringify!($float), "` key ordered by `", stringify!($float), "::total_cmp()`.")]
+        ///
+        /// Note that equality follows the total order as well, so `-0.0 != 0.0` and `NaN == NaN`
+        /// (for NaNs with identical bit patterns).
+        #[derive(Clone, Copy, Default)]
+        pub struct $name(pub $float);
+
+        impl $name {
+            #[inline(always)]
+            /// Returns the wrapped float
+            pub fn get(self) -> $float {
+                self.0
+            }
+        }
+
+        impl Ord for $name {
+            #[inline(always)]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl PartialOrd for $name {
+            #[inline(always)]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl From<$float> for $name {
+            #[inline(always)]
+            fn from(value: $float) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $float {
+            #[inline(always)]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseFloatError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse::<$float>().map(Self)
+            }
+        }
+    };
+}
+
+total_float!(TotalF64, f64);
+total_float!(TotalF32, f32);
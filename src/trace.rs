@@ -0,0 +1,284 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Step-by-step trace of the mutations of a [`LinkedList`], e.g. for animating sweep-line
+//! algorithms in an external visualizer.
+
+use crate::{LinkedList, MapError};
+use std::fmt::{Debug, Display, Write};
+
+/// A recorded mutation. Keys are stored as their Display text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceOp {
+    /// A new item was inserted at index
+    Insert {
+        /// slot index of the new item
+        index: usize,
+        /// key of the new item
+        key: String,
+    },
+    /// The item at index was removed
+    Remove {
+        /// slot index of the removed item
+        index: usize,
+        /// key of the removed item
+        key: String,
+    },
+    /// The key at index was replaced
+    ReplaceKey {
+        /// slot index of the item
+        index: usize,
+        /// the replaced key
+        old_key: String,
+        /// the new key
+        key: String,
+    },
+    /// A user supplied label, e.g. the current sweep-line event. The list is not modified.
+    Mark(String),
+}
+
+/// One step of a [`Tracer`]: the mutation and the resulting order of the list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceFrame {
+    /// The mutation
+    pub op: TraceOp,
+    /// `(index, key)` of every item in sorted order, after the mutation
+    pub order: Vec<(usize, String)>,
+}
+
+/// A [`LinkedList`] that records every mutation together with a snapshot of the resulting
+/// order. The recorded frames can be exported as JSON with [`Tracer::to_json()`].
+///
+/// # Examples
+///
+/// ```
+/// # use cpp_map::{LinkedList, Tracer};
+/// let mut t = Tracer::new(LinkedList::<i8, ()>::default());
+/// let _ = t.ordered_insert(2, ());
+/// t.mark("event x=1");
+/// let _ = t.ordered_insert(1, ());
+/// let _ = t.pop_front();
+/// assert_eq!(t.frames().len(), 4);
+/// assert_eq!(
+///     t.to_json(),
+///     concat!(
+///         r#"{"frames":["#,
+///         r#"{"op":"insert","index":0,"key":"2","order":[[0,"2"]]},"#,
+///         r#"{"op":"mark","label":"event x=1","order":[[0,"2"]]},"#,
+///         r#"{"op":"insert","index":1,"key":"1","order":[[1,"1"],[0,"2"]]},"#,
+///         r#"{"op":"remove","index":1,"key":"1","order":[[0,"2"]]}"#,
+///         r#"]}"#
+///     )
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Tracer<K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: LinkedList<K, V>,
+    frames_: Vec<TraceFrame>,
+}
+
+impl<K, V> Tracer<K, V>
+where
+    K: Debug + Ord + PartialOrd + Display,
+    V: Debug,
+{
+    /// Wraps a list, nothing is recorded until the first mutation.
+    pub fn new(list: LinkedList<K, V>) -> Self {
+        Self {
+            list_: list,
+            frames_: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    /// Read only access to the list
+    pub fn list(&self) -> &LinkedList<K, V> {
+        &self.list_
+    }
+
+    #[inline(always)]
+    /// Drops the trace and returns the list
+    pub fn into_inner(self) -> LinkedList<K, V> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Returns the recorded frames, oldest first
+    pub fn frames(&self) -> &[TraceFrame] {
+        &self.frames_
+    }
+
+    /// Forget all recorded frames
+    pub fn clear_frames(&mut self) {
+        self.frames_.clear();
+    }
+
+    /// Records a label frame without modifying the list
+    pub fn mark(&mut self, label: &str) {
+        self.record_(TraceOp::Mark(label.to_string()));
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`]
+    pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        self.insert_(key, value, None)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`]. Nothing is recorded if the key already
+    /// existed.
+    pub fn ordered_insert_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        self.insert_(key, value, Some(position))
+    }
+
+    /// Inserts with an optional position hint, the insert is recorded unless it is a NOP
+    fn insert_(&mut self, key: K, value: V, position: Option<usize>) -> Result<usize, MapError> {
+        let len = self.list_.len();
+        let index = match position {
+            Some(position) => self.list_.ordered_insert_pos(key, value, position)?,
+            None => self.list_.ordered_insert(key, value)?,
+        };
+        if self.list_.len() != len {
+            let key = self.list_.get_k(index)?.to_string();
+            self.record_(TraceOp::Insert { index, key });
+        }
+        Ok(index)
+    }
+
+    /// Replace the key at index. This will destroy the internal order of element if you
+    /// replace an element with something out of order.
+    pub fn replace_key(&mut self, index: usize, key: K) -> Result<(), MapError> {
+        let new_key = key.to_string();
+        let old_key = self.list_.replace_key_(index, key)?.to_string();
+        self.record_(TraceOp::ReplaceKey {
+            index,
+            old_key,
+            key: new_key,
+        });
+        Ok(())
+    }
+
+    /// Remove the item at index and return it.
+    pub fn remove(&mut self, index: usize) -> Result<(K, V), MapError> {
+        let (_, (key, value), _) = self.list_.remove__(index)?;
+        self.record_(TraceOp::Remove {
+            index,
+            key: key.to_string(),
+        });
+        Ok((key, value))
+    }
+
+    /// Same as [`LinkedList::pop_front()`]
+    pub fn pop_front(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let head = self.list_.head_;
+        Ok(Some(self.remove(head)?))
+    }
+
+    /// Same as [`LinkedList::pop_back()`]
+    pub fn pop_back(&mut self) -> Result<Option<(K, V)>, MapError> {
+        if self.list_.is_empty() {
+            return Ok(None);
+        }
+        let tail = self.list_.tail_;
+        Ok(Some(self.remove(tail)?))
+    }
+
+    /// Returns the recorded frames as JSON:
+    /// `{"frames":[{"op":"insert","index":0,"key":"2","order":[[0,"2"]]}, ...]}`.
+    /// The `op` field is one of `insert`, `remove`, `replace_key` (with an extra `old_key`
+    /// field) or `mark` (with a `label` field instead of `index` and `key`).
+    pub fn to_json(&self) -> String {
+        let mut json = String::from(r#"{"frames":["#);
+        for (i, frame) in self.frames_.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            // writing to a String never fails
+            let _ = match &frame.op {
+                TraceOp::Insert { index, key } => write!(
+                    json,
+                    r#"{{"op":"insert","index":{},"key":{}"#,
+                    index,
+                    JsonStr(key)
+                ),
+                TraceOp::Remove { index, key } => write!(
+                    json,
+                    r#"{{"op":"remove","index":{},"key":{}"#,
+                    index,
+                    JsonStr(key)
+                ),
+                TraceOp::ReplaceKey {
+                    index,
+                    old_key,
+                    key,
+                } => write!(
+                    json,
+                    r#"{{"op":"replace_key","index":{},"old_key":{},"key":{}"#,
+                    index,
+                    JsonStr(old_key),
+                    JsonStr(key)
+                ),
+                TraceOp::Mark(label) => {
+                    write!(json, r#"{{"op":"mark","label":{}"#, JsonStr(label))
+                }
+            };
+            json.push_str(r#","order":["#);
+            for (j, (index, key)) in frame.order.iter().enumerate() {
+                if j > 0 {
+                    json.push(',');
+                }
+                let _ = write!(json, "[{},{}]", index, JsonStr(key));
+            }
+            json.push_str("]}");
+        }
+        json.push_str("]}");
+        json
+    }
+
+    /// Store a frame with a snapshot of the current order
+    fn record_(&mut self, op: TraceOp) {
+        let mut order = Vec::with_capacity(self.list_.len());
+        let mut index = self.list_.head_;
+        while let Some(Some(node)) = self.list_.nodes_.get(index) {
+            order.push((index, node.key_.to_string()));
+            index = node.next_;
+        }
+        self.frames_.push(TraceFrame { op, order });
+    }
+}
+
+/// Display adapter that writes a quoted and escaped JSON string
+struct JsonStr<'a>(&'a str);
+
+impl<'a> Display for JsonStr<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
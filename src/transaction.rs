@@ -0,0 +1,184 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Transactional editing of a [`LinkedList`].
+
+use crate::edit_log::{EditLog, EditOp};
+use crate::jump_table::JumpTable;
+use crate::{LinkedList, MapError};
+use std::fmt::Debug;
+
+/// Mutable access to a list inside [`LinkedList::transaction()`].
+/// Every successful operation is recorded, so that it can be rolled back if the transaction fails.
+pub struct Editor<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a mut LinkedList<K, V, M>,
+    log_: EditLog<K, V, M>,
+    // the jump table as it was before the transaction, restored by a rollback
+    jump_table_: Option<JumpTable>,
+}
+
+impl<'a, K, V, M> Editor<'a, K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    pub(crate) fn new(list: &'a mut LinkedList<K, V, M>) -> Self {
+        let jump_table = list.jump_table_.clone();
+        Self {
+            list_: list,
+            log_: Vec::new(),
+            jump_table_: jump_table,
+        }
+    }
+
+    #[inline(always)]
+    /// Read only access to the list being edited
+    pub fn list(&self) -> &LinkedList<K, V, M> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Same as [`LinkedList::ordered_insert()`]
+    pub fn ordered_insert(&mut self, key: K, value: V) -> Result<usize, MapError> {
+        self.insert_(key, value, None)
+    }
+
+    /// Same as [`LinkedList::ordered_insert_pos()`]
+    pub fn ordered_insert_pos(
+        &mut self,
+        key: K,
+        value: V,
+        position: usize,
+    ) -> Result<usize, MapError> {
+        self.insert_(key, value, Some(position))
+    }
+
+    /// Inserts with an optional position hint, the insert is recorded unless it is a NOP
+    fn insert_(&mut self, key: K, value: V, position: Option<usize>) -> Result<usize, MapError> {
+        let len = self.list_.len();
+        let next_slot = self.list_.nodes_.len();
+        let index = match position {
+            Some(position) => self.list_.ordered_insert_pos(key, value, position)?,
+            None => self.list_.ordered_insert(key, value)?,
+        };
+        if self.list_.len() != len {
+            // not a NOP
+            self.log_.push(EditOp::Insert {
+                index,
+                appended: index == next_slot,
+            });
+        }
+        Ok(index)
+    }
+
+    /// Replace the key at index. This will destroy the internal order of element if you
+    /// replace an element with something out of order.
+    pub fn replace_key(&mut self, index: usize, key: K) -> Result<(), MapError> {
+        let old_key = self.list_.replace_key_(index, key)?;
+        self.log_.push(EditOp::ReplaceKey {
+            index,
+            key: old_key,
+        });
+        Ok(())
+    }
+
+    /// Remove the item at index, returns the removed key and value. The item is moved into the
+    /// log together with its metadata and bookmarks, a rollback restores all of them.
+    pub fn remove(&mut self, index: usize) -> Result<(&K, &V), MapError> {
+        // take_meta() fails on an invalid index, before anything is changed
+        let meta = self.list_.take_meta(index)?;
+        let bookmarks = self.list_.bookmarks_at_(index);
+        let (prev, (key, value), next) = match self.list_.remove__(index) {
+            Ok(rv) => rv,
+            Err(err) => {
+                let _ = self.list_.set_meta(index, meta);
+                return Err(err);
+            }
+        };
+        self.log_.push(EditOp::Remove {
+            index,
+            prev,
+            next,
+            key,
+            value,
+            meta,
+            bookmarks,
+        });
+        match self.log_.last() {
+            Some(EditOp::Remove { key, value, .. }) => Ok((key, value)),
+            _ => Err(map_error!(
+                InternalError,
+                "The removal of index {} was not logged. {}:{}",
+                index,
+                file!(),
+                line!()
+            )),
+        }
+    }
+
+    /// Revert all the recorded operations, newest first, and restore the jump table.
+    pub(crate) fn rollback(&mut self) -> Result<(), MapError> {
+        while let Some(op) = self.log_.pop() {
+            let _ = self.list_.revert_(op)?;
+        }
+        self.list_.jump_table_ = self.jump_table_.take();
+        Ok(())
+    }
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    /// Run a batch of edits as one transaction.
+    /// The edits are applied as they are made, and if the closure returns an error (or any
+    /// operation fails) every edit is rolled back. The list, including all slot indices, is then
+    /// restored to the state it had before the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::{LinkedList, MapError, MapErrorKind};
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// let _ = ll.ordered_insert(1, 1);
+    /// let rv: Result<(), MapError> = ll.transaction(|editor| {
+    ///     let _ = editor.ordered_insert(2, 2)?;
+    ///     let _ = editor.remove(editor.list().head())?;
+    ///     Err(MapErrorKind::InternalError.into())
+    /// });
+    /// assert!(rv.is_err());
+    /// assert_eq!(ll.iter().collect::<Vec<_>>(), vec![(&1, &1)]);
+    /// ```
+    pub fn transaction<R, F>(&mut self, f: F) -> Result<R, MapError>
+    where
+        F: FnOnce(&mut Editor<'_, K, V, M>) -> Result<R, MapError>,
+    {
+        Ok(self.transaction_(f)?.0)
+    }
+
+    /// Run a transaction, returns the closure result and the log of the committed operations.
+    pub(crate) fn transaction_<R, F>(&mut self, f: F) -> Result<(R, EditLog<K, V, M>), MapError>
+    where
+        F: FnOnce(&mut Editor<'_, K, V, M>) -> Result<R, MapError>,
+    {
+        let mut editor = Editor::new(self);
+        match f(&mut editor) {
+            Ok(rv) => Ok((rv, editor.log_)),
+            Err(err) => {
+                editor.rollback()?;
+                Err(err)
+            }
+        }
+    }
+}
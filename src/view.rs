@@ -0,0 +1,238 @@
+//           Copyright 2021 Eadf (github.com/eadf)
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A borrowed view of the items of a [`LinkedList`] within a key range.
+
+use crate::{LinkedList, OUT_OF_BOUNDS};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
+
+/// A read only window of a [`LinkedList`], created by [`LinkedList::view()`].
+/// The window is resolved when the view is created, so the view itself holds no keys.
+#[derive(Clone, Copy, Debug)]
+pub struct View<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a LinkedList<K, V, M>,
+    head_: usize,
+    tail_: usize,
+}
+
+impl<K, V, M> LinkedList<K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+{
+    /// Returns a view of the items with keys inside `range`.
+    /// Finding the window is a sequential search from the head.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cpp_map::LinkedList;
+    /// let mut ll = LinkedList::<i8, i8>::default();
+    /// for i in 0..10 {
+    ///     let _ = ll.ordered_insert_pos(i, i * 10, ll.tail());
+    /// }
+    /// let view = ll.view(3..6);
+    /// assert_eq!(view.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![3, 4, 5]);
+    /// assert_eq!(view.get_k(view.lower_bound(&0).unwrap()).unwrap(), &3);
+    /// assert!(view.lower_bound(&6).is_none());
+    /// assert!(view.find(&7).is_none());
+    /// assert_eq!(ll.get_v(view.find(&4).unwrap()).unwrap(), &40);
+    /// ```
+    pub fn view<R: RangeBounds<K>>(&self, range: R) -> View<'_, K, V, M> {
+        let mut head = OUT_OF_BOUNDS;
+        let mut tail = OUT_OF_BOUNDS;
+        let mut curr_index = self.head_;
+        while let Some(Some(node)) = self.nodes_.get(curr_index) {
+            let after_start = match range.start_bound() {
+                Bound::Included(start) => node.key_ >= *start,
+                Bound::Excluded(start) => node.key_ > *start,
+                Bound::Unbounded => true,
+            };
+            if after_start {
+                let before_end = match range.end_bound() {
+                    Bound::Included(end) => node.key_ <= *end,
+                    Bound::Excluded(end) => node.key_ < *end,
+                    Bound::Unbounded => true,
+                };
+                if !before_end {
+                    break;
+                }
+                if head == OUT_OF_BOUNDS {
+                    head = curr_index;
+                }
+                tail = curr_index;
+            }
+            curr_index = node.next_;
+        }
+        View {
+            list_: self,
+            head_: head,
+            tail_: tail,
+        }
+    }
+}
+
+impl<'a, K, V, M> View<'a, K, V, M>
+where
+    K: Debug + Ord + PartialOrd,
+    V: Debug,
+    M: Default,
+{
+    #[inline(always)]
+    /// Returns the whole parent list
+    pub fn list(&self) -> &'a LinkedList<K, V, M> {
+        self.list_
+    }
+
+    #[inline(always)]
+    /// Return the index of the first item in the view, OUT_OF_BOUNDS if empty
+    pub fn head(&self) -> usize {
+        self.head_
+    }
+
+    #[inline(always)]
+    /// Return the index of the last item in the view, OUT_OF_BOUNDS if empty
+    pub fn tail(&self) -> usize {
+        self.tail_
+    }
+
+    #[inline(always)]
+    /// Returns true if there are no items in the view
+    pub fn is_empty(&self) -> bool {
+        self.head_ == OUT_OF_BOUNDS
+    }
+
+    /// Returns the number of items in the view. Note that this is a sequential count.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns true if the item at index is inside the view.
+    /// The key of the item is compared with the keys at the ends of the view, nothing is walked.
+    pub fn contains_index(&self, index: usize) -> bool {
+        match (
+            self.list_.get_k_opt(index),
+            self.list_.get_k_opt(self.head_),
+            self.list_.get_k_opt(self.tail_),
+        ) {
+            (Some(key), Some(first), Some(last)) => first <= key && key <= last,
+            _ => false,
+        }
+    }
+
+    /// Returns the key at index, if the index is inside the view
+    pub fn get_k(&self, index: usize) -> Option<&'a K> {
+        if self.contains_index(index) {
+            self.list_.get_k_opt(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the key and value at index, if the index is inside the view
+    pub fn get(&self, index: usize) -> Option<(&'a K, &'a V)> {
+        if self.contains_index(index) {
+            self.list_.get_opt(index)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the items of the view in order
+    pub fn iter(&self) -> ViewIterator<'a, K, V, M> {
+        ViewIterator {
+            list_: self.list_,
+            my_next_: self.head_,
+            tail_: self.tail_,
+        }
+    }
+
+    /// Returns the index of the first item in the view whose key is not less than `key`.
+    /// Returns None if there is no such item in the view.
+    pub fn lower_bound<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.indices_().find(|i| {
+            self.list_
+                .get_k_opt(*i)
+                .is_some_and(|k| key.cmp(k.borrow()) != Ordering::Greater)
+        })
+    }
+
+    /// Returns the index of the item with `key`, if it is inside the view
+    pub fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        for i in self.indices_() {
+            match self.list_.get_k_opt(i).map(|k| key.cmp(k.borrow())) {
+                Some(Ordering::Equal) => return Some(i),
+                Some(Ordering::Less) => return None,
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// Iterates over the slot indices of the view in order
+    fn indices_(&self) -> impl Iterator<Item = usize> + 'a {
+        let list = self.list_;
+        let tail = self.tail_;
+        let mut curr_index = self.head_;
+        std::iter::from_fn(move || {
+            let node = list.nodes_.get(curr_index)?.as_ref()?;
+            let rv = curr_index;
+            curr_index = if rv == tail {
+                OUT_OF_BOUNDS
+            } else {
+                node.next_
+            };
+            Some(rv)
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Iterator over the items of a [`View`]
+pub struct ViewIterator<'a, K, V, M = ()>
+where
+    K: Debug,
+    V: Debug,
+{
+    list_: &'a LinkedList<K, V, M>,
+    my_next_: usize,
+    tail_: usize,
+}
+
+impl<'a, K, V, M> Iterator for ViewIterator<'a, K, V, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        let node = self.list_.nodes_.get(self.my_next_)?.as_ref()?;
+        self.my_next_ = if self.my_next_ == self.tail_ {
+            OUT_OF_BOUNDS
+        } else {
+            node.next_
+        };
+        Some((&node.key_, &node.value_))
+    }
+}